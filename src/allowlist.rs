@@ -0,0 +1,63 @@
+//! Merkle tree helpers for invite-only events.
+//!
+//! Leaves are `sha256(0x00 || wallet pubkey)` and interior nodes are
+//! `sha256(0x01 || min(left, right) || max(left, right))`, so a proof is just
+//! the list of sibling hashes from the leaf up to the root.
+
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Hash of the allowlist leaf for a wallet
+pub fn leaf(wallet: &Pubkey) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, wallet.as_ref()]).to_bytes()
+}
+
+/// Hash of an interior node from its two children
+pub fn node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    if left <= right {
+        hashv(&[NODE_PREFIX, left, right]).to_bytes()
+    } else {
+        hashv(&[NODE_PREFIX, right, left]).to_bytes()
+    }
+}
+
+/// Check that `wallet` is a leaf of the tree with the given root
+pub fn verify(root: &[u8; 32], wallet: &Pubkey, proof: &[[u8; 32]]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf(wallet), |hash, sibling| node(&hash, sibling));
+    computed == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_every_leaf_of_the_tree() {
+        let wallets: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = wallets.iter().map(leaf).collect();
+        let left = node(&leaves[0], &leaves[1]);
+        let right = node(&leaves[2], &leaves[3]);
+        let root = node(&left, &right);
+
+        assert!(verify(&root, &wallets[0], &[leaves[1], right]));
+        assert!(verify(&root, &wallets[1], &[leaves[0], right]));
+        assert!(verify(&root, &wallets[2], &[leaves[3], left]));
+        assert!(verify(&root, &wallets[3], &[leaves[2], left]));
+    }
+
+    #[test]
+    fn rejects_other_wallets_and_proofs() {
+        let wallets: Vec<Pubkey> = (0..2).map(|_| Pubkey::new_unique()).collect();
+        let root = node(&leaf(&wallets[0]), &leaf(&wallets[1]));
+
+        assert!(!verify(&root, &Pubkey::new_unique(), &[leaf(&wallets[1])]));
+        assert!(!verify(&root, &wallets[0], &[]));
+        assert!(!verify(&root, &wallets[0], &[leaf(&wallets[0])]));
+        // An interior node can't be passed off as a leaf
+        assert!(!verify(&root, &Pubkey::new_from_array(root), &[]));
+    }
+}
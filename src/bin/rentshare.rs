@@ -0,0 +1,1117 @@
+//! Command line client managing events of a deployed RentShare program.
+//!
+//! Build with `cargo build --features cli --bin rentshare`. Events and seats are
+//! created as seeded accounts of the keypair, the same way the README examples do.
+
+use std::{error::Error, str::FromStr};
+
+use borsh::BorshDeserialize;
+use clap::{Parser, Subcommand};
+use rentshare::{
+    checkin, decoder,
+    instruction::{Instruction as RentShareInstruction, TicketTier},
+    state::{
+        InitEvent, Participant, PayeeShare, PriceStep, RefundStep, SponsorPoolDestination,
+        Sponsorship, Vesting,
+    },
+};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signer},
+    system_instruction, system_program, sysvar,
+    transaction::Transaction,
+};
+
+type CliResult<T = ()> = Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[clap(name = "rentshare", version, about = "Manage RentShare events")]
+struct Cli {
+    /// JSON RPC URL of the cluster, e.g. a local `solana-test-validator`
+    #[clap(long, short = 'u', default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Keypair paying for and signing transactions
+    #[clap(long, short = 'k', default_value = "~/.config/solana/id.json")]
+    keypair: String,
+
+    /// Address of the deployed RentShare program
+    #[clap(long, env = "RENTSHARE_PROGRAM_ID")]
+    program_id: Pubkey,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)] // parsed once per run
+enum Command {
+    /// Create and initialize an event owned by the keypair
+    Create {
+        /// Event identifier, also used in the seed of the event account
+        #[clap(long)]
+        event_id: u64,
+
+        /// Account receiving the deposits once the event ends
+        #[clap(long)]
+        payee: Pubkey,
+
+        /// Deposit per participant in lamports, used when no tier is given
+        #[clap(long)]
+        deposit: u64,
+
+        /// Maximum number of participants
+        #[clap(long)]
+        capacity: u64,
+
+        /// Ticket tier as `price:capacity`, repeat for several tiers
+        #[clap(long = "tier")]
+        tiers: Vec<TierArg>,
+
+        /// Hex encoded merkle root of the allowed wallets
+        #[clap(long, parse(try_from_str = parse_hash))]
+        allowlist_root: Option<[u8; 32]>,
+
+        /// Key signing join permits
+        #[clap(long)]
+        permit_signer: Option<Pubkey>,
+
+        /// Unix timestamp the event starts at
+        #[clap(long, default_value = "0")]
+        start_time: i64,
+
+        /// Refund step as `seconds_before_start:refund_bps`, repeat from the earliest deadline on
+        #[clap(long = "refund-step")]
+        refund_schedule: Vec<RefundStepArg>,
+
+        /// Bond in lamports escrowed from the keypair, paid to participants if the event is cancelled
+        #[clap(long, default_value = "0")]
+        bond: u64,
+
+        /// Key resolving disputes during the dispute window
+        #[clap(long, requires = "dispute-window")]
+        arbiter: Option<Pubkey>,
+
+        /// Seconds after the event ends during which participants can dispute the payout
+        #[clap(long, default_value = "0")]
+        dispute_window: i64,
+
+        /// Share of participants in basis points that must confirm the event before payout
+        #[clap(long, default_value = "0", requires = "confirmation-window")]
+        confirmation_quorum_bps: u16,
+
+        /// Seconds after the event ends during which participants can confirm it
+        #[clap(long, default_value = "0")]
+        confirmation_window: i64,
+
+        /// Payout share as `payee:bps`, repeat starting with `--payee`; shares sum to 10000
+        #[clap(long = "payee-share")]
+        payees: Vec<PayeeShareArg>,
+
+        /// Release the escrow to the payees while the event runs, `linear` or `cliff`
+        #[clap(long, parse(try_from_str = parse_vesting), requires = "vesting-duration")]
+        vesting: Option<Vesting>,
+
+        /// Seconds after the event starts over which the escrow vests
+        #[clap(long, default_value = "0")]
+        vesting_duration: i64,
+
+        /// Basis points of the sponsor pool awarded to a rank, repeat from the winner on
+        #[clap(long = "prize-bps", requires = "results-window")]
+        prize_table: Vec<u16>,
+
+        /// Seconds after the payout within which the results are submitted
+        #[clap(long, default_value = "0")]
+        results_window: i64,
+
+        /// Deposits in lamports required by the registration deadline for the event to take place
+        #[clap(long, default_value = "0", requires = "registration-deadline")]
+        funding_goal: u64,
+
+        /// Unix timestamp registration closes at
+        #[clap(long, default_value = "0")]
+        registration_deadline: i64,
+
+        /// Smallest deposit in lamports of a pay-what-you-want event
+        #[clap(long, default_value = "0", requires = "max-deposit")]
+        min_deposit: u64,
+
+        /// Largest deposit in lamports participants may choose, replacing the tier prices
+        #[clap(long, default_value = "0")]
+        max_deposit: u64,
+
+        /// Price step as `starts_at:price`, repeat from the earliest start on
+        #[clap(long = "price-step")]
+        price_schedule: Vec<PriceStepArg>,
+
+        /// Allocate seats by a lottery drawn after the registration deadline
+        #[clap(long, requires = "registration-deadline")]
+        lottery: bool,
+    },
+    /// Join an event as the keypair, paying the ticket tier price or a chosen deposit
+    Join {
+        #[clap(long)]
+        event: Pubkey,
+
+        #[clap(long, default_value = "0")]
+        tier: u8,
+
+        /// Deposit in lamports for pay-what-you-want events, defaults to the minimum
+        #[clap(long)]
+        amount: Option<u64>,
+
+        /// Hex encoded allowlist proof hash, repeat for each level of the tree
+        #[clap(long = "proof", parse(try_from_str = parse_hash))]
+        allowlist_proof: Vec<[u8; 32]>,
+    },
+    /// Register several wallets, the keypair paying their deposits and receiving their refunds
+    JoinGroup {
+        #[clap(long)]
+        event: Pubkey,
+
+        #[clap(long, default_value = "0")]
+        tier: u8,
+
+        /// Deposit in lamports per seat for pay-what-you-want events, defaults to the minimum
+        #[clap(long)]
+        amount: Option<u64>,
+
+        /// Participant wallet, repeat for each seat
+        #[clap(long = "wallet", required = true)]
+        wallets: Vec<Pubkey>,
+    },
+    /// Contribute lamports from the keypair to the sponsor pool of an event
+    Sponsor {
+        #[clap(long)]
+        event: Pubkey,
+
+        #[clap(long)]
+        amount: u64,
+    },
+    /// Send the sponsor pool to the `payee`, the `attendees` or the `ranked` winners when the event is paid out
+    SponsorPool {
+        #[clap(long)]
+        event: Pubkey,
+
+        #[clap(long, parse(try_from_str = parse_sponsor_pool_destination))]
+        to: SponsorPoolDestination,
+    },
+    /// Rank the participants of a paid out competition
+    SubmitResults {
+        #[clap(long)]
+        event: Pubkey,
+
+        /// Participant account, repeat in placement order from the winner on
+        #[clap(long = "participant", required = true)]
+        participants: Vec<Pubkey>,
+    },
+    /// Claim the prize of the keypair's rank
+    ClaimPrize {
+        #[clap(long)]
+        event: Pubkey,
+    },
+    /// Commit the lottery of an event to a future draw slot once its registration deadline passed
+    CommitDraw {
+        #[clap(long)]
+        event: Pubkey,
+    },
+    /// Draw the lottery of an event once its committed draw slot passed
+    DrawLottery {
+        #[clap(long)]
+        event: Pubkey,
+    },
+    /// Open self check-in of a started event for the given code, as the creator
+    OpenCheckIn {
+        #[clap(long)]
+        event: Pubkey,
+
+        /// Code announced at the venue, long enough not to be guessed from its commitment
+        #[clap(long)]
+        code: String,
+
+        /// Seconds check-in stays open
+        #[clap(long)]
+        window: i64,
+    },
+    /// Check in at a started event with the code announced at the venue, as the participant keypair
+    CheckIn {
+        #[clap(long)]
+        event: Pubkey,
+
+        #[clap(long)]
+        code: String,
+    },
+    /// Reveal the check-in code once check-in closed, as the participant keypair that checked in
+    RevealCheckIn {
+        #[clap(long)]
+        event: Pubkey,
+
+        #[clap(long)]
+        code: String,
+    },
+    /// Seat or refund a lottery entry after the draw
+    ResolveEntry {
+        #[clap(long)]
+        event: Pubkey,
+
+        /// Participant account of the entry
+        #[clap(long)]
+        participant: Pubkey,
+    },
+    /// Close registration and start an event
+    Start {
+        #[clap(long)]
+        event: Pubkey,
+    },
+    /// End a started event and pay the payee
+    End {
+        #[clap(long)]
+        event: Pubkey,
+    },
+    /// Confirm an ended event took place, as the participant keypair
+    Confirm {
+        #[clap(long)]
+        event: Pubkey,
+    },
+    /// Pay the payees the escrow vested so far
+    ClaimVested {
+        #[clap(long)]
+        event: Pubkey,
+    },
+    /// Pay out an ended event once its dispute window closed
+    Settle {
+        #[clap(long)]
+        event: Pubkey,
+    },
+    /// Cancel an event
+    Cancel {
+        #[clap(long)]
+        event: Pubkey,
+    },
+    /// List the participant accounts of an event
+    Participants {
+        #[clap(long)]
+        event: Pubkey,
+    },
+    /// Print an event, participant, waitlist, series or sponsorship account as JSON
+    Show { account: Pubkey },
+}
+
+struct TierArg(TicketTier);
+
+impl FromStr for TierArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (price, capacity) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected `price:capacity`, got `{}`", s))?;
+        Ok(Self(TicketTier {
+            price: price
+                .parse()
+                .map_err(|err| format!("invalid price: {}", err))?,
+            capacity: capacity
+                .parse()
+                .map_err(|err| format!("invalid capacity: {}", err))?,
+        }))
+    }
+}
+
+struct RefundStepArg(RefundStep);
+
+impl FromStr for RefundStepArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (seconds_before_start, refund_bps) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected `seconds_before_start:refund_bps`, got `{}`", s))?;
+        Ok(Self(RefundStep {
+            seconds_before_start: seconds_before_start
+                .parse()
+                .map_err(|err| format!("invalid seconds before start: {}", err))?,
+            refund_bps: refund_bps
+                .parse()
+                .map_err(|err| format!("invalid refund bps: {}", err))?,
+        }))
+    }
+}
+
+struct PriceStepArg(PriceStep);
+
+impl FromStr for PriceStepArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (starts_at, price) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected `starts_at:price`, got `{}`", s))?;
+        Ok(Self(PriceStep {
+            starts_at: starts_at
+                .parse()
+                .map_err(|err| format!("invalid price step start: {}", err))?,
+            price: price
+                .parse()
+                .map_err(|err| format!("invalid price: {}", err))?,
+        }))
+    }
+}
+
+struct PayeeShareArg(PayeeShare);
+
+impl FromStr for PayeeShareArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (payee, share_bps) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected `payee:bps`, got `{}`", s))?;
+        Ok(Self(PayeeShare {
+            payee: payee
+                .parse()
+                .map_err(|err| format!("invalid payee: {}", err))?,
+            share_bps: share_bps
+                .parse()
+                .map_err(|err| format!("invalid share bps: {}", err))?,
+        }))
+    }
+}
+
+fn parse_vesting(s: &str) -> Result<Vesting, String> {
+    match s {
+        "linear" => Ok(Vesting::Linear),
+        "cliff" => Ok(Vesting::Cliff),
+        _ => Err(format!("expected `linear` or `cliff`, got `{}`", s)),
+    }
+}
+
+fn parse_sponsor_pool_destination(s: &str) -> Result<SponsorPoolDestination, String> {
+    match s {
+        "payee" => Ok(SponsorPoolDestination::Payee),
+        "attendees" => Ok(SponsorPoolDestination::Attendees),
+        "ranked" => Ok(SponsorPoolDestination::Ranked),
+        _ => Err(format!(
+            "expected `payee`, `attendees` or `ranked`, got `{}`",
+            s
+        )),
+    }
+}
+
+fn parse_hash(s: &str) -> Result<[u8; 32], String> {
+    let s = s.trim_start_matches("0x");
+    if s.len() != 64 {
+        return Err(format!("expected 64 hex characters, got {}", s.len()));
+    }
+
+    let mut hash = [0; 32];
+    for (index, byte) in hash.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[index * 2..index * 2 + 2], 16)
+            .map_err(|err| format!("invalid hex: {}", err))?;
+    }
+    Ok(hash)
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(err) = run(cli) {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> CliResult {
+    let client = RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed());
+    let program_id = cli.program_id;
+
+    match cli.command {
+        Command::Show { account } => show_account(&client, &account),
+        Command::Participants { event } => list_participants(&client, &program_id, &event),
+        command => {
+            let payer = read_keypair_file(expand_home(&cli.keypair))?;
+            match command {
+                Command::Create {
+                    event_id,
+                    payee,
+                    deposit,
+                    capacity,
+                    tiers,
+                    allowlist_root,
+                    permit_signer,
+                    start_time,
+                    refund_schedule,
+                    bond,
+                    arbiter,
+                    dispute_window,
+                    confirmation_quorum_bps,
+                    confirmation_window,
+                    payees,
+                    vesting,
+                    vesting_duration,
+                    prize_table,
+                    results_window,
+                    funding_goal,
+                    registration_deadline,
+                    min_deposit,
+                    max_deposit,
+                    price_schedule,
+                    lottery,
+                } => create_event(
+                    &client,
+                    &program_id,
+                    &payer,
+                    RentShareInstruction::InitializeEvent {
+                        payee_pubkey: payee,
+                        event_creator: payer.pubkey(),
+                        event_id,
+                        fix_deposit_amount_per_person: deposit,
+                        total_partcipator: capacity,
+                        tiers: tiers.into_iter().map(|tier| tier.0).collect(),
+                        allowlist_root: allowlist_root.unwrap_or_default(),
+                        permit_signer: permit_signer.unwrap_or_default(),
+                        start_time,
+                        refund_schedule: refund_schedule.into_iter().map(|step| step.0).collect(),
+                        bond,
+                        arbiter: arbiter.unwrap_or_default(),
+                        dispute_window,
+                        confirmation_quorum_bps,
+                        confirmation_window,
+                        payees: payees.into_iter().map(|share| share.0).collect(),
+                        vesting: vesting.unwrap_or(Vesting::None),
+                        vesting_duration,
+                        prize_table,
+                        results_window,
+                        funding_goal,
+                        registration_deadline,
+                        min_deposit,
+                        max_deposit,
+                        price_schedule: price_schedule.into_iter().map(|step| step.0).collect(),
+                        lottery,
+                    },
+                ),
+                Command::Join {
+                    event,
+                    tier,
+                    amount,
+                    allowlist_proof,
+                } => join_event(
+                    &client,
+                    &program_id,
+                    &payer,
+                    &event,
+                    tier,
+                    amount,
+                    allowlist_proof,
+                ),
+                Command::JoinGroup {
+                    event,
+                    tier,
+                    amount,
+                    wallets,
+                } => join_group(&client, &program_id, &payer, &event, tier, amount, &wallets),
+                Command::Sponsor { event, amount } => {
+                    sponsor_event(&client, &program_id, &payer, &event, amount)
+                }
+                Command::SponsorPool { event, to } => {
+                    let data = fetch_event(&client, &event)?;
+                    let instruction = Instruction::new_with_borsh(
+                        program_id,
+                        &RentShareInstruction::SetSponsorPoolDestination {
+                            event_id: data.event_id,
+                            event_creator: data.event_creator,
+                            destination: to,
+                        },
+                        vec![
+                            AccountMeta::new(event, false),
+                            AccountMeta::new_readonly(payer.pubkey(), true),
+                        ],
+                    );
+                    send(&client, &[instruction], &payer)
+                }
+                Command::SubmitResults {
+                    event,
+                    participants,
+                } => {
+                    let data = fetch_event(&client, &event)?;
+                    let instruction = Instruction::new_with_borsh(
+                        program_id,
+                        &RentShareInstruction::SubmitResults {
+                            event_id: data.event_id,
+                            event_creator: data.event_creator,
+                        },
+                        [
+                            vec![
+                                AccountMeta::new(event, false),
+                                AccountMeta::new_readonly(payer.pubkey(), true),
+                                AccountMeta::new(data.payee_pubkey, false),
+                            ],
+                            remaining_payees(&data),
+                            participants
+                                .iter()
+                                .map(|participant| AccountMeta::new(*participant, false))
+                                .collect(),
+                        ]
+                        .concat(),
+                    );
+                    send(&client, &[instruction], &payer)
+                }
+                Command::ClaimPrize { event } => {
+                    let data = fetch_event(&client, &event)?;
+                    let participant = seat_address(&program_id, &payer.pubkey(), &event)?;
+                    let instruction = Instruction::new_with_borsh(
+                        program_id,
+                        &RentShareInstruction::ClaimPrize {
+                            event_id: data.event_id,
+                            event_creator: data.event_creator,
+                        },
+                        vec![
+                            AccountMeta::new(event, false),
+                            AccountMeta::new(participant, false),
+                            AccountMeta::new(payer.pubkey(), true),
+                        ],
+                    );
+                    send(&client, &[instruction], &payer)
+                }
+                Command::CommitDraw { event } => {
+                    let data = fetch_event(&client, &event)?;
+                    let mut accounts = vec![AccountMeta::new(event, false)];
+                    if data.lottery_draw_slot > 0 {
+                        accounts.push(AccountMeta::new_readonly(sysvar::slot_hashes::id(), false));
+                    }
+                    let instruction = Instruction::new_with_borsh(
+                        program_id,
+                        &RentShareInstruction::CommitLotteryDraw {
+                            event_id: data.event_id,
+                            event_creator: data.event_creator,
+                        },
+                        accounts,
+                    );
+                    send(&client, &[instruction], &payer)
+                }
+                Command::DrawLottery { event } => {
+                    let data = fetch_event(&client, &event)?;
+                    let instruction = Instruction::new_with_borsh(
+                        program_id,
+                        &RentShareInstruction::DrawLottery {
+                            event_id: data.event_id,
+                            event_creator: data.event_creator,
+                        },
+                        vec![
+                            AccountMeta::new(event, false),
+                            AccountMeta::new_readonly(sysvar::slot_hashes::id(), false),
+                        ],
+                    );
+                    send(&client, &[instruction], &payer)
+                }
+                Command::ResolveEntry { event, participant } => {
+                    let data = fetch_event(&client, &event)?;
+                    let entry =
+                        Participant::try_from_slice(&client.get_account(&participant)?.data)?;
+                    let instruction = Instruction::new_with_borsh(
+                        program_id,
+                        &RentShareInstruction::ResolveLotteryEntry {
+                            event_id: data.event_id,
+                            event_creator: data.event_creator,
+                        },
+                        vec![
+                            AccountMeta::new(event, false),
+                            AccountMeta::new(participant, false),
+                            AccountMeta::new(entry.participant, false),
+                        ],
+                    );
+                    send(&client, &[instruction], &payer)
+                }
+                Command::OpenCheckIn {
+                    event,
+                    code,
+                    window,
+                } => {
+                    let data = fetch_event(&client, &event)?;
+                    let instruction = Instruction::new_with_borsh(
+                        program_id,
+                        &RentShareInstruction::OpenCheckIn {
+                            event_id: data.event_id,
+                            event_creator: data.event_creator,
+                            code_commitment: checkin::commitment(&event, code.as_bytes()),
+                            checkin_window: window,
+                        },
+                        vec![
+                            AccountMeta::new(event, false),
+                            AccountMeta::new_readonly(payer.pubkey(), true),
+                        ],
+                    );
+                    send(&client, &[instruction], &payer)
+                }
+                Command::CheckIn { event, code } => {
+                    let data = fetch_event(&client, &event)?;
+                    let participant = seat_address(&program_id, &payer.pubkey(), &event)?;
+                    let instruction = Instruction::new_with_borsh(
+                        program_id,
+                        &RentShareInstruction::CheckIn {
+                            event_id: data.event_id,
+                            event_creator: data.event_creator,
+                            checkin_commitment: checkin::participant_commitment(
+                                code.as_bytes(),
+                                &payer.pubkey(),
+                            ),
+                        },
+                        vec![
+                            AccountMeta::new_readonly(event, false),
+                            AccountMeta::new(participant, false),
+                            AccountMeta::new_readonly(payer.pubkey(), true),
+                        ],
+                    );
+                    send(&client, &[instruction], &payer)
+                }
+                Command::RevealCheckIn { event, code } => {
+                    let data = fetch_event(&client, &event)?;
+                    let participant = seat_address(&program_id, &payer.pubkey(), &event)?;
+                    let instruction = Instruction::new_with_borsh(
+                        program_id,
+                        &RentShareInstruction::RevealCheckIn {
+                            event_id: data.event_id,
+                            event_creator: data.event_creator,
+                            code: code.into_bytes(),
+                        },
+                        vec![
+                            AccountMeta::new(event, false),
+                            AccountMeta::new(participant, false),
+                            AccountMeta::new_readonly(payer.pubkey(), true),
+                        ],
+                    );
+                    send(&client, &[instruction], &payer)
+                }
+                Command::Start { event } => {
+                    let data = fetch_event(&client, &event)?;
+                    let instruction = Instruction::new_with_borsh(
+                        program_id,
+                        &RentShareInstruction::StartEvent {
+                            event_id: data.event_id,
+                            event_creator: data.event_creator,
+                        },
+                        vec![
+                            AccountMeta::new(event, false),
+                            AccountMeta::new_readonly(payer.pubkey(), true),
+                        ],
+                    );
+                    send(&client, &[instruction], &payer)
+                }
+                Command::End { event } => {
+                    let data = fetch_event(&client, &event)?;
+                    let instruction = Instruction::new_with_borsh(
+                        program_id,
+                        &RentShareInstruction::EndEvent {
+                            event_id: data.event_id,
+                            event_creator: data.event_creator,
+                        },
+                        [
+                            vec![
+                                AccountMeta::new(event, false),
+                                AccountMeta::new(payer.pubkey(), true),
+                                AccountMeta::new(data.payee_pubkey, false),
+                            ],
+                            remaining_payees(&data),
+                        ]
+                        .concat(),
+                    );
+                    send(&client, &[instruction], &payer)
+                }
+                Command::Confirm { event } => {
+                    let data = fetch_event(&client, &event)?;
+                    let participant = seat_address(&program_id, &payer.pubkey(), &event)?;
+                    let instruction = Instruction::new_with_borsh(
+                        program_id,
+                        &RentShareInstruction::ConfirmEvent {
+                            event_id: data.event_id,
+                            event_creator: data.event_creator,
+                        },
+                        vec![
+                            AccountMeta::new(event, false),
+                            AccountMeta::new(participant, false),
+                            AccountMeta::new_readonly(payer.pubkey(), true),
+                        ],
+                    );
+                    send(&client, &[instruction], &payer)
+                }
+                Command::ClaimVested { event } => {
+                    let data = fetch_event(&client, &event)?;
+                    let instruction = Instruction::new_with_borsh(
+                        program_id,
+                        &RentShareInstruction::ClaimVested {
+                            event_id: data.event_id,
+                            event_creator: data.event_creator,
+                        },
+                        [
+                            vec![
+                                AccountMeta::new(event, false),
+                                AccountMeta::new(data.payee_pubkey, false),
+                            ],
+                            remaining_payees(&data),
+                        ]
+                        .concat(),
+                    );
+                    send(&client, &[instruction], &payer)
+                }
+                Command::Settle { event } => {
+                    let data = fetch_event(&client, &event)?;
+                    let instruction = Instruction::new_with_borsh(
+                        program_id,
+                        &RentShareInstruction::SettleEvent {
+                            event_id: data.event_id,
+                            event_creator: data.event_creator,
+                        },
+                        [
+                            vec![
+                                AccountMeta::new(event, false),
+                                AccountMeta::new(data.payee_pubkey, false),
+                                AccountMeta::new(data.event_creator, false),
+                            ],
+                            remaining_payees(&data),
+                        ]
+                        .concat(),
+                    );
+                    send(&client, &[instruction], &payer)
+                }
+                Command::Cancel { event } => {
+                    let data = fetch_event(&client, &event)?;
+                    let instruction = Instruction::new_with_borsh(
+                        program_id,
+                        &RentShareInstruction::CancelEvent {
+                            event_creator: data.event_creator,
+                            event_id: data.event_id,
+                        },
+                        vec![
+                            AccountMeta::new(event, false),
+                            AccountMeta::new(payer.pubkey(), true),
+                        ],
+                    );
+                    send(&client, &[instruction], &payer)
+                }
+                Command::Show { .. } | Command::Participants { .. } => unreachable!(),
+            }
+        }
+    }
+}
+
+fn create_event(
+    client: &RpcClient,
+    program_id: &Pubkey,
+    payer: &Keypair,
+    initialize_event: RentShareInstruction,
+) -> CliResult {
+    let (event_id, bond) = match &initialize_event {
+        RentShareInstruction::InitializeEvent { event_id, bond, .. } => (*event_id, *bond),
+        _ => unreachable!(),
+    };
+
+    let seed = format!("event-{}", event_id);
+    let event = Pubkey::create_with_seed(&payer.pubkey(), &seed, program_id)?;
+    let lamports = client.get_minimum_balance_for_rent_exemption(InitEvent::LEN)?;
+
+    let mut accounts = vec![
+        AccountMeta::new(event, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    if bond > 0 {
+        accounts.push(AccountMeta::new(payer.pubkey(), true));
+        accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    }
+
+    let instructions = [
+        system_instruction::create_account_with_seed(
+            &payer.pubkey(),
+            &event,
+            &payer.pubkey(),
+            &seed,
+            lamports,
+            InitEvent::LEN as u64,
+            program_id,
+        ),
+        Instruction::new_with_borsh(*program_id, &initialize_event, accounts),
+    ];
+    send(client, &instructions, payer)?;
+
+    println!("Event: {}", event);
+    Ok(())
+}
+
+fn join_event(
+    client: &RpcClient,
+    program_id: &Pubkey,
+    payer: &Keypair,
+    event: &Pubkey,
+    tier: u8,
+    amount: Option<u64>,
+    allowlist_proof: Vec<[u8; 32]>,
+) -> CliResult {
+    let data = fetch_event(client, event)?;
+    if data.requires_permit() || data.has_tickets() {
+        return Err(
+            "events requiring join permits or issuing tickets can't be joined from the CLI".into(),
+        );
+    }
+    let price = deposit_amount(client, &data, tier, amount)?;
+
+    let seed = seat_seed(event);
+    let participant = seat_address(program_id, &payer.pubkey(), event)?;
+
+    let mut instructions = Vec::new();
+    if client
+        .get_account_with_commitment(&participant, client.commitment())?
+        .value
+        .is_none()
+    {
+        let lamports = client.get_minimum_balance_for_rent_exemption(Participant::LEN)?;
+        instructions.push(system_instruction::create_account_with_seed(
+            &payer.pubkey(),
+            &participant,
+            &payer.pubkey(),
+            &seed,
+            lamports,
+            Participant::LEN as u64,
+            program_id,
+        ));
+    }
+
+    instructions.push(Instruction::new_with_borsh(
+        *program_id,
+        &RentShareInstruction::ParticipateInEvent {
+            event_id: data.event_id,
+            particpate_amount: price,
+            event_creator: data.event_creator,
+            tier,
+            allowlist_proof,
+        },
+        vec![
+            AccountMeta::new(*event, false),
+            AccountMeta::new(participant, false),
+            AccountMeta::new(payer.pubkey(), true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    ));
+    send(client, &instructions, payer)?;
+
+    println!("Participant: {}", participant);
+    Ok(())
+}
+
+// Tier price in effect on the cluster, or the chosen deposit of pay-what-you-want events
+fn deposit_amount(
+    client: &RpcClient,
+    data: &InitEvent,
+    tier: u8,
+    amount: Option<u64>,
+) -> CliResult<u64> {
+    let mut price = data.tier(tier).ok_or("unknown ticket tier")?.price;
+    if data.has_price_schedule() {
+        price = data.price_at(price, client.get_block_time(client.get_slot()?)?);
+    }
+    match amount {
+        Some(amount) if data.has_flexible_pricing() => Ok(amount),
+        Some(_) => Err(
+            "the event has fixed tier prices, --amount is only for pay-what-you-want events".into(),
+        ),
+        None if data.has_flexible_pricing() => Ok(data.min_deposit),
+        None => Ok(price),
+    }
+}
+
+// One seat per keypair and event, so the participant account is derived from the event key
+fn seat_seed(event: &Pubkey) -> String {
+    format!("seat-{}", &event.to_string()[..16])
+}
+
+fn seat_address(program_id: &Pubkey, wallet: &Pubkey, event: &Pubkey) -> CliResult<Pubkey> {
+    Ok(Pubkey::create_with_seed(
+        wallet,
+        &seat_seed(event),
+        program_id,
+    )?)
+}
+
+fn join_group(
+    client: &RpcClient,
+    program_id: &Pubkey,
+    payer: &Keypair,
+    event: &Pubkey,
+    tier: u8,
+    amount: Option<u64>,
+    wallets: &[Pubkey],
+) -> CliResult {
+    let data = fetch_event(client, event)?;
+    let price = deposit_amount(client, &data, tier, amount)?;
+    let lamports = client.get_minimum_balance_for_rent_exemption(Participant::LEN)?;
+
+    let mut instructions = Vec::new();
+    let mut accounts = vec![
+        AccountMeta::new(*event, false),
+        AccountMeta::new(payer.pubkey(), true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    let mut participants = Vec::new();
+    for wallet in wallets {
+        // Group seats are created by the payer, one per event and wallet
+        let seed = format!("g{}{}", &event.to_string()[..15], &wallet.to_string()[..15]);
+        let participant = Pubkey::create_with_seed(&payer.pubkey(), &seed, program_id)?;
+        instructions.push(system_instruction::create_account_with_seed(
+            &payer.pubkey(),
+            &participant,
+            &payer.pubkey(),
+            &seed,
+            lamports,
+            Participant::LEN as u64,
+            program_id,
+        ));
+        accounts.push(AccountMeta::new(participant, false));
+        accounts.push(AccountMeta::new_readonly(*wallet, false));
+        participants.push((participant, wallet));
+    }
+
+    instructions.push(Instruction::new_with_borsh(
+        *program_id,
+        &RentShareInstruction::BatchParticipateInEvent {
+            event_id: data.event_id,
+            event_creator: data.event_creator,
+            tier,
+            particpate_amount: price,
+        },
+        accounts,
+    ));
+    send(client, &instructions, payer)?;
+
+    for (participant, wallet) in participants {
+        println!("Participant: {} wallet={}", participant, wallet);
+    }
+    Ok(())
+}
+
+fn sponsor_event(
+    client: &RpcClient,
+    program_id: &Pubkey,
+    payer: &Keypair,
+    event: &Pubkey,
+    amount: u64,
+) -> CliResult {
+    let data = fetch_event(client, event)?;
+
+    // One sponsorship per keypair and event, later contributions add up in it
+    let seed = format!("sponsor-{}", &event.to_string()[..16]);
+    let sponsorship = Pubkey::create_with_seed(&payer.pubkey(), &seed, program_id)?;
+
+    let mut instructions = Vec::new();
+    if client
+        .get_account_with_commitment(&sponsorship, client.commitment())?
+        .value
+        .is_none()
+    {
+        let lamports = client.get_minimum_balance_for_rent_exemption(Sponsorship::LEN)?;
+        instructions.push(system_instruction::create_account_with_seed(
+            &payer.pubkey(),
+            &sponsorship,
+            &payer.pubkey(),
+            &seed,
+            lamports,
+            Sponsorship::LEN as u64,
+            program_id,
+        ));
+    }
+
+    instructions.push(Instruction::new_with_borsh(
+        *program_id,
+        &RentShareInstruction::Sponsor {
+            event_id: data.event_id,
+            event_creator: data.event_creator,
+            amount,
+        },
+        vec![
+            AccountMeta::new(*event, false),
+            AccountMeta::new(sponsorship, false),
+            AccountMeta::new(payer.pubkey(), true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    ));
+    send(client, &instructions, payer)?;
+
+    println!("Sponsorship: {}", sponsorship);
+    Ok(())
+}
+
+fn list_participants(client: &RpcClient, program_id: &Pubkey, event: &Pubkey) -> CliResult {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::DataSize(Participant::LEN as u64),
+            // `Participant::event` follows the status byte
+            RpcFilterType::Memcmp(Memcmp {
+                offset: 1,
+                bytes: MemcmpEncodedBytes::Base58(event.to_string()),
+                encoding: None,
+            }),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        with_context: None,
+    };
+
+    for (address, account) in client.get_program_accounts_with_config(program_id, config)? {
+        let participant = Participant::try_from_slice(&account.data)?;
+        println!(
+            "{} wallet={} tier={} deposit={} status={}",
+            address,
+            participant.participant,
+            participant.tier,
+            participant.deposit,
+            participant.status
+        );
+    }
+    Ok(())
+}
+
+fn show_account(client: &RpcClient, address: &Pubkey) -> CliResult {
+    let account = client.get_account(address)?;
+    println!("{}", decoder::to_json(&account.data)?);
+    Ok(())
+}
+
+/// Payee accounts after `payee_pubkey` that a payout is split to
+fn remaining_payees(event: &InitEvent) -> Vec<AccountMeta> {
+    event.payees[..event.payee_count as usize]
+        .iter()
+        .skip(1)
+        .map(|share| AccountMeta::new(share.payee, false))
+        .collect()
+}
+
+fn fetch_event(client: &RpcClient, event: &Pubkey) -> CliResult<InitEvent> {
+    let account = client.get_account(event)?;
+    Ok(InitEvent::try_from_slice(&account.data)?)
+}
+
+fn send(client: &RpcClient, instructions: &[Instruction], payer: &Keypair) -> CliResult {
+    let blockhash = client.get_latest_blockhash()?;
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        blockhash,
+    );
+    let signature = client.send_and_confirm_transaction(&transaction)?;
+
+    println!("Signature: {}", signature);
+    Ok(())
+}
+
+fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{}/{}", home, rest),
+        _ => path.to_string(),
+    }
+}
@@ -0,0 +1,71 @@
+//! Commit-reveal helpers for self check-in at the door.
+//!
+//! The event creator commits to `sha256(event pubkey || code)` and announces the
+//! code at the venue. Binding the event key means a code can't be replayed
+//! across events, but short codes can still be brute forced from the
+//! commitment, so they should be long enough not to be guessed within the
+//! check-in window.
+//!
+//! Participants don't send the code while check-in is open, as anyone could
+//! copy it from the transaction. They commit to `sha256(code || wallet)`
+//! instead, which is useless to other wallets, and reveal the code once the
+//! window closed and no more commitments are taken.
+
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+/// Commitment to the check-in code of an event
+pub fn commitment(event: &Pubkey, code: &[u8]) -> [u8; 32] {
+    hashv(&[event.as_ref(), code]).to_bytes()
+}
+
+/// Check that `code` is the one committed to for the event
+pub fn verify(commitment_hash: &[u8; 32], event: &Pubkey, code: &[u8]) -> bool {
+    commitment(event, code) == *commitment_hash
+}
+
+/// Commitment of a participant wallet to the check-in code it was given at the venue
+pub fn participant_commitment(code: &[u8], wallet: &Pubkey) -> [u8; 32] {
+    hashv(&[code, wallet.as_ref()]).to_bytes()
+}
+
+/// Check that `code` is the one the wallet committed to
+pub fn verify_participant(commitment_hash: &[u8; 32], code: &[u8], wallet: &Pubkey) -> bool {
+    participant_commitment(code, wallet) == *commitment_hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_are_bound_to_the_event() {
+        let event = Pubkey::new_unique();
+        let commitment_hash = commitment(&event, b"door-code");
+
+        assert!(verify(&commitment_hash, &event, b"door-code"));
+        assert!(!verify(&commitment_hash, &event, b"door-cod"));
+        assert!(!verify(
+            &commitment_hash,
+            &Pubkey::new_unique(),
+            b"door-code"
+        ));
+    }
+
+    #[test]
+    fn participant_commitments_are_bound_to_the_wallet() {
+        let wallet = Pubkey::new_unique();
+        let commitment_hash = participant_commitment(b"door-code", &wallet);
+
+        assert!(verify_participant(&commitment_hash, b"door-code", &wallet));
+        assert!(!verify_participant(
+            &commitment_hash,
+            b"other-code",
+            &wallet
+        ));
+        assert!(!verify_participant(
+            &commitment_hash,
+            b"door-code",
+            &Pubkey::new_unique()
+        ));
+    }
+}
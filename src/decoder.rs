@@ -0,0 +1,658 @@
+//! Human-readable decoding of RentShare accounts for support tooling.
+//!
+//! Every account type has a fixed size, so [`decode`] detects the layout from
+//! the length of the account data and renders it with status names and base58
+//! pubkeys. The program has no separate rent agreement layout any more: the
+//! event account (`InitEvent`) is the agreement account and decodes as `event`.
+
+use borsh::BorshDeserialize;
+use serde::Serialize;
+use solana_program::pubkey::Pubkey;
+use thiserror::Error;
+
+use crate::state::{
+    DisputeStatus, EventSeries, EventStatus, EventTier, InitEvent, Participant, ParticipantStatus,
+    SeriesStatus, SponsorPoolDestination, Sponsorship, SponsorshipStatus, TransferPolicy, Vesting,
+    Waitlist, WaitlistStatus,
+};
+
+#[derive(Error, Debug)]
+pub enum DecodeError {
+    /// Data length doesn't match any account layout of the program
+    #[error("Unknown account layout of {0} bytes")]
+    UnknownLayout(usize),
+    #[error("Invalid account data: {0}")]
+    InvalidData(#[from] std::io::Error),
+    #[error("JSON encoding failed: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DecodedAccount {
+    Event(Box<EventView>),
+    Participant(ParticipantView),
+    Waitlist(WaitlistView),
+    Series(SeriesView),
+    Sponsorship(SponsorshipView),
+}
+
+#[derive(Serialize, Debug)]
+pub struct EventView {
+    pub status: &'static str,
+    pub event_id: u64,
+    pub event_creator: String,
+    pub payee: String,
+    pub fix_deposit_amount_per_person: u64,
+    pub total_partcipator: u64,
+    pub participant_count: u64,
+    pub total_deposits: u64,
+    pub tiers: Vec<TierView>,
+    pub allowlist_root: Option<String>,
+    pub permit_signer: Option<String>,
+    pub transfer_policy: &'static str,
+    pub ticket_mint: Option<String>,
+    pub freeze_tickets: bool,
+    pub series: Option<String>,
+    pub start_time: i64,
+    pub refund_schedule: Vec<RefundStepView>,
+    pub bond: u64,
+    pub bond_share: u64,
+    pub arbiter: Option<String>,
+    pub dispute_window: i64,
+    pub dispute_deadline: i64,
+    pub dispute_status: &'static str,
+    pub disputant: Option<String>,
+    pub dispute_refund_bps: u16,
+    pub dispute_refund_reserve: u64,
+    pub confirmation_quorum_bps: u16,
+    pub confirmation_window: i64,
+    pub confirmation_deadline: i64,
+    pub confirmations: u64,
+    pub payees: Vec<PayeeShareView>,
+    pub vesting: &'static str,
+    pub vesting_duration: i64,
+    pub vesting_start: i64,
+    pub vesting_total: u64,
+    pub vested_claimed: u64,
+    pub sponsor_pool: u64,
+    pub sponsor_pool_destination: &'static str,
+    pub sponsor_share: u64,
+    pub prize_table: Vec<u16>,
+    pub prize_pool: u64,
+    pub results_submitted: bool,
+    pub results_window: i64,
+    pub results_deadline: i64,
+    pub funding_goal: u64,
+    pub registration_deadline: i64,
+    pub min_deposit: u64,
+    pub max_deposit: u64,
+    pub price_schedule: Vec<PriceStepView>,
+    pub lottery: bool,
+    pub lottery_entries: u64,
+    pub lottery_unresolved: u64,
+    pub lottery_draw_slot: u64,
+    pub lottery_pool: u64,
+    pub lottery_seed: Option<String>,
+    pub checkin_commitment: Option<String>,
+    pub checkin_deadline: i64,
+    pub attendance: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PayeeShareView {
+    pub payee: String,
+    pub share_bps: u16,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PriceStepView {
+    pub starts_at: i64,
+    pub price: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RefundStepView {
+    pub seconds_before_start: i64,
+    pub refund_bps: u16,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TierView {
+    pub price: u64,
+    pub capacity: u64,
+    pub sold: u64,
+    pub waitlist: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ParticipantView {
+    pub status: &'static str,
+    pub event: String,
+    pub participant: String,
+    pub tier: u8,
+    pub deposit: u64,
+    pub ticket_account: Option<String>,
+    pub dispute_refund_claimed: bool,
+    pub confirmed: bool,
+    pub payer: Option<String>,
+    pub sponsor_share_claimed: bool,
+    pub rank: u8,
+    pub prize_claimed: bool,
+    pub lottery_entry: u64,
+    pub checkin_commitment: Option<String>,
+    pub attended: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct WaitlistView {
+    pub status: &'static str,
+    pub event: String,
+    pub tier: u8,
+    pub entries: Vec<WaitlistEntryView>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct WaitlistEntryView {
+    pub wallet: String,
+    pub deposit: u64,
+    pub participant_account: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SeriesView {
+    pub status: &'static str,
+    pub series_creator: String,
+    pub first_event_id: u64,
+    pub payee: String,
+    pub fix_deposit_amount_per_person: u64,
+    pub total_partcipator: u64,
+    pub organizers: Vec<String>,
+    pub first_start: i64,
+    pub interval: i64,
+    pub max_occurrences: u64,
+    pub occurrence_count: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SponsorshipView {
+    pub status: &'static str,
+    pub event: String,
+    pub sponsor: String,
+    pub amount: u64,
+}
+
+/// Decode raw account data, detecting the account type from its length
+pub fn decode(data: &[u8]) -> Result<DecodedAccount, DecodeError> {
+    match data.len() {
+        InitEvent::LEN => Ok(DecodedAccount::Event(Box::new(event_view(
+            &InitEvent::try_from_slice(data)?,
+        )))),
+        Participant::LEN => Ok(DecodedAccount::Participant(participant_view(
+            &Participant::try_from_slice(data)?,
+        ))),
+        Waitlist::LEN => Ok(DecodedAccount::Waitlist(waitlist_view(
+            &Waitlist::try_from_slice(data)?,
+        ))),
+        EventSeries::LEN => Ok(DecodedAccount::Series(series_view(
+            &EventSeries::try_from_slice(data)?,
+        ))),
+        Sponsorship::LEN => Ok(DecodedAccount::Sponsorship(sponsorship_view(
+            &Sponsorship::try_from_slice(data)?,
+        ))),
+        len => Err(DecodeError::UnknownLayout(len)),
+    }
+}
+
+/// Decode raw account data and render it as pretty-printed JSON
+pub fn to_json(data: &[u8]) -> Result<String, DecodeError> {
+    Ok(serde_json::to_string_pretty(&decode(data)?)?)
+}
+
+fn event_view(event: &InitEvent) -> EventView {
+    EventView {
+        status: event_status_name(event.status),
+        event_id: event.event_id,
+        event_creator: event.event_creator.to_string(),
+        payee: event.payee_pubkey.to_string(),
+        fix_deposit_amount_per_person: event.fix_deposit_amount_per_person,
+        total_partcipator: event.total_partcipator,
+        participant_count: event.participant_count,
+        total_deposits: event.total_deposits,
+        tiers: event.tiers[..(event.tier_count as usize).min(event.tiers.len())]
+            .iter()
+            .map(tier_view)
+            .collect(),
+        allowlist_root: if event.has_allowlist() {
+            Some(hex(&event.allowlist_root))
+        } else {
+            None
+        },
+        permit_signer: optional_pubkey(&event.permit_signer),
+        transfer_policy: transfer_policy_name(event.transfer_policy),
+        ticket_mint: optional_pubkey(&event.ticket_mint),
+        freeze_tickets: event.freeze_tickets,
+        series: optional_pubkey(&event.series),
+        start_time: event.start_time,
+        refund_schedule: event.refund_schedule
+            [..(event.refund_step_count as usize).min(event.refund_schedule.len())]
+            .iter()
+            .map(|step| RefundStepView {
+                seconds_before_start: step.seconds_before_start,
+                refund_bps: step.refund_bps,
+            })
+            .collect(),
+        bond: event.bond,
+        bond_share: event.bond_share,
+        arbiter: optional_pubkey(&event.arbiter),
+        dispute_window: event.dispute_window,
+        dispute_deadline: event.dispute_deadline,
+        dispute_status: dispute_status_name(event.dispute_status),
+        disputant: optional_pubkey(&event.disputant),
+        dispute_refund_bps: event.dispute_refund_bps,
+        dispute_refund_reserve: event.dispute_refund_reserve,
+        confirmation_quorum_bps: event.confirmation_quorum_bps,
+        confirmation_window: event.confirmation_window,
+        confirmation_deadline: event.confirmation_deadline,
+        confirmations: event.confirmations,
+        payees: event.payees[..(event.payee_count as usize).min(event.payees.len())]
+            .iter()
+            .map(|share| PayeeShareView {
+                payee: share.payee.to_string(),
+                share_bps: share.share_bps,
+            })
+            .collect(),
+        vesting: vesting_name(event.vesting),
+        vesting_duration: event.vesting_duration,
+        vesting_start: event.vesting_start,
+        vesting_total: event.vesting_total,
+        vested_claimed: event.vested_claimed,
+        sponsor_pool: event.sponsor_pool,
+        sponsor_pool_destination: sponsor_pool_destination_name(event.sponsor_pool_destination),
+        sponsor_share: event.sponsor_share,
+        prize_table: event.prize_table
+            [..(event.prize_rank_count as usize).min(event.prize_table.len())]
+            .to_vec(),
+        prize_pool: event.prize_pool,
+        results_submitted: event.results_submitted,
+        results_window: event.results_window,
+        results_deadline: event.results_deadline,
+        funding_goal: event.funding_goal,
+        registration_deadline: event.registration_deadline,
+        min_deposit: event.min_deposit,
+        max_deposit: event.max_deposit,
+        price_schedule: event.price_schedule
+            [..(event.price_step_count as usize).min(event.price_schedule.len())]
+            .iter()
+            .map(|step| PriceStepView {
+                starts_at: step.starts_at,
+                price: step.price,
+            })
+            .collect(),
+        lottery: event.lottery,
+        lottery_entries: event.lottery_entries,
+        lottery_unresolved: event.lottery_unresolved,
+        lottery_draw_slot: event.lottery_draw_slot,
+        lottery_pool: event.lottery_pool,
+        lottery_seed: if event.lottery_drawn {
+            Some(hex(&event.lottery_seed))
+        } else {
+            None
+        },
+        checkin_commitment: if event.has_checkin() {
+            Some(hex(&event.checkin_commitment))
+        } else {
+            None
+        },
+        checkin_deadline: event.checkin_deadline,
+        attendance: event.attendance,
+    }
+}
+
+fn tier_view(tier: &EventTier) -> TierView {
+    TierView {
+        price: tier.price,
+        capacity: tier.capacity,
+        sold: tier.sold,
+        waitlist: optional_pubkey(&tier.waitlist),
+    }
+}
+
+fn participant_view(participant: &Participant) -> ParticipantView {
+    ParticipantView {
+        status: participant_status_name(participant.status),
+        event: participant.event.to_string(),
+        participant: participant.participant.to_string(),
+        tier: participant.tier,
+        deposit: participant.deposit,
+        ticket_account: optional_pubkey(&participant.ticket_account),
+        dispute_refund_claimed: participant.dispute_refund_claimed,
+        confirmed: participant.confirmed,
+        payer: optional_pubkey(&participant.payer),
+        sponsor_share_claimed: participant.sponsor_share_claimed,
+        rank: participant.rank,
+        prize_claimed: participant.prize_claimed,
+        lottery_entry: participant.lottery_entry,
+        checkin_commitment: if participant.checkin_commitment != [0; 32] {
+            Some(hex(&participant.checkin_commitment))
+        } else {
+            None
+        },
+        attended: participant.attended,
+    }
+}
+
+fn waitlist_view(waitlist: &Waitlist) -> WaitlistView {
+    WaitlistView {
+        status: waitlist_status_name(waitlist.status),
+        event: waitlist.event.to_string(),
+        tier: waitlist.tier,
+        entries: waitlist.entries[..(waitlist.len as usize).min(waitlist.entries.len())]
+            .iter()
+            .map(|entry| WaitlistEntryView {
+                wallet: entry.wallet.to_string(),
+                deposit: entry.deposit,
+                participant_account: entry.participant_account.to_string(),
+            })
+            .collect(),
+    }
+}
+
+fn series_view(series: &EventSeries) -> SeriesView {
+    SeriesView {
+        status: series_status_name(series.status),
+        series_creator: series.series_creator.to_string(),
+        first_event_id: series.first_event_id,
+        payee: series.payee_pubkey.to_string(),
+        fix_deposit_amount_per_person: series.fix_deposit_amount_per_person,
+        total_partcipator: series.total_partcipator,
+        organizers: series.organizers
+            [..(series.organizer_count as usize).min(series.organizers.len())]
+            .iter()
+            .map(Pubkey::to_string)
+            .collect(),
+        first_start: series.first_start,
+        interval: series.interval,
+        max_occurrences: series.max_occurrences,
+        occurrence_count: series.occurrence_count,
+    }
+}
+
+fn sponsorship_view(sponsorship: &Sponsorship) -> SponsorshipView {
+    SponsorshipView {
+        status: sponsorship_status_name(sponsorship.status),
+        event: sponsorship.event.to_string(),
+        sponsor: sponsorship.sponsor.to_string(),
+        amount: sponsorship.amount,
+    }
+}
+
+fn event_status_name(status: u8) -> &'static str {
+    match status {
+        s if s == EventStatus::Uninitialized as u8 => "uninitialized",
+        s if s == EventStatus::Active as u8 => "active",
+        s if s == EventStatus::Completed as u8 => "completed",
+        s if s == EventStatus::Terminated as u8 => "terminated",
+        s if s == EventStatus::Started as u8 => "started",
+        s if s == EventStatus::Ended as u8 => "ended",
+        _ => "unknown",
+    }
+}
+
+fn dispute_status_name(status: u8) -> &'static str {
+    match status {
+        s if s == DisputeStatus::None as u8 => "none",
+        s if s == DisputeStatus::Open as u8 => "open",
+        s if s == DisputeStatus::Resolved as u8 => "resolved",
+        _ => "unknown",
+    }
+}
+
+fn participant_status_name(status: u8) -> &'static str {
+    match status {
+        s if s == ParticipantStatus::Uninitialized as u8 => "uninitialized",
+        s if s == ParticipantStatus::Joined as u8 => "joined",
+        s if s == ParticipantStatus::Waiting as u8 => "waiting",
+        s if s == ParticipantStatus::Entered as u8 => "entered",
+        _ => "unknown",
+    }
+}
+
+fn waitlist_status_name(status: u8) -> &'static str {
+    match status {
+        s if s == WaitlistStatus::Uninitialized as u8 => "uninitialized",
+        s if s == WaitlistStatus::Active as u8 => "active",
+        _ => "unknown",
+    }
+}
+
+fn series_status_name(status: u8) -> &'static str {
+    match status {
+        s if s == SeriesStatus::Uninitialized as u8 => "uninitialized",
+        s if s == SeriesStatus::Active as u8 => "active",
+        _ => "unknown",
+    }
+}
+
+fn sponsorship_status_name(status: u8) -> &'static str {
+    match status {
+        s if s == SponsorshipStatus::Uninitialized as u8 => "uninitialized",
+        s if s == SponsorshipStatus::Active as u8 => "active",
+        _ => "unknown",
+    }
+}
+
+fn sponsor_pool_destination_name(destination: u8) -> &'static str {
+    match destination {
+        d if d == SponsorPoolDestination::Payee as u8 => "payee",
+        d if d == SponsorPoolDestination::Attendees as u8 => "attendees",
+        d if d == SponsorPoolDestination::Ranked as u8 => "ranked",
+        _ => "unknown",
+    }
+}
+
+fn transfer_policy_name(policy: u8) -> &'static str {
+    match policy {
+        p if p == TransferPolicy::Allowed as u8 => "allowed",
+        p if p == TransferPolicy::Disabled as u8 => "disabled",
+        p if p == TransferPolicy::RequiresCreator as u8 => "requires_creator",
+        _ => "unknown",
+    }
+}
+
+fn vesting_name(vesting: u8) -> &'static str {
+    match vesting {
+        v if v == Vesting::None as u8 => "none",
+        v if v == Vesting::Linear as u8 => "linear",
+        v if v == Vesting::Cliff as u8 => "cliff",
+        _ => "unknown",
+    }
+}
+
+fn optional_pubkey(pubkey: &Pubkey) -> Option<String> {
+    if *pubkey == Pubkey::default() {
+        None
+    } else {
+        Some(pubkey.to_string())
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{RefundStep, WaitlistEntry};
+    use borsh::BorshSerialize;
+    use serde_json::{json, Value};
+
+    fn json_of<T: BorshSerialize>(account: &T) -> Value {
+        let data = account.try_to_vec().unwrap();
+        serde_json::from_str(&to_json(&data).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn events_decode_to_json() {
+        let creator = Pubkey::new_unique();
+        let waitlist = Pubkey::new_unique();
+        let mut event = InitEvent::try_from_slice(&[0; InitEvent::LEN]).unwrap();
+        event.status = EventStatus::Active as u8;
+        event.event_id = 7;
+        event.event_creator = creator;
+        event.total_partcipator = 3;
+        event.tier_count = 1;
+        event.tiers[0] = EventTier {
+            price: 100,
+            capacity: 3,
+            sold: 1,
+            waitlist,
+        };
+        event.transfer_policy = TransferPolicy::RequiresCreator as u8;
+        event.start_time = 5_000;
+        event.refund_step_count = 1;
+        event.refund_schedule[0] = RefundStep {
+            seconds_before_start: 600,
+            refund_bps: 5_000,
+        };
+
+        let json = json_of(&event);
+        assert_eq!(json["type"], "event");
+        assert_eq!(json["status"], "active");
+        assert_eq!(json["event_id"], 7);
+        assert_eq!(json["event_creator"], creator.to_string());
+        assert_eq!(
+            json["tiers"],
+            json!([{ "price": 100, "capacity": 3, "sold": 1, "waitlist": waitlist.to_string() }])
+        );
+        assert_eq!(json["allowlist_root"], Value::Null);
+        assert_eq!(json["permit_signer"], Value::Null);
+        assert_eq!(json["transfer_policy"], "requires_creator");
+        assert_eq!(json["ticket_mint"], Value::Null);
+        assert_eq!(json["series"], Value::Null);
+        assert_eq!(json["start_time"], 5_000);
+        assert_eq!(
+            json["refund_schedule"],
+            json!([{ "seconds_before_start": 600, "refund_bps": 5_000 }])
+        );
+        assert_eq!(json["sponsor_pool_destination"], "payee");
+        assert_eq!(json["prize_table"], json!([]));
+        assert_eq!(json["lottery_seed"], Value::Null);
+        assert_eq!(json["checkin_commitment"], Value::Null);
+    }
+
+    #[test]
+    fn participants_decode_to_json() {
+        let event = Pubkey::new_unique();
+        let wallet = Pubkey::new_unique();
+        let mut participant = Participant::try_from_slice(&[0; Participant::LEN]).unwrap();
+        participant.status = ParticipantStatus::Waiting as u8;
+        participant.event = event;
+        participant.participant = wallet;
+        participant.tier = 1;
+
+        let json = json_of(&participant);
+        assert_eq!(json["type"], "participant");
+        assert_eq!(json["status"], "waiting");
+        assert_eq!(json["event"], event.to_string());
+        assert_eq!(json["participant"], wallet.to_string());
+        assert_eq!(json["tier"], 1);
+        assert_eq!(json["ticket_account"], Value::Null);
+        assert_eq!(json["payer"], Value::Null);
+        assert_eq!(json["checkin_commitment"], Value::Null);
+        assert_eq!(json["attended"], false);
+    }
+
+    #[test]
+    fn waitlists_decode_to_json() {
+        let wallet = Pubkey::new_unique();
+        let participant_account = Pubkey::new_unique();
+        let mut waitlist = Waitlist::try_from_slice(&[0; Waitlist::LEN]).unwrap();
+        waitlist.status = WaitlistStatus::Active as u8;
+        waitlist.push(WaitlistEntry {
+            wallet,
+            deposit: 100,
+            participant_account,
+        });
+
+        let json = json_of(&waitlist);
+        assert_eq!(json["type"], "waitlist");
+        assert_eq!(json["status"], "active");
+        assert_eq!(
+            json["entries"],
+            json!([{
+                "wallet": wallet.to_string(),
+                "deposit": 100,
+                "participant_account": participant_account.to_string(),
+            }])
+        );
+    }
+
+    #[test]
+    fn series_decode_to_json() {
+        let creator = Pubkey::new_unique();
+        let organizer = Pubkey::new_unique();
+        let mut series = EventSeries::try_from_slice(&[0; EventSeries::LEN]).unwrap();
+        series.status = SeriesStatus::Active as u8;
+        series.series_creator = creator;
+        series.organizer_count = 1;
+        series.organizers[0] = organizer;
+        series.first_start = 1_000;
+        series.interval = 604_800;
+
+        let json = json_of(&series);
+        assert_eq!(json["type"], "series");
+        assert_eq!(json["status"], "active");
+        assert_eq!(json["series_creator"], creator.to_string());
+        assert_eq!(json["organizers"], json!([organizer.to_string()]));
+        assert_eq!(json["first_start"], 1_000);
+        assert_eq!(json["interval"], 604_800);
+    }
+
+    #[test]
+    fn sponsorships_decode_to_json() {
+        let event = Pubkey::new_unique();
+        let sponsor = Pubkey::new_unique();
+        let mut sponsorship = Sponsorship::try_from_slice(&[0; Sponsorship::LEN]).unwrap();
+        sponsorship.status = SponsorshipStatus::Active as u8;
+        sponsorship.event = event;
+        sponsorship.sponsor = sponsor;
+        sponsorship.amount = 500;
+
+        let json = json_of(&sponsorship);
+        assert_eq!(json["type"], "sponsorship");
+        assert_eq!(json["status"], "active");
+        assert_eq!(json["event"], event.to_string());
+        assert_eq!(json["sponsor"], sponsor.to_string());
+        assert_eq!(json["amount"], 500);
+    }
+
+    #[test]
+    fn layouts_are_detected_by_data_length() {
+        assert!(matches!(
+            decode(&[0; InitEvent::LEN]).unwrap(),
+            DecodedAccount::Event(_)
+        ));
+        assert!(matches!(
+            decode(&[0; Participant::LEN]).unwrap(),
+            DecodedAccount::Participant(_)
+        ));
+        assert!(matches!(
+            decode(&[0; Waitlist::LEN]).unwrap(),
+            DecodedAccount::Waitlist(_)
+        ));
+        assert!(matches!(
+            decode(&[0; EventSeries::LEN]).unwrap(),
+            DecodedAccount::Series(_)
+        ));
+        assert!(matches!(
+            decode(&[0; Sponsorship::LEN]).unwrap(),
+            DecodedAccount::Sponsorship(_)
+        ));
+        assert!(matches!(
+            decode(&[0; InitEvent::LEN - 1]),
+            Err(DecodeError::UnknownLayout(len)) if len == InitEvent::LEN - 1
+        ));
+    }
+}
@@ -15,6 +15,230 @@ pub enum RentShareError {
     /// Rent agreement already terminated
     #[error("Rent Agreement Terminated")]
     RentAgreementTerminated,
+
+    /// Signer or instruction argument doesn't match the event creator
+    #[error("Event Creator Mismatch")]
+    EventCreatorMismatch,
+
+    /// Instruction event id doesn't match the event account
+    #[error("Event Id Mismatch")]
+    EventIdMismatch,
+
+    /// Event is not accepting registrations
+    #[error("Event Registration Closed")]
+    EventRegistrationClosed,
+
+    /// Event is not in the state required by the instruction
+    #[error("Invalid Event Status")]
+    InvalidEventStatus,
+
+    /// Ticket tier index out of range or tier configuration invalid
+    #[error("Invalid Ticket Tier")]
+    InvalidTicketTier,
+
+    /// Ticket tier has no remaining capacity
+    #[error("Ticket Tier Sold Out")]
+    TicketTierSoldOut,
+
+    /// Event reached `total_partcipator`
+    #[error("Event Full")]
+    EventFull,
+
+    /// Deposit doesn't match the price of the ticket tier
+    #[error("Deposit Amount Mismatch")]
+    DepositAmountMismatch,
+
+    /// Participant account doesn't belong to the event or wallet
+    #[error("Participant Mismatch")]
+    ParticipantMismatch,
+
+    /// Signer is not part of the event allowlist
+    #[error("Not On Allowlist")]
+    NotOnAllowlist,
+
+    /// Join permit missing, malformed or not signed by the permit signer
+    #[error("Invalid Permit")]
+    InvalidPermit,
+
+    /// Join permit expiry has passed
+    #[error("Permit Expired")]
+    PermitExpired,
+
+    /// Join permit nonce was already used for this event
+    #[error("Permit Already Used")]
+    PermitAlreadyUsed,
+
+    /// Waitlist account doesn't match the one registered for the ticket tier
+    #[error("Waitlist Mismatch")]
+    WaitlistMismatch,
+
+    /// Waitlist has no room for another wallet
+    #[error("Waitlist Full")]
+    WaitlistFull,
+
+    /// Wallet is already queued on the waitlist
+    #[error("Already On Waitlist")]
+    AlreadyOnWaitlist,
+
+    /// Wallet is not queued on the waitlist
+    #[error("Not On Waitlist")]
+    NotOnWaitlist,
+
+    /// Seats are still available, join the event directly
+    #[error("Seats Still Available")]
+    SeatsStillAvailable,
+
+    /// Event creator disabled seat transfers, or the event requires join permits
+    #[error("Transfers Disabled")]
+    TransfersDisabled,
+
+    /// Token account isn't a ticket account of the event mint held by the participant
+    #[error("Invalid Ticket Account")]
+    InvalidTicketAccount,
+
+    /// Event doesn't issue tickets, or tickets can't be frozen
+    #[error("Tickets Not Enabled")]
+    TicketsNotEnabled,
+
+    /// Participant already holds a ticket
+    #[error("Ticket Already Issued")]
+    TicketAlreadyIssued,
+
+    /// Participant wallet holds no ticket of the event to burn or freeze
+    #[error("Ticket Not Held")]
+    TicketNotHeld,
+
+    /// Instruction arguments don't match the event series account
+    #[error("Event Series Mismatch")]
+    EventSeriesMismatch,
+
+    /// Signer is neither the series creator nor one of its organizers
+    #[error("Not A Series Organizer")]
+    NotSeriesOrganizer,
+
+    /// All occurrences of the series were already instantiated
+    #[error("Event Series Finished")]
+    EventSeriesFinished,
+
+    /// Recurrence interval must be positive, with occurrence starts fitting in a timestamp
+    #[error("Invalid Recurrence")]
+    InvalidRecurrence,
+
+    /// Refund steps must be ordered by decreasing time before a scheduled start and refund at most the deposit
+    #[error("Invalid Refund Schedule")]
+    InvalidRefundSchedule,
+
+    /// Event has no dispute window, or it closed already
+    #[error("Dispute Window Closed")]
+    DisputeWindowClosed,
+
+    /// Event can't be settled before its dispute window closes
+    #[error("Dispute Window Open")]
+    DisputeWindowOpen,
+
+    /// Event was already disputed
+    #[error("Dispute Already Opened")]
+    DisputeAlreadyOpened,
+
+    /// Event has no open dispute
+    #[error("No Open Dispute")]
+    NoOpenDispute,
+
+    /// Event can't be settled while a dispute is open
+    #[error("Dispute Unresolved")]
+    DisputeUnresolved,
+
+    /// Signer is not the event arbiter
+    #[error("Arbiter Mismatch")]
+    ArbiterMismatch,
+
+    /// Participant has no dispute refund left to claim
+    #[error("Nothing To Claim")]
+    NothingToClaim,
+
+    /// Event doesn't take confirmations, or its confirmation window closed
+    #[error("Confirmation Closed")]
+    ConfirmationClosed,
+
+    /// Participant already confirmed the event
+    #[error("Already Confirmed")]
+    AlreadyConfirmed,
+
+    /// Confirmation quorum isn't reached yet and the confirmation window is still open
+    #[error("Confirmation Pending")]
+    ConfirmationPending,
+
+    /// Payee shares exceed the maximum, don't sum to 10000 bps or don't start with the payee
+    #[error("Invalid Payee Split")]
+    InvalidPayeeSplit,
+
+    /// Event escrow is still vesting to the payees
+    #[error("Vesting Incomplete")]
+    VestingIncomplete,
+
+    /// Group registration isn't available for events with an allowlist, join permits or tickets
+    #[error("Group Registration Unsupported")]
+    GroupRegistrationUnsupported,
+
+    /// Sponsorship account belongs to another event or sponsor
+    #[error("Sponsorship Mismatch")]
+    SponsorshipMismatch,
+
+    /// Prize table has too many ranks or doesn't sum to 10000 bps
+    #[error("Invalid Prize Table")]
+    InvalidPrizeTable,
+
+    /// Results of the competition were already submitted
+    #[error("Results Already Submitted")]
+    ResultsAlreadySubmitted,
+
+    /// Results of the competition were due before the results deadline
+    #[error("Results Deadline Passed")]
+    ResultsDeadlinePassed,
+
+    /// Funding of the event is still open until its registration deadline
+    #[error("Funding Open")]
+    FundingOpen,
+
+    /// Deposits didn't reach the funding goal by the registration deadline
+    #[error("Funding Goal Not Met")]
+    FundingGoalNotMet,
+
+    /// Price steps must be ordered by increasing start, on an event with a single fixed price tier
+    #[error("Invalid Price Schedule")]
+    InvalidPriceSchedule,
+
+    /// Waitlists, group registration and tickets aren't available for lottery events
+    #[error("Lottery Unsupported")]
+    LotteryUnsupported,
+
+    /// Lottery isn't drawn yet, or some of its entries are unresolved
+    #[error("Lottery Pending")]
+    LotteryPending,
+
+    /// Lottery of the event was already drawn
+    #[error("Lottery Already Drawn")]
+    LotteryAlreadyDrawn,
+
+    /// Hash of the lottery draw slot is no longer in `SlotHashes`, the draw has to be committed again
+    #[error("Lottery Draw Expired")]
+    LotteryDrawExpired,
+
+    /// Event check-in isn't open, or its window closed
+    #[error("Check-In Closed")]
+    CheckInClosed,
+
+    /// Check-in code doesn't match the commitment of the event
+    #[error("Invalid Check-In Code")]
+    InvalidCheckInCode,
+
+    /// Participant already checked in
+    #[error("Already Checked In")]
+    AlreadyCheckedIn,
+
+    /// Check-in codes are only revealed once the check-in window closed
+    #[error("Check-In Open")]
+    CheckInOpen,
 }
 
 impl From<RentShareError> for ProgramError {
@@ -0,0 +1,633 @@
+//! Structured records logged for indexers.
+//!
+//! Every state change logs one Borsh-encoded [`EventRecord`] through `sol_log_data`,
+//! which shows up as a base64 `Program data:` log line. The first byte of the
+//! record is the variant index and identifies the record type.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{entrypoint::ProgramResult, log::sol_log_data, pubkey::Pubkey};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+pub enum EventRecord {
+    EventCreated {
+        event: Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+        payee: Pubkey,
+        total_partcipator: u64,
+        tier_count: u8,
+    },
+    ParticipantJoined {
+        event: Pubkey,
+        participant: Pubkey,
+        participant_account: Pubkey,
+        tier: u8,
+        deposit: u64,
+    },
+    Withdrawn {
+        event: Pubkey,
+        participant: Pubkey,
+        participant_account: Pubkey,
+    },
+    Started {
+        event: Pubkey,
+    },
+    Ended {
+        event: Pubkey,
+    },
+    Cancelled {
+        event: Pubkey,
+    },
+    Refunded {
+        event: Pubkey,
+        recipient: Pubkey,
+        amount: u64,
+    },
+    PaidOut {
+        event: Pubkey,
+        payee: Pubkey,
+        amount: u64,
+    },
+    ParticipantRemoved {
+        event: Pubkey,
+        participant: Pubkey,
+        participant_account: Pubkey,
+    },
+    ParticipationTransferred {
+        event: Pubkey,
+        participant_account: Pubkey,
+        from: Pubkey,
+        to: Pubkey,
+    },
+    WaitlistCreated {
+        event: Pubkey,
+        waitlist: Pubkey,
+        tier: u8,
+    },
+    WaitlistJoined {
+        event: Pubkey,
+        waitlist: Pubkey,
+        wallet: Pubkey,
+        deposit: u64,
+    },
+    WaitlistLeft {
+        event: Pubkey,
+        waitlist: Pubkey,
+        wallet: Pubkey,
+    },
+    SeatPromoted {
+        event: Pubkey,
+        participant_account: Pubkey,
+        participant: Pubkey,
+        deposit: u64,
+    },
+    AllowlistUpdated {
+        event: Pubkey,
+        allowlist_root: [u8; 32],
+    },
+    TransferPolicyUpdated {
+        event: Pubkey,
+        transfer_policy: u8,
+    },
+    TicketMintCreated {
+        event: Pubkey,
+        ticket_mint: Pubkey,
+    },
+    TicketIssued {
+        event: Pubkey,
+        participant: Pubkey,
+        ticket_account: Pubkey,
+    },
+    TicketFrozen {
+        event: Pubkey,
+        ticket_account: Pubkey,
+    },
+    SeriesCreated {
+        series: Pubkey,
+        series_creator: Pubkey,
+        first_event_id: u64,
+        first_start: i64,
+        interval: i64,
+    },
+    OccurrenceCreated {
+        series: Pubkey,
+        event: Pubkey,
+        event_id: u64,
+        occurrence: u64,
+        start_time: i64,
+    },
+    /// Part of a withdrawn deposit kept for the payee by the event refund schedule
+    RefundRetained {
+        event: Pubkey,
+        participant: Pubkey,
+        payee: Pubkey,
+        amount: u64,
+    },
+    BondPosted {
+        event: Pubkey,
+        event_creator: Pubkey,
+        amount: u64,
+    },
+    /// Creator bond split among the seats of a cancelled event
+    BondSlashed {
+        event: Pubkey,
+        participant_count: u64,
+        bond_share: u64,
+    },
+    BondCompensation {
+        event: Pubkey,
+        recipient: Pubkey,
+        amount: u64,
+    },
+    BondReturned {
+        event: Pubkey,
+        event_creator: Pubkey,
+        amount: u64,
+    },
+    DisputeWindowOpened {
+        event: Pubkey,
+        dispute_deadline: i64,
+    },
+    DisputeOpened {
+        event: Pubkey,
+        disputant: Pubkey,
+    },
+    DisputeResolved {
+        event: Pubkey,
+        arbiter: Pubkey,
+        refund_bps: u16,
+    },
+    Settled {
+        event: Pubkey,
+    },
+    ConfirmationWindowOpened {
+        event: Pubkey,
+        confirmation_deadline: i64,
+    },
+    ParticipantConfirmed {
+        event: Pubkey,
+        participant: Pubkey,
+        confirmations: u64,
+    },
+    /// Confirmation window closed without quorum, deposits are refundable
+    ConfirmationFailed {
+        event: Pubkey,
+        confirmations: u64,
+    },
+    VestingStarted {
+        event: Pubkey,
+        vesting_total: u64,
+        vesting_start: i64,
+    },
+    VestedClaimed {
+        event: Pubkey,
+        amount: u64,
+        vested_claimed: u64,
+    },
+    GroupJoined {
+        event: Pubkey,
+        payer: Pubkey,
+        tier: u8,
+        seats: u64,
+        deposit: u64,
+    },
+    Sponsored {
+        event: Pubkey,
+        sponsor: Pubkey,
+        amount: u64,
+        sponsor_pool: u64,
+    },
+    SponsorPoolDestinationUpdated {
+        event: Pubkey,
+        destination: u8,
+    },
+    SponsorPoolDistributed {
+        event: Pubkey,
+        destination: u8,
+        amount: u64,
+        sponsor_share: u64,
+    },
+    SponsorShareClaimed {
+        event: Pubkey,
+        participant: Pubkey,
+        amount: u64,
+    },
+    SponsorshipRefunded {
+        event: Pubkey,
+        sponsor: Pubkey,
+        amount: u64,
+    },
+    ResultsSubmitted {
+        event: Pubkey,
+        winners: u8,
+        prize_pool: u64,
+    },
+    ParticipantRanked {
+        event: Pubkey,
+        participant: Pubkey,
+        participant_account: Pubkey,
+        rank: u8,
+    },
+    PrizeClaimed {
+        event: Pubkey,
+        participant: Pubkey,
+        rank: u8,
+        amount: u64,
+    },
+    LotteryEntered {
+        event: Pubkey,
+        participant: Pubkey,
+        participant_account: Pubkey,
+        entry: u64,
+        deposit: u64,
+    },
+    LotteryDrawn {
+        event: Pubkey,
+        entries: u64,
+        winners: u64,
+        seed: [u8; 32],
+    },
+    LotteryEntryResolved {
+        event: Pubkey,
+        participant: Pubkey,
+        participant_account: Pubkey,
+        won: bool,
+    },
+    LotteryDrawCommitted {
+        event: Pubkey,
+        draw_slot: u64,
+    },
+    CheckInOpened {
+        event: Pubkey,
+        checkin_deadline: i64,
+    },
+    CheckedIn {
+        event: Pubkey,
+        participant: Pubkey,
+        participant_account: Pubkey,
+        attendance: u64,
+    },
+    CheckInCommitted {
+        event: Pubkey,
+        participant: Pubkey,
+        participant_account: Pubkey,
+    },
+}
+
+impl EventRecord {
+    pub fn emit(&self) -> ProgramResult {
+        sol_log_data(&[&self.try_to_vec()?]);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    /// One record of every variant, paired with the tag indexers match on
+    fn records() -> Vec<(u8, EventRecord)> {
+        let records = vec![
+            EventRecord::EventCreated {
+                event: key(1),
+                event_id: 7,
+                event_creator: key(2),
+                payee: key(3),
+                total_partcipator: 10,
+                tier_count: 2,
+            },
+            EventRecord::ParticipantJoined {
+                event: key(1),
+                participant: key(4),
+                participant_account: key(5),
+                tier: 1,
+                deposit: 500,
+            },
+            EventRecord::Withdrawn {
+                event: key(1),
+                participant: key(4),
+                participant_account: key(5),
+            },
+            EventRecord::Started { event: key(1) },
+            EventRecord::Ended { event: key(1) },
+            EventRecord::Cancelled { event: key(1) },
+            EventRecord::Refunded {
+                event: key(1),
+                recipient: key(4),
+                amount: 500,
+            },
+            EventRecord::PaidOut {
+                event: key(1),
+                payee: key(3),
+                amount: 5_000,
+            },
+            EventRecord::ParticipantRemoved {
+                event: key(1),
+                participant: key(4),
+                participant_account: key(5),
+            },
+            EventRecord::ParticipationTransferred {
+                event: key(1),
+                participant_account: key(5),
+                from: key(4),
+                to: key(6),
+            },
+            EventRecord::WaitlistCreated {
+                event: key(1),
+                waitlist: key(7),
+                tier: 0,
+            },
+            EventRecord::WaitlistJoined {
+                event: key(1),
+                waitlist: key(7),
+                wallet: key(6),
+                deposit: 500,
+            },
+            EventRecord::WaitlistLeft {
+                event: key(1),
+                waitlist: key(7),
+                wallet: key(6),
+            },
+            EventRecord::SeatPromoted {
+                event: key(1),
+                participant_account: key(8),
+                participant: key(6),
+                deposit: 500,
+            },
+            EventRecord::AllowlistUpdated {
+                event: key(1),
+                allowlist_root: [9; 32],
+            },
+            EventRecord::TransferPolicyUpdated {
+                event: key(1),
+                transfer_policy: 1,
+            },
+            EventRecord::TicketMintCreated {
+                event: key(1),
+                ticket_mint: key(10),
+            },
+            EventRecord::TicketIssued {
+                event: key(1),
+                participant: key(4),
+                ticket_account: key(11),
+            },
+            EventRecord::TicketFrozen {
+                event: key(1),
+                ticket_account: key(11),
+            },
+            EventRecord::SeriesCreated {
+                series: key(12),
+                series_creator: key(2),
+                first_event_id: 7,
+                first_start: 1_000,
+                interval: 604_800,
+            },
+            EventRecord::OccurrenceCreated {
+                series: key(12),
+                event: key(1),
+                event_id: 8,
+                occurrence: 1,
+                start_time: 605_800,
+            },
+            EventRecord::RefundRetained {
+                event: key(1),
+                participant: key(4),
+                payee: key(3),
+                amount: 250,
+            },
+            EventRecord::BondPosted {
+                event: key(1),
+                event_creator: key(2),
+                amount: 1_000,
+            },
+            EventRecord::BondSlashed {
+                event: key(1),
+                participant_count: 4,
+                bond_share: 250,
+            },
+            EventRecord::BondCompensation {
+                event: key(1),
+                recipient: key(4),
+                amount: 250,
+            },
+            EventRecord::BondReturned {
+                event: key(1),
+                event_creator: key(2),
+                amount: 1_000,
+            },
+            EventRecord::DisputeWindowOpened {
+                event: key(1),
+                dispute_deadline: 2_000,
+            },
+            EventRecord::DisputeOpened {
+                event: key(1),
+                disputant: key(4),
+            },
+            EventRecord::DisputeResolved {
+                event: key(1),
+                arbiter: key(13),
+                refund_bps: 5_000,
+            },
+            EventRecord::Settled { event: key(1) },
+            EventRecord::ConfirmationWindowOpened {
+                event: key(1),
+                confirmation_deadline: 2_000,
+            },
+            EventRecord::ParticipantConfirmed {
+                event: key(1),
+                participant: key(4),
+                confirmations: 1,
+            },
+            EventRecord::ConfirmationFailed {
+                event: key(1),
+                confirmations: 1,
+            },
+            EventRecord::VestingStarted {
+                event: key(1),
+                vesting_total: 1_000,
+                vesting_start: 1_000,
+            },
+            EventRecord::VestedClaimed {
+                event: key(1),
+                amount: 250,
+                vested_claimed: 250,
+            },
+            EventRecord::GroupJoined {
+                event: key(1),
+                payer: key(2),
+                tier: 0,
+                seats: 2,
+                deposit: 200,
+            },
+            EventRecord::Sponsored {
+                event: key(1),
+                sponsor: key(2),
+                amount: 500,
+                sponsor_pool: 500,
+            },
+            EventRecord::SponsorPoolDestinationUpdated {
+                event: key(1),
+                destination: 1,
+            },
+            EventRecord::SponsorPoolDistributed {
+                event: key(1),
+                destination: 1,
+                amount: 500,
+                sponsor_share: 250,
+            },
+            EventRecord::SponsorShareClaimed {
+                event: key(1),
+                participant: key(3),
+                amount: 250,
+            },
+            EventRecord::SponsorshipRefunded {
+                event: key(1),
+                sponsor: key(2),
+                amount: 500,
+            },
+            EventRecord::ResultsSubmitted {
+                event: key(1),
+                winners: 2,
+                prize_pool: 500,
+            },
+            EventRecord::ParticipantRanked {
+                event: key(1),
+                participant: key(3),
+                participant_account: key(4),
+                rank: 1,
+            },
+            EventRecord::PrizeClaimed {
+                event: key(1),
+                participant: key(3),
+                rank: 1,
+                amount: 350,
+            },
+            EventRecord::LotteryEntered {
+                event: key(1),
+                participant: key(3),
+                participant_account: key(4),
+                entry: 0,
+                deposit: 100,
+            },
+            EventRecord::LotteryDrawn {
+                event: key(1),
+                entries: 3,
+                winners: 2,
+                seed: [9; 32],
+            },
+            EventRecord::LotteryEntryResolved {
+                event: key(1),
+                participant: key(3),
+                participant_account: key(4),
+                won: true,
+            },
+            EventRecord::LotteryDrawCommitted {
+                event: key(1),
+                draw_slot: 116,
+            },
+            EventRecord::CheckInOpened {
+                event: key(1),
+                checkin_deadline: 8_200,
+            },
+            EventRecord::CheckedIn {
+                event: key(1),
+                participant: key(3),
+                participant_account: key(4),
+                attendance: 1,
+            },
+            EventRecord::CheckInCommitted {
+                event: key(1),
+                participant: key(3),
+                participant_account: key(4),
+            },
+        ];
+
+        records
+            .into_iter()
+            .map(|record| (tag(&record), record))
+            .collect()
+    }
+
+    /// Tags are part of the log format, so a new variant must only ever be appended
+    fn tag(record: &EventRecord) -> u8 {
+        match record {
+            EventRecord::EventCreated { .. } => 0,
+            EventRecord::ParticipantJoined { .. } => 1,
+            EventRecord::Withdrawn { .. } => 2,
+            EventRecord::Started { .. } => 3,
+            EventRecord::Ended { .. } => 4,
+            EventRecord::Cancelled { .. } => 5,
+            EventRecord::Refunded { .. } => 6,
+            EventRecord::PaidOut { .. } => 7,
+            EventRecord::ParticipantRemoved { .. } => 8,
+            EventRecord::ParticipationTransferred { .. } => 9,
+            EventRecord::WaitlistCreated { .. } => 10,
+            EventRecord::WaitlistJoined { .. } => 11,
+            EventRecord::WaitlistLeft { .. } => 12,
+            EventRecord::SeatPromoted { .. } => 13,
+            EventRecord::AllowlistUpdated { .. } => 14,
+            EventRecord::TransferPolicyUpdated { .. } => 15,
+            EventRecord::TicketMintCreated { .. } => 16,
+            EventRecord::TicketIssued { .. } => 17,
+            EventRecord::TicketFrozen { .. } => 18,
+            EventRecord::SeriesCreated { .. } => 19,
+            EventRecord::OccurrenceCreated { .. } => 20,
+            EventRecord::RefundRetained { .. } => 21,
+            EventRecord::BondPosted { .. } => 22,
+            EventRecord::BondSlashed { .. } => 23,
+            EventRecord::BondCompensation { .. } => 24,
+            EventRecord::BondReturned { .. } => 25,
+            EventRecord::DisputeWindowOpened { .. } => 26,
+            EventRecord::DisputeOpened { .. } => 27,
+            EventRecord::DisputeResolved { .. } => 28,
+            EventRecord::Settled { .. } => 29,
+            EventRecord::ConfirmationWindowOpened { .. } => 30,
+            EventRecord::ParticipantConfirmed { .. } => 31,
+            EventRecord::ConfirmationFailed { .. } => 32,
+            EventRecord::VestingStarted { .. } => 33,
+            EventRecord::VestedClaimed { .. } => 34,
+            EventRecord::GroupJoined { .. } => 35,
+            EventRecord::Sponsored { .. } => 36,
+            EventRecord::SponsorPoolDestinationUpdated { .. } => 37,
+            EventRecord::SponsorPoolDistributed { .. } => 38,
+            EventRecord::SponsorShareClaimed { .. } => 39,
+            EventRecord::SponsorshipRefunded { .. } => 40,
+            EventRecord::ResultsSubmitted { .. } => 41,
+            EventRecord::ParticipantRanked { .. } => 42,
+            EventRecord::PrizeClaimed { .. } => 43,
+            EventRecord::LotteryEntered { .. } => 44,
+            EventRecord::LotteryDrawn { .. } => 45,
+            EventRecord::LotteryEntryResolved { .. } => 46,
+            EventRecord::LotteryDrawCommitted { .. } => 47,
+            EventRecord::CheckInOpened { .. } => 48,
+            EventRecord::CheckedIn { .. } => 49,
+            EventRecord::CheckInCommitted { .. } => 50,
+        }
+    }
+
+    #[test]
+    fn every_record_round_trips_through_borsh() {
+        for (_, record) in records() {
+            let bytes = record.try_to_vec().unwrap();
+            assert_eq!(EventRecord::try_from_slice(&bytes).unwrap(), record);
+        }
+    }
+
+    #[test]
+    fn records_start_with_their_fixed_tag() {
+        let records = records();
+        for (index, (tag, record)) in records.iter().enumerate() {
+            assert_eq!(
+                usize::from(*tag),
+                index,
+                "{:?} is listed out of order",
+                record
+            );
+            assert_eq!(record.try_to_vec().unwrap()[0], *tag);
+        }
+    }
+}
@@ -0,0 +1,415 @@
+//! JSON interface description (IDL) of the program for non-Rust clients.
+//!
+//! The IDL is generated from the Rust definitions themselves: [`generate`] reads
+//! `instruction.rs`, `state.rs` and `error.rs` as compiled into the crate, so it
+//! can't fall behind the code it describes. The checked-in `idl/rentshare.json`
+//! is kept in sync by the `idl` integration test; run it with `UPDATE_IDL=1` to
+//! rewrite the file after changing any of these definitions.
+//!
+//! Types are written as `"u8"`, `"u16"`, `"u32"`, `"u64"`, `"i64"`, `"bool"`, `"publicKey"`,
+//! `{"array": [type, len]}`, `{"vec": type}` and `{"defined": name}`, and Borsh
+//! encodes them the usual way: little-endian integers, a one byte enum tag and a
+//! `u32` length before vector items.
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+
+const INSTRUCTION_SOURCE: &str = include_str!("instruction.rs");
+const STATE_SOURCE: &str = include_str!("state.rs");
+const ERROR_SOURCE: &str = include_str!("error.rs");
+
+/// Build the IDL of the program
+pub fn generate() -> Value {
+    let consts = parse_consts(STATE_SOURCE);
+    let mut structs = parse_structs(INSTRUCTION_SOURCE, &consts);
+    structs.extend(parse_structs(STATE_SOURCE, &consts));
+    let enums = parse_enums(STATE_SOURCE);
+
+    let sizes = Sizes {
+        structs: &structs,
+        enums: &enums,
+    };
+    let accounts: Vec<Value> = structs
+        .iter()
+        .filter(|item| is_account(&item.name))
+        .map(|item| {
+            let mut offset = 0;
+            let fields: Vec<Value> = item
+                .fields
+                .iter()
+                .map(|field| {
+                    let value = field_json(field, Some(offset));
+                    offset += sizes
+                        .of(&field.ty)
+                        .expect("account fields have a fixed size");
+                    value
+                })
+                .collect();
+            json!({
+                "name": item.name,
+                "docs": item.docs,
+                "size": offset,
+                "fields": fields,
+            })
+        })
+        .collect();
+
+    let mut types: Vec<Value> = structs
+        .iter()
+        .filter(|item| !is_account(&item.name))
+        .map(|item| {
+            json!({
+                "name": item.name,
+                "docs": item.docs,
+                "kind": "struct",
+                "size": sizes.struct_size(item),
+                "fields": item.fields.iter().map(|field| field_json(field, None)).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    types.extend(enums.iter().map(|item| {
+        json!({
+            "name": item.name,
+            "docs": item.docs,
+            "kind": "enum",
+            "variants": item.variants.iter().map(|(name, value)| json!({
+                "name": name,
+                "value": value,
+            })).collect::<Vec<_>>(),
+        })
+    }));
+
+    json!({
+        "name": "rentshare",
+        "version": env!("CARGO_PKG_VERSION"),
+        "instructions": parse_instructions(INSTRUCTION_SOURCE, &consts),
+        "accounts": accounts,
+        "types": types,
+        "errors": parse_errors(ERROR_SOURCE),
+    })
+}
+
+/// Render the IDL as pretty-printed JSON, the format of `idl/rentshare.json`
+pub fn to_json() -> String {
+    let mut json = serde_json::to_string_pretty(&generate()).expect("IDL is valid JSON");
+    json.push('\n');
+    json
+}
+
+/// State structs stored in their own program accounts
+fn is_account(name: &str) -> bool {
+    matches!(
+        name,
+        "InitEvent" | "Participant" | "Waitlist" | "EventSeries" | "Sponsorship"
+    )
+}
+
+struct Field {
+    name: String,
+    docs: Vec<String>,
+    ty: Value,
+}
+
+struct StructItem {
+    name: String,
+    docs: Vec<String>,
+    fields: Vec<Field>,
+}
+
+struct EnumItem {
+    name: String,
+    docs: Vec<String>,
+    variants: Vec<(String, u64)>,
+}
+
+struct Sizes<'a> {
+    structs: &'a [StructItem],
+    enums: &'a [EnumItem],
+}
+
+impl Sizes<'_> {
+    /// Borsh size of a type, `None` when it varies
+    fn of(&self, ty: &Value) -> Option<usize> {
+        match ty {
+            Value::String(name) => match name.as_str() {
+                "u8" | "bool" => Some(1),
+                "u16" => Some(2),
+                "u32" => Some(4),
+                "u64" | "i64" => Some(8),
+                "publicKey" => Some(32),
+                _ => None,
+            },
+            Value::Object(map) => {
+                if let Some(array) = map.get("array") {
+                    Some(self.of(&array[0])? * array[1].as_u64()? as usize)
+                } else if let Some(name) = map.get("defined").and_then(Value::as_str) {
+                    if self.enums.iter().any(|item| item.name == name) {
+                        Some(1)
+                    } else {
+                        self.struct_size(self.structs.iter().find(|item| item.name == name)?)
+                    }
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn struct_size(&self, item: &StructItem) -> Option<usize> {
+        item.fields.iter().map(|field| self.of(&field.ty)).sum()
+    }
+}
+
+fn field_json(field: &Field, offset: Option<usize>) -> Value {
+    let mut value = json!({
+        "name": field.name,
+        "type": field.ty,
+    });
+    if !field.docs.is_empty() {
+        value["docs"] = json!(field.docs);
+    }
+    if let Some(offset) = offset {
+        value["offset"] = json!(offset);
+    }
+    value
+}
+
+/// Lines of the block opened by `header`, up to the closing brace at column 0
+fn block<'a>(source: &'a str, header: &str) -> Vec<&'a str> {
+    source
+        .lines()
+        .skip_while(|line| *line != header)
+        .skip(1)
+        .take_while(|line| *line != "}")
+        .collect()
+}
+
+/// Doc comment lines directly above line `index`
+fn docs_above(lines: &[&str], index: usize) -> Vec<String> {
+    let mut docs: Vec<String> = lines[..index]
+        .iter()
+        .rev()
+        .map(|line| line.trim())
+        .take_while(|line| line.starts_with("///") || line.starts_with("#["))
+        .filter_map(|line| line.strip_prefix("///"))
+        .map(|line| line.trim().to_string())
+        .collect();
+    docs.reverse();
+    docs
+}
+
+fn parse_consts(source: &str) -> BTreeMap<String, u64> {
+    source
+        .lines()
+        .filter_map(|line| line.strip_prefix("pub const "))
+        .filter_map(|line| {
+            let (name, rest) = line.split_once(':')?;
+            let value = rest.split_once('=')?.1.trim().trim_end_matches(';');
+            Some((name.trim().to_string(), value.parse().ok()?))
+        })
+        .collect()
+}
+
+fn parse_type(ty: &str, consts: &BTreeMap<String, u64>) -> Value {
+    let ty = ty.trim();
+    if let Some(inner) = ty.strip_prefix("Vec<").and_then(|ty| ty.strip_suffix('>')) {
+        json!({ "vec": parse_type(inner, consts) })
+    } else if let Some(inner) = ty.strip_prefix('[').and_then(|ty| ty.strip_suffix(']')) {
+        let (item, len) = inner.rsplit_once(';').expect("array type has a length");
+        let len = len.trim();
+        let len = consts
+            .get(len)
+            .copied()
+            .unwrap_or_else(|| len.parse().expect("array length is a literal or constant"));
+        json!({ "array": [parse_type(item, consts), len] })
+    } else {
+        match ty {
+            "u8" | "u16" | "u32" | "u64" | "i64" | "bool" => json!(ty),
+            "Pubkey" => json!("publicKey"),
+            name => json!({ "defined": name }),
+        }
+    }
+}
+
+/// `name: Type` field of a struct or enum variant, `None` for other lines
+fn parse_field(line: &str, consts: &BTreeMap<String, u64>) -> Option<(String, Value)> {
+    let line = line.trim();
+    if line.starts_with("//") || line.starts_with("#[") {
+        return None;
+    }
+    let line = line.strip_prefix("pub ").unwrap_or(line);
+    let (name, ty) = line.split_once(':')?;
+    Some((
+        name.trim().to_string(),
+        parse_type(ty.trim().trim_end_matches(','), consts),
+    ))
+}
+
+fn parse_structs(source: &str, consts: &BTreeMap<String, u64>) -> Vec<StructItem> {
+    let lines: Vec<&str> = source.lines().collect();
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let name = line.strip_prefix("pub struct ")?.strip_suffix('{')?.trim();
+            let body = block(source, line);
+            let fields = body
+                .iter()
+                .enumerate()
+                .filter_map(|(field_index, line)| {
+                    let (name, ty) = parse_field(line, consts)?;
+                    Some(Field {
+                        name,
+                        docs: docs_above(&body, field_index),
+                        ty,
+                    })
+                })
+                .collect();
+            Some(StructItem {
+                name: name.to_string(),
+                docs: docs_above(&lines, index),
+                fields,
+            })
+        })
+        .collect()
+}
+
+fn parse_enums(source: &str) -> Vec<EnumItem> {
+    let lines: Vec<&str> = source.lines().collect();
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let name = line.strip_prefix("pub enum ")?.strip_suffix('{')?.trim();
+            let mut next = 0;
+            let variants = block(source, line)
+                .iter()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty() && !line.starts_with("//"))
+                .map(|line| {
+                    let line = line.trim_end_matches(',');
+                    let (variant, value) = match line.split_once('=') {
+                        Some((variant, value)) => (
+                            variant.trim(),
+                            value.trim().parse().expect("enum value is a literal"),
+                        ),
+                        None => (line, next),
+                    };
+                    next = value + 1;
+                    (variant.to_string(), value)
+                })
+                .collect();
+            Some(EnumItem {
+                name: name.to_string(),
+                docs: docs_above(&lines, index),
+                variants,
+            })
+        })
+        .collect()
+}
+
+/// Account line of an "Accounts expected:" list, e.g. ``0. `[writable]` The event account``
+fn parse_account(line: &str) -> Option<Value> {
+    let (index, rest) = line.split_once(". `[")?;
+    let index: u64 = index.parse().ok()?;
+    let (flags, docs) = rest.split_once("]`")?;
+    let flags: Vec<&str> = flags.split(',').map(str::trim).collect();
+    let docs = docs.trim();
+    Some(json!({
+        "index": index,
+        "writable": flags.contains(&"writable"),
+        "signer": flags.contains(&"signer"),
+        "optional": docs.contains("only for") || docs.contains("only when"),
+        "docs": docs,
+    }))
+}
+
+fn parse_instructions(source: &str, consts: &BTreeMap<String, u64>) -> Vec<Value> {
+    let body = block(source, "pub enum Instruction {");
+    let mut instructions = Vec::new();
+    let mut current: Option<Value> = None;
+
+    for (index, line) in body.iter().enumerate() {
+        if let Some(name) = line
+            .strip_prefix("    ")
+            .filter(|line| !line.starts_with(' '))
+        {
+            if line.trim().starts_with("//") {
+                continue;
+            }
+            if name.starts_with('}') {
+                instructions.extend(current.take());
+            } else if let Some(name) = name.strip_suffix('{').or_else(|| name.strip_suffix(',')) {
+                let mut docs = Vec::new();
+                let mut accounts = Vec::new();
+                for doc in docs_above(&body, index) {
+                    match parse_account(&doc) {
+                        Some(account) => accounts.push(account),
+                        None if doc == "Accounts expected:" => {}
+                        None if doc.is_empty() && docs.is_empty() => {}
+                        None => docs.push(doc),
+                    }
+                }
+                while docs.last().is_some_and(String::is_empty) {
+                    docs.pop();
+                }
+                let instruction = json!({
+                    "name": name.trim(),
+                    "discriminant": instructions.len(),
+                    "docs": docs,
+                    "args": [],
+                    "accounts": accounts,
+                });
+                if line.trim_end().ends_with('{') {
+                    current = Some(instruction);
+                } else {
+                    instructions.push(instruction);
+                }
+            }
+        } else if let Some(instruction) = current.as_mut() {
+            if let Some((name, ty)) = parse_field(line, consts) {
+                instruction["args"]
+                    .as_array_mut()
+                    .expect("args is an array")
+                    .push(json!({ "name": name, "type": ty }));
+            }
+        }
+    }
+    instructions
+}
+
+fn parse_errors(source: &str) -> Vec<Value> {
+    let body = block(source, "pub enum RentShareError {");
+    let mut next = 0;
+    let mut message = String::new();
+    let mut errors = Vec::new();
+
+    for (index, line) in body.iter().enumerate() {
+        let line = line.trim();
+        if let Some(msg) = line
+            .strip_prefix("#[error(\"")
+            .and_then(|line| line.strip_suffix("\")]"))
+        {
+            message = msg.to_string();
+        } else if !line.is_empty() && !line.starts_with("//") && !line.starts_with("#[") {
+            let line = line.trim_end_matches(',');
+            let (name, code) = match line.split_once('=') {
+                Some((name, code)) => (
+                    name.trim(),
+                    code.trim().parse().expect("error code is a literal"),
+                ),
+                None => (line, next),
+            };
+            next = code + 1;
+            errors.push(json!({
+                "code": code,
+                "name": name,
+                "msg": message,
+                "docs": docs_above(&body, index),
+            }));
+        }
+    }
+    errors
+}
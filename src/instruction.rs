@@ -1,56 +1,614 @@
+use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{program_error::ProgramError, pubkey::Pubkey};
-use std::convert::TryInto;
 
-#[derive(Debug)]
+use crate::state::{
+    PayeeShare, PriceStep, RefundStep, SponsorPoolDestination, TransferPolicy, Vesting,
+};
+
+/// Price and capacity requested for one ticket tier at event initialization
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy)]
+pub struct TicketTier {
+    pub price: u64,
+    pub capacity: u64,
+}
+
+/// Program instructions, Borsh encoded with the variant index as the leading tag byte.
+/// Accounts marked "only for ..." are left out when they don't apply, shifting the
+/// following accounts down.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+#[allow(clippy::large_enum_variant)] // decoded once per instruction, boxing would change the public variants
 pub enum Instruction {
-    /// Initialize the rent contract with the agreed on terms and persist initial state in the agreement account
+    /// Initialize the event with the agreed on terms and persist initial state in the event account.
+    /// When `tiers` is empty a single tier priced at `fix_deposit_amount_per_person` with
+    /// `total_partcipator` seats is created, otherwise up to `MAX_EVENT_TIERS` tiers are stored.
+    /// A non-zero `allowlist_root` restricts participation to the wallets in that merkle tree,
+    /// and a non-default `permit_signer` requires a join permit signed by that key.
+    /// A `refund_schedule` of up to `MAX_REFUND_STEPS` steps, ordered by decreasing
+    /// `seconds_before_start`, cuts the refund of withdrawals closer to `start_time`.
+    /// A non-zero `bond` is escrowed from the creator: it compensates the participants if the
+    /// creator cancels the event and goes back to the creator once the event ends.
+    /// A positive `dispute_window` holds the payout for that many seconds after `EndEvent`,
+    /// letting participants dispute it before the `arbiter` key.
+    /// A non-zero `confirmation_quorum_bps` also holds it until that share of the seats confirms
+    /// the event took place; without quorum `confirmation_window` seconds after `EndEvent`,
+    /// deposits become refundable.
+    /// Non-empty `payees` split the payout by basis points summing to 10000, starting with
+    /// `payee_pubkey`, which also receives the rounding dust. Refunds retained by the refund
+    /// schedule are split the same way.
+    /// With `vesting`, the deposits escrowed at `StartEvent` are released to the payees over
+    /// `vesting_duration` seconds with `ClaimVested`, and the event can't end before that.
+    /// A non-empty `prize_table` of basis points summing to 10000 awards the sponsor pool by
+    /// placement, see `SubmitResults`, with results due `results_window` seconds after the payout.
+    /// Registration closes at a non-zero `registration_deadline`. With a `funding_goal`, the event
+    /// can only start after the deadline if the deposits reached the goal; otherwise every
+    /// deposit is refunded in full with `WithdrawFromEvent`.
+    /// A non-zero `max_deposit` lets each participant choose their deposit between `min_deposit`
+    /// and `max_deposit` instead of paying the tier price; tiers still cap the seats.
+    /// A `price_schedule` of up to `MAX_PRICE_STEPS` steps, ordered by increasing `starts_at`,
+    /// raises the price of a single tier event as it approaches; the tier price applies until
+    /// the first step starts.
+    /// A `lottery` event takes entries until `registration_deadline` instead of seating
+    /// participants as they join, see `CommitLotteryDraw`; it needs a single tier and no funding goal.
     ///
     /// Accounts expected:
-    /// 0. `[writable]` The Rent Agreement account created to manage state across 2 parties; owned by program id.
+    /// 0. `[writable]` The event account created to manage state; owned by program id.
     /// 1. `[]` Sysvar Rent Account to validate rent exemption (SYSVAR_RENT_PUBKEY)
+    /// 2. `[signer, writable]` Event creator account, only for events with a bond
+    /// 3. `[]` System program account, only for events with a bond
     InitializeEvent {
         payee_pubkey: Pubkey,
-        event_creator:Pubkey,
+        event_creator: Pubkey,
         event_id: u64,
         fix_deposit_amount_per_person: u64,
-        total_partcipator: u64
+        total_partcipator: u64,
+        tiers: Vec<TicketTier>,
+        allowlist_root: [u8; 32],
+        permit_signer: Pubkey,
+        start_time: i64,
+        refund_schedule: Vec<RefundStep>,
+        bond: u64,
+        arbiter: Pubkey,
+        dispute_window: i64,
+        confirmation_quorum_bps: u16,
+        confirmation_window: i64,
+        payees: Vec<PayeeShare>,
+        vesting: Vesting,
+        vesting_duration: i64,
+        prize_table: Vec<u16>,
+        results_window: i64,
+        funding_goal: u64,
+        registration_deadline: i64,
+        min_deposit: u64,
+        max_deposit: u64,
+        price_schedule: Vec<PriceStep>,
+        lottery: bool,
     },
-    CancelEvent{
-        event_creator:Pubkey,
-        event_id: u64
-
-    }
+    /// Cancel an event that has not been completed yet. The creator bond, if any, is split
+    /// evenly among the seats and paid out with their refunds; the creator only gets back the
+    /// remainder of the split, or the whole bond when nobody joined. Deposits are refunded
+    /// less the share already claimed by vested payees.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[signer, writable]` Event creator account, receiving what is left of the creator bond
+    CancelEvent {
+        event_creator: Pubkey,
+        event_id: u64,
+    },
+    /// Close registration and mark the event as started. Events with a funding goal only start
+    /// once their registration deadline passed with the goal reached, and lottery events once
+    /// every entry of the drawn lottery is resolved.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[signer]` Event creator account
     StartEvent {
         event_id: u64,
-        event_creator:Pubkey
-
+        event_creator: Pubkey,
     },
+    /// Complete a started event, paying the escrowed deposits, and the sponsor pool unless it goes
+    /// to the attendees, to the payee and returning the creator bond, if any. Events with a
+    /// dispute window or a confirmation quorum only open their windows, and are paid out by
+    /// `SettleEvent`. Vesting events can only end once fully vested.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[signer, writable]` Event creator account, receiving the creator bond back
+    /// 2. `[writable]` Payee account (public key)
+    /// 3. `[writable]` Remaining payee accounts in the order of `payees`, only for events splitting the payout
     EndEvent {
         event_id: u64,
-        event_creator:Pubkey
+        event_creator: Pubkey,
     },
+    /// Join an event in the given ticket tier, escrowing the tier price in the event account.
+    /// Invite-only events also require a merkle proof that the participant wallet is allowlisted.
+    /// Events with a permit signer require the preceding instruction to be an Ed25519 program
+    /// verification of a `JoinPermit` for this event and wallet.
+    /// Events issuing tickets mint one to the participant's token account.
+    /// Joining a lottery event enters its lottery, the deposit being escrowed until the draw.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[writable]` The participant account created for this seat; owned by program id.
+    /// 2. `[signer, writable]` Participant wallet paying the deposit
+    /// 3. `[]` System program account
+    /// 4. `[]` Sysvar Instructions account, only for events with a permit signer (SYSVAR_INSTRUCTIONS_PUBKEY)
+    /// 5. `[writable]` Permit nonce account, PDA of `["permit", event, nonce]`, only for events with a permit signer
+    /// 6. `[writable]` Ticket mint, only for events issuing tickets
+    /// 7. `[writable]` Participant ticket token account, only for events issuing tickets
+    /// 8. `[]` Ticket authority, PDA of `["tickets", event]`, only for events issuing tickets
+    /// 9. `[]` Token program, only for events issuing tickets
     ParticipateInEvent {
         event_id: u64,
         particpate_amount: u64,
-        event_creator:Pubkey
-
+        event_creator: Pubkey,
+        tier: u8,
+        allowlist_proof: Vec<[u8; 32]>,
+    },
+    /// Replace the allowlist merkle root while registration is still open; all zeros removes it
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[signer]` Event creator account
+    SetAllowlistRoot {
+        event_id: u64,
+        event_creator: Pubkey,
+        allowlist_root: [u8; 32],
+    },
+    /// Attach a waitlist to a ticket tier so freed seats are promoted to queued wallets
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[writable]` The waitlist account created for the tier; owned by program id.
+    /// 2. `[signer]` Event creator account
+    InitializeWaitlist {
+        event_id: u64,
+        event_creator: Pubkey,
+        tier: u8,
+    },
+    /// Queue for a sold out ticket tier, escrowing the deposit `ParticipateInEvent` would take in
+    /// the waitlist account. The participant account created for the seat is held on the waitlist
+    /// and takes the seat once the wallet is promoted.
+    /// The allowlist and permit checks of `ParticipateInEvent` apply.
+    ///
+    /// Accounts expected:
+    /// 0. `[]` The event account; owned by program id.
+    /// 1. `[writable]` The tier waitlist account; owned by program id.
+    /// 2. `[writable]` The participant account created for the seat; owned by program id.
+    /// 3. `[signer, writable]` Waiting wallet
+    /// 4. `[]` System program account
+    /// 5. `[]` Sysvar Instructions account, only for events with a permit signer (SYSVAR_INSTRUCTIONS_PUBKEY)
+    /// 6. `[writable]` Permit nonce account, PDA of `["permit", event, nonce]`, only for events with a permit signer
+    JoinWaitlist {
+        event_id: u64,
+        event_creator: Pubkey,
+        tier: u8,
+        deposit: u64,
+        allowlist_proof: Vec<[u8; 32]>,
+    },
+    /// Leave a waitlist at any time, refunding the escrowed deposit and closing the participant
+    /// account held on the waitlist
+    ///
+    /// Accounts expected:
+    /// 0. `[]` The event account; owned by program id.
+    /// 1. `[writable]` The tier waitlist account; owned by program id.
+    /// 2. `[writable]` The participant account held on the waitlist; owned by program id.
+    /// 3. `[signer, writable]` Waiting wallet
+    LeaveWaitlist {
+        event_id: u64,
+        event_creator: Pubkey,
+    },
+    /// Give up a seat while registration is open, or after the event is cancelled, refunding the deposit
+    /// and closing the participant account.
+    /// While registration is open the seat goes to the head of the tier waitlist, if any, and the
+    /// event refund schedule applies: the part of the deposit it doesn't refund goes to the payees.
+    /// Seats of cancelled events are always refunded in full, along with their share of the creator bond.
+    /// So are the seats of events that missed their funding goal, while events that reached it keep
+    /// the deposits once registration closed.
+    /// The participant's ticket, if issued, is burned from a token account of the ticket mint the
+    /// wallet holds, and the withdrawal fails while the wallet holds none.
+    /// The last seat of a cancelled event whose payees claimed vested funds also pays what the
+    /// rounding of the unvested refunds left in escrow to the payees.
+    /// Refunds and bond shares of seats paid by a group payer go to that payer.
+    /// Lottery entries are refunded by `ResolveLotteryEntry`, unless the event is cancelled.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[writable]` The participant account; owned by program id.
+    /// 2. `[signer, writable]` Participant wallet
+    /// 3. `[writable]` Ticket mint, only when a ticket was issued
+    /// 4. `[writable]` Token account of the ticket mint held by the participant wallet, only when a ticket was issued
+    /// 5. `[]` Ticket authority, PDA of `["tickets", event]`, only when a ticket was issued
+    /// 6. `[]` Token program, only when a ticket was issued
+    /// 7. `[writable]` Payee account, only for events with a refund schedule while registration is open, or for the last seat of a cancelled event whose payees claimed vested funds
+    /// 8. `[writable]` Remaining payee accounts in the order of `payees`, only along with the payee account for events splitting the payout
+    /// 9. `[writable]` Group payer account receiving the refund, only for seats paid by a group payer
+    /// 10. `[writable]` The tier waitlist account, only when the tier has a waitlist and registration is open
+    /// 11. `[writable]` Participant account held on the waitlist by its head, only when a wallet is promoted
+    WithdrawFromEvent {
+        event_id: u64,
+        event_creator: Pubkey,
     },
-    /// Pay rent from payee to payer
+    /// Remove a participant while registration is open, refunding their deposit, closing their
+    /// participant account and promoting the head of the tier waitlist, if any.
+    /// The participant's ticket, if issued, is frozen in the token account of the ticket mint the
+    /// wallet holds, and the removal fails while the wallet holds none.
     ///
     /// Accounts expected:
-    /// 0. `[writable]` The Rent Agreement account created to manage state across 2 parties; owned by program id.
-    /// 1. `[signer]` Payer (Renter) account (keypair)
-    /// 2. `[]` Payee (Owner) account (public key)
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[signer]` Event creator account
+    /// 2. `[writable]` The participant account; owned by program id.
+    /// 3. `[writable]` Participant wallet
+    /// 4. `[writable]` Ticket mint, only when a ticket was issued
+    /// 5. `[writable]` Token account of the ticket mint held by the participant wallet, only when a ticket was issued
+    /// 6. `[]` Ticket authority, PDA of `["tickets", event]`, only when a ticket was issued
+    /// 7. `[]` Token program, only when a ticket was issued
+    /// 8. `[writable]` Group payer account receiving the refund, only for seats paid by a group payer
+    /// 9. `[writable]` The tier waitlist account, only when the tier has a waitlist and registration is open
+    /// 10. `[writable]` Participant account held on the waitlist by its head, only when a wallet is promoted
+    RemoveParticipant {
+        event_id: u64,
+        event_creator: Pubkey,
+    },
+    /// Choose whether seats can be transferred freely, not at all, or only with the creator co-signing
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[signer]` Event creator account
+    SetTransferPolicy {
+        event_id: u64,
+        event_creator: Pubkey,
+        transfer_policy: TransferPolicy,
+    },
+    /// Hand a seat, and the right to its escrowed deposit, to another wallet.
+    /// Invite-only events require a merkle proof that the new wallet is allowlisted, and seats of
+    /// events requiring join permits can't be transferred.
+    /// The current ticket, if issued, is burned from a token account of the ticket mint the wallet
+    /// holds, failing while it holds none, and the new wallet claims its own with `ClaimTicket`.
+    /// Seats paid by a group payer also need its signature, and their refunds then go to the new wallet.
+    ///
+    /// Accounts expected:
+    /// 0. `[]` The event account; owned by program id.
+    /// 1. `[writable]` The participant account; owned by program id.
+    /// 2. `[signer]` Current participant wallet
+    /// 3. `[]` New participant wallet
+    /// 4. `[signer]` Event creator account, only when the transfer policy requires it
+    /// 5. `[writable]` Ticket mint, only when a ticket was issued
+    /// 6. `[writable]` Token account of the ticket mint held by the current wallet, only when a ticket was issued
+    /// 7. `[]` Ticket authority, PDA of `["tickets", event]`, only when a ticket was issued
+    /// 8. `[]` Token program, only when a ticket was issued
+    /// 9. `[signer]` Group payer of the seat, only for seats paid by a group payer
+    TransferParticipation {
+        event_id: u64,
+        event_creator: Pubkey,
+        allowlist_proof: Vec<[u8; 32]>,
+    },
+    /// Set up the SPL token mint issuing tickets before anyone joins the event. Tickets are
+    /// fungible: every participant receives one unit of this 0 decimals mint.
+    /// `freeze_tickets` lets tickets be frozen as attendance badges once the event ends.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[writable]` Uninitialized mint account; owned by the token program.
+    /// 2. `[signer]` Event creator account
+    /// 3. `[]` Sysvar Rent Account (SYSVAR_RENT_PUBKEY)
+    /// 4. `[]` Token program
+    InitializeTicketMint {
+        event_id: u64,
+        event_creator: Pubkey,
+        freeze_tickets: bool,
+    },
+    /// Mint a ticket for a paid seat that doesn't hold one, e.g. after a waitlist promotion or a transfer
+    ///
+    /// Accounts expected:
+    /// 0. `[]` The event account; owned by program id.
+    /// 1. `[writable]` The participant account; owned by program id.
+    /// 2. `[signer]` Participant wallet
+    /// 3. `[writable]` Ticket mint
+    /// 4. `[writable]` Participant ticket token account
+    /// 5. `[]` Ticket authority, PDA of `["tickets", event]`
+    /// 6. `[]` Token program
+    ClaimTicket {
+        event_id: u64,
+        event_creator: Pubkey,
+    },
+    /// Freeze a participant ticket as a non-transferable attendance badge after the event ended.
+    /// Anyone can send this instruction for events created with `freeze_tickets`.
+    ///
+    /// Accounts expected:
+    /// 0. `[]` The event account; owned by program id.
+    /// 1. `[]` The participant account; owned by program id.
+    /// 2. `[]` Ticket mint
+    /// 3. `[writable]` Participant ticket token account
+    /// 4. `[]` Ticket authority, PDA of `["tickets", event]`
+    /// 5. `[]` Token program
+    FreezeTicket {
+        event_id: u64,
+        event_creator: Pubkey,
+    },
+    /// Set up a recurring series whose occurrences share the payee, deposit and capacity.
+    /// Occurrence `n` starts `n * interval` seconds after `first_start` and gets the event id
+    /// `first_event_id + n`. `max_occurrences` of 0 lets the series recur indefinitely.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The series account created to manage the template; owned by program id.
+    /// 1. `[signer]` Series creator account
+    /// 2. `[]` Sysvar Rent Account to validate rent exemption (SYSVAR_RENT_PUBKEY)
+    InitializeEventSeries {
+        series_creator: Pubkey,
+        first_event_id: u64,
+        payee_pubkey: Pubkey,
+        fix_deposit_amount_per_person: u64,
+        total_partcipator: u64,
+        organizers: Vec<Pubkey>,
+        first_start: i64,
+        interval: i64,
+        max_occurrences: u64,
+    },
+    /// Create the event account of the next occurrence of a series from its template.
+    /// The signing organizer pays for the account and becomes the event creator.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The series account; owned by program id.
+    /// 1. `[writable]` Occurrence event account, PDA of `["occurrence", series, index]`
+    /// 2. `[signer, writable]` Series creator or organizer account
     /// 3. `[]` System program account
-    // PayRent { rent_amount: u64 },
-
-    /// Terminate agreement early, violating the terms
+    InstantiateOccurrence {
+        series_creator: Pubkey,
+        first_event_id: u64,
+    },
+    /// Dispute the payout of an ended event while its dispute window is open, holding the payout
+    /// until the arbiter resolves it. An event can only be disputed once.
     ///
     /// Accounts expected:
-    /// 0. `[writable]` The Rent Agreement account created to manage state across 2 parties; owned by program id.
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[]` The participant account; owned by program id.
+    /// 2. `[signer]` Participant wallet
+    OpenDispute {
+        event_id: u64,
+        event_creator: Pubkey,
+    },
+    /// Resolve the open dispute of an event, refunding `refund_bps` basis points of every
+    /// participant's deposit with `ClaimDisputeRefund` and paying the rest to the payee
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[signer]` Arbiter account
+    ResolveDispute {
+        event_id: u64,
+        event_creator: Pubkey,
+        refund_bps: u16,
+    },
+    /// Pay an ended event out once its dispute window closed without an unresolved dispute and
+    /// its confirmation quorum, if any, was reached, returning the creator bond, if any.
+    /// An event missing its quorum when the confirmation window closes is terminated instead:
+    /// deposits are refunded with `WithdrawFromEvent` and the bond is split as on cancellation.
+    /// Anyone can send this instruction.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[writable]` Payee account (public key)
+    /// 2. `[writable]` Event creator account, receiving the bond
+    /// 3. `[writable]` Remaining payee accounts in the order of `payees`, only for events splitting the payout
+    SettleEvent {
+        event_id: u64,
+        event_creator: Pubkey,
+    },
+    /// Claim the refund the arbiter granted to a participant when resolving a dispute
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[writable]` The participant account; owned by program id.
+    /// 2. `[signer, writable]` Participant wallet
+    /// 3. `[writable]` Group payer account receiving the refund, only for seats paid by a group payer
+    ClaimDisputeRefund {
+        event_id: u64,
+        event_creator: Pubkey,
+    },
+    /// Confirm, as a participant, that an ended event took place, while its confirmation window is open
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[writable]` The participant account; owned by program id.
+    /// 2. `[signer]` Participant wallet
+    ConfirmEvent {
+        event_id: u64,
+        event_creator: Pubkey,
+    },
+    /// Pay the payees the part of the escrow vested since the event started and not claimed yet.
+    /// Anyone can send this instruction.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[writable]` Payee account (public key)
+    /// 2. `[writable]` Remaining payee accounts in the order of `payees`, only for events splitting the payout
+    ClaimVested {
+        event_id: u64,
+        event_creator: Pubkey,
+    },
+    /// Register several participant wallets in the given ticket tier in one go, the payer
+    /// escrowing the tier price for each seat. The seats record the payer, and their refunds
+    /// go back to it. Not available for events with an allowlist, join permits or tickets.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[signer, writable]` Payer wallet paying the deposits
+    /// 2. `[]` System program account
+    /// 3. `[writable]` The participant account created for the first seat; owned by program id.
+    /// 4. `[]` Participant wallet of the first seat
+    /// 5. Participant account and wallet pairs of the remaining seats, in the same layout
+    BatchParticipateInEvent {
+        event_id: u64,
+        event_creator: Pubkey,
+        tier: u8,
+        particpate_amount: u64,
+    },
+    /// Contribute to the sponsor pool of an open or started event without taking a seat.
+    /// Repeated contributions of a sponsor add up in the same sponsorship account.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[writable]` The sponsorship account created by the sponsor; owned by program id.
+    /// 2. `[signer, writable]` Sponsor wallet paying the contribution
+    /// 3. `[]` System program account
+    Sponsor {
+        event_id: u64,
+        event_creator: Pubkey,
+        amount: u64,
+    },
+    /// Choose whether the sponsor pool goes to the payees or to the attendees when the event is paid out
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[signer]` Event creator account
+    SetSponsorPoolDestination {
+        event_id: u64,
+        event_creator: Pubkey,
+        destination: SponsorPoolDestination,
+    },
+    /// Claim the share of the sponsor pool of a paid out event that went to the attendees
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[writable]` The participant account; owned by program id.
+    /// 2. `[signer, writable]` Participant wallet
+    ClaimSponsorShare {
+        event_id: u64,
+        event_creator: Pubkey,
+    },
+    /// Refund a sponsor's contribution once the event was cancelled, or once a paid out
+    /// competition missed its results deadline, closing the sponsorship account
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[writable]` The sponsorship account; owned by program id.
+    /// 2. `[signer, writable]` Sponsor wallet
+    WithdrawSponsorship {
+        event_id: u64,
+        event_creator: Pubkey,
+    },
+    /// Rank the participants of a paid out competition by placement, passing their participant
+    /// accounts winner first. Prizes of ranks left without a participant, and the rounding
+    /// dust, go to the payees. Once the results deadline passed without results, the sponsors
+    /// get their contributions back with `WithdrawSponsorship` instead.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[signer]` Event creator account
+    /// 2. `[writable]` Payee account (public key)
+    /// 3. `[writable]` Remaining payee accounts in the order of `payees`, only for events splitting the payout
+    /// 4. `[writable]` Participant accounts in placement order, at most one per prize rank
+    SubmitResults {
+        event_id: u64,
+        event_creator: Pubkey,
+    },
+    /// Claim the prize of the participant's rank once the results are submitted
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[writable]` The participant account; owned by program id.
+    /// 2. `[signer, writable]` Participant wallet
+    ClaimPrize {
+        event_id: u64,
+        event_creator: Pubkey,
+    },
+    /// Draw the lottery of an event once its committed draw slot passed, seeding the winner
+    /// selection with the hash of the first slot produced at or after it, see `lottery`. Fails
+    /// with `LotteryDrawExpired` once `SlotHashes` no longer reaches back to the draw slot.
+    /// Anyone can send this instruction.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[]` Sysvar SlotHashes account (SYSVAR_SLOT_HASHES_PUBKEY)
+    DrawLottery {
+        event_id: u64,
+        event_creator: Pubkey,
+    },
+    /// Seat a lottery entry that won the draw, or refund the deposit of one that lost and close
+    /// its participant account. Every entry has to be resolved before the event starts.
+    /// Anyone can send this instruction.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[writable]` The participant account of the entry; owned by program id.
+    /// 2. `[writable]` Participant wallet
+    ResolveLotteryEntry {
+        event_id: u64,
+        event_creator: Pubkey,
+    },
+    /// Commit the lottery of an event to be drawn from the hash of a slot `DRAW_DELAY_SLOTS`
+    /// ahead, once its registration deadline passed. A draw can only be committed again once
+    /// `SlotHashes` no longer reaches back to its slot without it being drawn. Anyone can send
+    /// this instruction.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[]` Sysvar SlotHashes account (SYSVAR_SLOT_HASHES_PUBKEY), only for events whose draw was already committed
+    CommitLotteryDraw {
+        event_id: u64,
+        event_creator: Pubkey,
+    },
+    /// Commit to the check-in code of a started event, `checkin::commitment` of the event and
+    /// the code, opening self check-in for `checkin_window` seconds. The code is then announced
+    /// at the venue and participants check in with `CheckIn`, then `RevealCheckIn` once the
+    /// window closed.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[signer]` Event creator account
+    OpenCheckIn {
+        event_id: u64,
+        event_creator: Pubkey,
+        code_commitment: [u8; 32],
+        checkin_window: i64,
+    },
+    /// Check in while check-in is open by committing to the code announced at the venue with
+    /// `checkin::participant_commitment` of the code and the participant wallet. The code itself
+    /// stays off chain until `RevealCheckIn`, so other wallets can't copy it.
+    ///
+    /// Accounts expected:
+    /// 0. `[]` The event account; owned by program id.
+    /// 1. `[writable]` The participant account; owned by program id.
+    /// 2. `[signer]` Participant wallet
+    CheckIn {
+        event_id: u64,
+        event_creator: Pubkey,
+        checkin_commitment: [u8; 32],
+    },
+    /// Mark the participant as attended by revealing the check-in code once check-in closed.
+    /// The code has to match both the commitment of the event and that of the participant.
+    ///
+    /// Accounts expected:
+    /// 0. `[writable]` The event account; owned by program id.
+    /// 1. `[writable]` The participant account; owned by program id.
+    /// 2. `[signer]` Participant wallet
+    RevealCheckIn {
+        event_id: u64,
+        event_creator: Pubkey,
+        code: Vec<u8>,
+    },
+    // Pay rent from payee to payer
+    //
+    // Accounts expected:
+    // 0. `[writable]` The Rent Agreement account created to manage state across 2 parties; owned by program id.
+    // 1. `[signer]` Payer (Renter) account (keypair)
+    // 2. `[]` Payee (Owner) account (public key)
+    // 3. `[]` System program account
+    // PayRent { rent_amount: u64 },
+
+    // Terminate agreement early, violating the terms
+    //
+    // Accounts expected:
+    // 0. `[writable]` The Rent Agreement account created to manage state across 2 parties; owned by program id.
     // TerminateEarly {},
 }
 
+impl Instruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        Self::try_from_slice(input).map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
+
 // impl RentShareInstruction {
 //     pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
 //         let (tag, rest) = input
@@ -1,7 +1,18 @@
+pub mod allowlist;
+pub mod checkin;
+#[cfg(feature = "serde")]
+pub mod decoder;
 pub mod error;
+pub mod events;
+#[cfg(feature = "serde")]
+pub mod idl;
 pub mod instruction;
+pub mod lottery;
+pub mod permit;
 pub mod processor;
+pub mod series;
 pub mod state;
+pub mod ticket;
 
 #[cfg(not(feature = "no-entrypoint"))]
 pub mod entrypoint;
@@ -0,0 +1,161 @@
+//! Randomness for lottery events.
+//!
+//! Drawing happens in two steps so nobody can pick the seed: `CommitLotteryDraw`
+//! records a draw slot `DRAW_DELAY_SLOTS` in the future, whose hash isn't known
+//! yet, and `DrawLottery` later reads from the `SlotHashes` sysvar the hash of
+//! the first slot produced at or after it, as the draw slot itself may be
+//! skipped. The seed is `sha256(slot hash || event pubkey)`, so sending the draw
+//! in a later slot doesn't change it. The sysvar only keeps the last
+//! `MAX_ENTRIES` slots; once it no longer reaches back to the draw slot, which
+//! hash came first is unknown and the draw has to be committed again.
+
+use std::convert::TryFrom;
+
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+pub use solana_program::slot_hashes::MAX_ENTRIES;
+
+/// Slots between committing to a draw and the slot whose hash seeds it
+pub const DRAW_DELAY_SLOTS: u64 = 16;
+
+// Layout of the sysvar data: entry count, then (slot, hash) entries from the most recent slot on
+const COUNT_LEN: usize = 8;
+const ENTRY_LEN: usize = 8 + 32;
+
+/// Hash seeding a draw committed to a slot, as found in the `SlotHashes` sysvar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawHash {
+    /// No slot was produced at or after the draw slot yet
+    Pending,
+    /// Hash of the first slot produced at or after the draw slot
+    Available([u8; 32]),
+    /// The sysvar no longer reaches back to the draw slot
+    Expired,
+}
+
+/// Slots and hashes of the raw `SlotHashes` sysvar data, from the most recent slot on.
+/// The sysvar is too large to deserialize on chain, so its entries are read in place.
+fn entries(slot_hashes: &[u8]) -> impl Iterator<Item = (u64, [u8; 32])> + '_ {
+    let count = slot_hashes
+        .get(..COUNT_LEN)
+        .and_then(|count| <[u8; 8]>::try_from(count).ok())
+        .map_or(0, u64::from_le_bytes);
+    slot_hashes
+        .get(COUNT_LEN..)
+        .unwrap_or_default()
+        .chunks_exact(ENTRY_LEN)
+        .take(count as usize)
+        .map(|entry| {
+            let slot = <[u8; 8]>::try_from(&entry[..8]).unwrap();
+            let hash = <[u8; 32]>::try_from(&entry[8..]).unwrap();
+            (u64::from_le_bytes(slot), hash)
+        })
+}
+
+/// Hash of the first slot produced at or after `draw_slot` in the raw `SlotHashes` sysvar data
+pub fn draw_hash(slot_hashes: &[u8], draw_slot: u64) -> DrawHash {
+    let mut first = None;
+    let mut count = 0;
+    for (slot, hash) in entries(slot_hashes) {
+        count += 1;
+        if slot < draw_slot {
+            // An older slot shows the sysvar still holds every slot since the draw slot
+            return first.map_or(DrawHash::Pending, |(_, hash)| DrawHash::Available(hash));
+        }
+        first = Some((slot, hash));
+    }
+
+    match first {
+        None => DrawHash::Pending,
+        // Unless the sysvar holds every slot so far, earlier slots past the draw slot may have aged out
+        Some((slot, hash)) if slot == draw_slot || count < MAX_ENTRIES => DrawHash::Available(hash),
+        Some(_) => DrawHash::Expired,
+    }
+}
+
+/// Lottery seed of an event drawn from the hash of its draw slot
+pub fn seed(slot_hash: &[u8; 32], event: &Pubkey) -> [u8; 32] {
+    hashv(&[slot_hash, event.as_ref()]).to_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot_hashes(entries: &[(u64, [u8; 32])]) -> Vec<u8> {
+        let mut data = (entries.len() as u64).to_le_bytes().to_vec();
+        for (slot, hash) in entries {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(hash);
+        }
+        data
+    }
+
+    #[test]
+    fn finds_the_hash_of_the_first_slot_from_the_draw_slot() {
+        let data = slot_hashes(&[(13, [3; 32]), (11, [2; 32]), (10, [1; 32])]);
+
+        assert_eq!(draw_hash(&data, 11), DrawHash::Available([2; 32]));
+        // Slot 12 was skipped
+        assert_eq!(draw_hash(&data, 12), DrawHash::Available([3; 32]));
+        assert_eq!(draw_hash(&data, 14), DrawHash::Pending);
+        assert_eq!(draw_hash(&[], 9), DrawHash::Pending);
+    }
+
+    #[test]
+    fn expires_once_the_sysvar_no_longer_reaches_the_draw_slot() {
+        let full: Vec<(u64, [u8; 32])> = (0..MAX_ENTRIES as u64)
+            .map(|age| (1_000 - age, [age as u8; 32]))
+            .collect();
+        let oldest = 1_000 - MAX_ENTRIES as u64 + 1;
+
+        assert_eq!(
+            draw_hash(&slot_hashes(&full), oldest),
+            DrawHash::Available(full[MAX_ENTRIES - 1].1)
+        );
+        assert_eq!(
+            draw_hash(&slot_hashes(&full), oldest - 1),
+            DrawHash::Expired
+        );
+        // A sysvar that isn't full yet holds every slot produced so far
+        assert_eq!(
+            draw_hash(&slot_hashes(&full[..3]), 0),
+            DrawHash::Available(full[2].1)
+        );
+    }
+
+    #[test]
+    fn ignores_data_past_the_entry_count() {
+        let mut data = slot_hashes(&[(10, [1; 32])]);
+        data.extend_from_slice(&11u64.to_le_bytes());
+        data.extend_from_slice(&[2; 32]);
+
+        assert_eq!(draw_hash(&data, 11), DrawHash::Pending);
+    }
+
+    #[test]
+    fn seed_does_not_depend_on_when_the_draw_is_sent() {
+        let draw_slot = 100;
+        let early = slot_hashes(&[(101, [7; 32]), (100, [5; 32]), (99, [4; 32])]);
+        // Drawing later only adds more recent slots, which the caller could pick among
+        let late = slot_hashes(&[
+            (140, [9; 32]),
+            (120, [8; 32]),
+            (101, [7; 32]),
+            (100, [5; 32]),
+            (99, [4; 32]),
+        ]);
+
+        assert_eq!(draw_hash(&early, draw_slot), draw_hash(&late, draw_slot));
+        assert_eq!(draw_hash(&late, draw_slot), DrawHash::Available([5; 32]));
+    }
+
+    #[test]
+    fn seed_is_bound_to_the_event() {
+        let hash = [5; 32];
+        assert_ne!(
+            seed(&hash, &Pubkey::new_unique()),
+            seed(&hash, &Pubkey::new_unique())
+        );
+    }
+}
@@ -0,0 +1,181 @@
+//! Join permits signed off-chain by the event's permit signer.
+//!
+//! The permit is the Borsh encoding of [`JoinPermit`] and must be verified by an
+//! Ed25519 program instruction placed right before `ParticipateInEvent` in the
+//! same transaction, with the signature, public key and message all stored in
+//! that instruction's own data.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo, ed25519_program, msg, program_error::ProgramError, pubkey::Pubkey,
+    sysvar::instructions::get_instruction_relative,
+};
+
+use crate::error::RentShareError;
+
+/// Seed prefix of the accounts recording used permit nonces
+pub const PERMIT_SEED: &[u8] = b"permit";
+
+// Layout of the Ed25519 program instruction data
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+const PUBKEY_LEN: usize = 32;
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Off-chain permit allowing `participant` to join `event` until `expiry`
+#[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+pub struct JoinPermit {
+    pub event: Pubkey,
+    pub participant: Pubkey,
+    pub expiry: i64,
+    pub nonce: u64,
+}
+
+/// Seeds of the account recording that a permit nonce was used for an event
+pub fn nonce_seeds<'a>(event: &'a Pubkey, nonce: &'a [u8; 8]) -> [&'a [u8]; 3] {
+    [PERMIT_SEED, event.as_ref(), nonce]
+}
+
+/// Read the permit verified by the Ed25519 program instruction preceding the current one
+/// and make sure it was signed by `signer`
+pub fn load_signed_permit(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+) -> Result<JoinPermit, ProgramError> {
+    let ed25519_instruction = get_instruction_relative(-1, instructions_sysvar)?;
+    if ed25519_instruction.program_id != ed25519_program::id() {
+        msg!("[RentShare] Permit must be verified by the preceding Ed25519 instruction");
+        return Err(RentShareError::InvalidPermit.into());
+    }
+
+    parse_signed_permit(&ed25519_instruction.data, signer)
+}
+
+/// Read the permit out of Ed25519 program instruction data holding a single signature by `signer`
+fn parse_signed_permit(data: &[u8], signer: &Pubkey) -> Result<JoinPermit, ProgramError> {
+    let offsets = data
+        .get(SIGNATURE_OFFSETS_START..SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN)
+        .filter(|_| data[0] == 1)
+        .ok_or(RentShareError::InvalidPermit)?;
+    let offset = |index: usize| u16::from_le_bytes([offsets[index * 2], offsets[index * 2 + 1]]);
+
+    // Signature, public key and message must live in the Ed25519 instruction itself,
+    // otherwise the verified bytes could differ from the ones read here
+    if offset(1) != CURRENT_INSTRUCTION
+        || offset(3) != CURRENT_INSTRUCTION
+        || offset(6) != CURRENT_INSTRUCTION
+    {
+        msg!("[RentShare] Permit signature data must be inline");
+        return Err(RentShareError::InvalidPermit.into());
+    }
+
+    let public_key_offset = offset(2) as usize;
+    let public_key = data
+        .get(public_key_offset..public_key_offset + PUBKEY_LEN)
+        .ok_or(RentShareError::InvalidPermit)?;
+    if public_key != signer.as_ref() {
+        msg!("[RentShare] Permit not signed by the event permit signer");
+        return Err(RentShareError::InvalidPermit.into());
+    }
+
+    let message_offset = offset(4) as usize;
+    let message = data
+        .get(message_offset..message_offset + offset(5) as usize)
+        .ok_or(RentShareError::InvalidPermit)?;
+
+    JoinPermit::try_from_slice(message).map_err(|_| RentShareError::InvalidPermit.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNATURE_LEN: usize = 64;
+
+    /// Ed25519 program instruction data for one signature, laid out like
+    /// `new_ed25519_instruction`: offsets, then public key, signature and message
+    fn ed25519_data(signer: &Pubkey, message: &[u8], instruction_index: u16) -> Vec<u8> {
+        let public_key_offset = SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN;
+        let signature_offset = public_key_offset + PUBKEY_LEN;
+        let message_offset = signature_offset + SIGNATURE_LEN;
+
+        let mut data = vec![1, 0];
+        for offset in [
+            signature_offset as u16,
+            instruction_index,
+            public_key_offset as u16,
+            instruction_index,
+            message_offset as u16,
+            message.len() as u16,
+            instruction_index,
+        ] {
+            data.extend_from_slice(&offset.to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(&[0; SIGNATURE_LEN]);
+        data.extend_from_slice(message);
+        data
+    }
+
+    fn permit() -> JoinPermit {
+        JoinPermit {
+            event: Pubkey::new_unique(),
+            participant: Pubkey::new_unique(),
+            expiry: 1_700_000_000,
+            nonce: 7,
+        }
+    }
+
+    #[test]
+    fn reads_the_inline_permit() {
+        let signer = Pubkey::new_unique();
+        let permit = permit();
+        let data = ed25519_data(&signer, &permit.try_to_vec().unwrap(), CURRENT_INSTRUCTION);
+
+        assert_eq!(parse_signed_permit(&data, &signer).unwrap(), permit);
+    }
+
+    #[test]
+    fn rejects_another_signer() {
+        let data = ed25519_data(
+            &Pubkey::new_unique(),
+            &permit().try_to_vec().unwrap(),
+            CURRENT_INSTRUCTION,
+        );
+
+        assert_eq!(
+            parse_signed_permit(&data, &Pubkey::new_unique()),
+            Err(RentShareError::InvalidPermit.into())
+        );
+    }
+
+    #[test]
+    fn rejects_data_verified_in_another_instruction() {
+        let signer = Pubkey::new_unique();
+        let data = ed25519_data(&signer, &permit().try_to_vec().unwrap(), 0);
+
+        assert_eq!(
+            parse_signed_permit(&data, &signer),
+            Err(RentShareError::InvalidPermit.into())
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_or_malformed_data() {
+        let signer = Pubkey::new_unique();
+        let data = ed25519_data(
+            &signer,
+            &permit().try_to_vec().unwrap(),
+            CURRENT_INSTRUCTION,
+        );
+
+        let mut no_signature = data.clone();
+        no_signature[0] = 0;
+        for malformed in [&data[..10], &data[..data.len() - 1], &no_signature[..], &[]] {
+            assert_eq!(
+                parse_signed_permit(malformed, &signer),
+                Err(RentShareError::InvalidPermit.into())
+            );
+        }
+    }
+}
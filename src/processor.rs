@@ -1,20 +1,31 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
     msg,
-    program::invoke,
+    program::{invoke, invoke_signed},
     program_error::ProgramError,
     program_pack::IsInitialized,
     pubkey::Pubkey,
     system_instruction,
-    sysvar::{rent::Rent, Sysvar},
+    sysvar::{self, rent::Rent, Sysvar},
 };
 
 use crate::{
+    allowlist, checkin,
     error::RentShareError,
-    instruction::RentShareInstruction,
-    state::{EventStatus, InitEvent},
+    events::EventRecord,
+    instruction::{Instruction, TicketTier},
+    lottery, permit, series,
+    state::{
+        DisputeStatus, EventSeries, EventStatus, EventTier, InitEvent, Participant,
+        ParticipantStatus, PayeeShare, PriceStep, RefundStep, SeriesStatus, SponsorPoolDestination,
+        Sponsorship, SponsorshipStatus, TransferPolicy, Vesting, Waitlist, WaitlistEntry,
+        WaitlistStatus, FULL_REFUND_BPS, MAX_EVENT_PAYEES, MAX_EVENT_TIERS, MAX_PRICE_STEPS,
+        MAX_PRIZE_RANKS, MAX_REFUND_STEPS, MAX_SERIES_ORGANIZERS,
+    },
+    ticket::{self, TicketAccounts},
 };
 
 pub struct Processor;
@@ -32,7 +43,28 @@ impl Processor {
                 event_creator,
                 event_id,
                 fix_deposit_amount_per_person,
-                total_partcipator
+                total_partcipator,
+                tiers,
+                allowlist_root,
+                permit_signer,
+                start_time,
+                refund_schedule,
+                bond,
+                arbiter,
+                dispute_window,
+                confirmation_quorum_bps,
+                confirmation_window,
+                payees,
+                vesting,
+                vesting_duration,
+                prize_table,
+                results_window,
+                funding_goal,
+                registration_deadline,
+                min_deposit,
+                max_deposit,
+                price_schedule,
+                lottery,
             } => Self::initialize_event(
                 accounts,
                 program_id,
@@ -40,46 +72,278 @@ impl Processor {
                 event_creator,
                 event_id,
                 fix_deposit_amount_per_person,
-                total_partcipator
+                total_partcipator,
+                tiers,
+                allowlist_root,
+                permit_signer,
+                start_time,
+                refund_schedule,
+                bond,
+                arbiter,
+                dispute_window,
+                confirmation_quorum_bps,
+                confirmation_window,
+                payees,
+                vesting,
+                vesting_duration,
+                prize_table,
+                results_window,
+                funding_goal,
+                registration_deadline,
+                min_deposit,
+                max_deposit,
+                price_schedule,
+                lottery,
             ),
             Instruction::CancelEvent {
                 event_creator,
-                event_id
-            } => Self::cancel_event(
+                event_id,
+            } => Self::cancel_event(accounts, program_id, event_creator, event_id),
+            Instruction::StartEvent {
+                event_id,
+                event_creator,
+            } => Self::start_event(accounts, program_id, event_id, event_creator),
+            Instruction::EndEvent {
+                event_id,
+                event_creator,
+            } => Self::end_event(accounts, program_id, event_id, event_creator),
+            Instruction::ParticipateInEvent {
+                event_id,
+                particpate_amount,
+                event_creator,
+                tier,
+                allowlist_proof,
+            } => Self::particpate_in_event(
                 accounts,
                 program_id,
-                event_creator
                 event_id,
+                particpate_amount,
+                event_creator,
+                tier,
+                allowlist_proof,
             ),
-            Instruction::StartEvent {                
+            Instruction::SetAllowlistRoot {
                 event_id,
-                event_creator
-            } => Self::start_event(
+                event_creator,
+                allowlist_root,
+            } => Self::set_allowlist_root(
                 accounts,
                 program_id,
                 event_id,
-                event_creator
+                event_creator,
+                allowlist_root,
             ),
-            Instruction::EndEvent {                
+            Instruction::InitializeWaitlist {
+                event_id,
+                event_creator,
+                tier,
+            } => Self::initialize_waitlist(accounts, program_id, event_id, event_creator, tier),
+            Instruction::JoinWaitlist {
                 event_id,
-                event_creator
-            } => Self::end_event(
+                event_creator,
+                tier,
+                deposit,
+                allowlist_proof,
+            } => Self::join_waitlist(
                 accounts,
                 program_id,
                 event_id,
-                event_creator
+                event_creator,
+                tier,
+                deposit,
+                allowlist_proof,
             ),
-            Instruction::ParticipateInEvent {
+            Instruction::LeaveWaitlist {
+                event_id,
+                event_creator,
+            } => Self::leave_waitlist(accounts, program_id, event_id, event_creator),
+            Instruction::WithdrawFromEvent {
+                event_id,
+                event_creator,
+            } => Self::withdraw_from_event(accounts, program_id, event_id, event_creator),
+            Instruction::RemoveParticipant {
+                event_id,
+                event_creator,
+            } => Self::remove_participant(accounts, program_id, event_id, event_creator),
+            Instruction::SetTransferPolicy {
+                event_id,
+                event_creator,
+                transfer_policy,
+            } => Self::set_transfer_policy(
+                accounts,
+                program_id,
+                event_id,
+                event_creator,
+                transfer_policy,
+            ),
+            Instruction::TransferParticipation {
+                event_id,
+                event_creator,
+                allowlist_proof,
+            } => Self::transfer_participation(
+                accounts,
+                program_id,
+                event_id,
+                event_creator,
+                allowlist_proof,
+            ),
+            Instruction::InitializeTicketMint {
+                event_id,
+                event_creator,
+                freeze_tickets,
+            } => Self::initialize_ticket_mint(
+                accounts,
+                program_id,
+                event_id,
+                event_creator,
+                freeze_tickets,
+            ),
+            Instruction::ClaimTicket {
+                event_id,
+                event_creator,
+            } => Self::claim_ticket(accounts, program_id, event_id, event_creator),
+            Instruction::FreezeTicket {
+                event_id,
+                event_creator,
+            } => Self::freeze_ticket(accounts, program_id, event_id, event_creator),
+            Instruction::InitializeEventSeries {
+                series_creator,
+                first_event_id,
+                payee_pubkey,
+                fix_deposit_amount_per_person,
+                total_partcipator,
+                organizers,
+                first_start,
+                interval,
+                max_occurrences,
+            } => Self::initialize_event_series(
+                accounts,
+                program_id,
+                series_creator,
+                first_event_id,
+                payee_pubkey,
+                fix_deposit_amount_per_person,
+                total_partcipator,
+                organizers,
+                first_start,
+                interval,
+                max_occurrences,
+            ),
+            Instruction::InstantiateOccurrence {
+                series_creator,
+                first_event_id,
+            } => Self::instantiate_occurrence(accounts, program_id, series_creator, first_event_id),
+            Instruction::OpenDispute {
+                event_id,
+                event_creator,
+            } => Self::open_dispute(accounts, program_id, event_id, event_creator),
+            Instruction::ResolveDispute {
+                event_id,
+                event_creator,
+                refund_bps,
+            } => Self::resolve_dispute(accounts, program_id, event_id, event_creator, refund_bps),
+            Instruction::SettleEvent {
+                event_id,
+                event_creator,
+            } => Self::settle_event(accounts, program_id, event_id, event_creator),
+            Instruction::ClaimDisputeRefund {
                 event_id,
+                event_creator,
+            } => Self::claim_dispute_refund(accounts, program_id, event_id, event_creator),
+            Instruction::ConfirmEvent {
+                event_id,
+                event_creator,
+            } => Self::confirm_event(accounts, program_id, event_id, event_creator),
+            Instruction::ClaimVested {
+                event_id,
+                event_creator,
+            } => Self::claim_vested(accounts, program_id, event_id, event_creator),
+            Instruction::BatchParticipateInEvent {
+                event_id,
+                event_creator,
+                tier,
                 particpate_amount,
-                event_creator
-            } => Self::particpate_in_event(
+            } => Self::batch_participate_in_event(
                 accounts,
                 program_id,
                 event_id,
+                event_creator,
+                tier,
                 particpate_amount,
-                event_creator
             ),
+            Instruction::Sponsor {
+                event_id,
+                event_creator,
+                amount,
+            } => Self::sponsor(accounts, program_id, event_id, event_creator, amount),
+            Instruction::SetSponsorPoolDestination {
+                event_id,
+                event_creator,
+                destination,
+            } => Self::set_sponsor_pool_destination(
+                accounts,
+                program_id,
+                event_id,
+                event_creator,
+                destination,
+            ),
+            Instruction::ClaimSponsorShare {
+                event_id,
+                event_creator,
+            } => Self::claim_sponsor_share(accounts, program_id, event_id, event_creator),
+            Instruction::WithdrawSponsorship {
+                event_id,
+                event_creator,
+            } => Self::withdraw_sponsorship(accounts, program_id, event_id, event_creator),
+            Instruction::SubmitResults {
+                event_id,
+                event_creator,
+            } => Self::submit_results(accounts, program_id, event_id, event_creator),
+            Instruction::ClaimPrize {
+                event_id,
+                event_creator,
+            } => Self::claim_prize(accounts, program_id, event_id, event_creator),
+            Instruction::DrawLottery {
+                event_id,
+                event_creator,
+            } => Self::draw_lottery(accounts, program_id, event_id, event_creator),
+            Instruction::ResolveLotteryEntry {
+                event_id,
+                event_creator,
+            } => Self::resolve_lottery_entry(accounts, program_id, event_id, event_creator),
+            Instruction::CommitLotteryDraw {
+                event_id,
+                event_creator,
+            } => Self::commit_lottery_draw(accounts, program_id, event_id, event_creator),
+            Instruction::OpenCheckIn {
+                event_id,
+                event_creator,
+                code_commitment,
+                checkin_window,
+            } => Self::open_check_in(
+                accounts,
+                program_id,
+                event_id,
+                event_creator,
+                code_commitment,
+                checkin_window,
+            ),
+            Instruction::CheckIn {
+                event_id,
+                event_creator,
+                checkin_commitment,
+            } => Self::check_in(
+                accounts,
+                program_id,
+                event_id,
+                event_creator,
+                checkin_commitment,
+            ),
+            Instruction::RevealCheckIn {
+                event_id,
+                event_creator,
+                code,
+            } => Self::reveal_check_in(accounts, program_id, event_id, event_creator, code),
         }
     }
 
@@ -88,10 +352,31 @@ impl Processor {
         accounts: &[AccountInfo],
         program_id: &Pubkey,
         payee_pubkey: Pubkey, // the party receiving the payment is known as the payee.
-        event_creator:Pubkey,
+        event_creator: Pubkey,
         event_id: u64,
         fix_deposit_amount_per_person: u64,
         total_partcipator: u64,
+        tiers: Vec<TicketTier>,
+        allowlist_root: [u8; 32],
+        permit_signer: Pubkey,
+        start_time: i64,
+        refund_schedule: Vec<RefundStep>,
+        bond: u64,
+        arbiter: Pubkey,
+        dispute_window: i64,
+        confirmation_quorum_bps: u16,
+        confirmation_window: i64,
+        payees: Vec<PayeeShare>,
+        vesting: Vesting,
+        vesting_duration: i64,
+        prize_table: Vec<u16>,
+        results_window: i64,
+        funding_goal: u64,
+        registration_deadline: i64,
+        min_deposit: u64,
+        max_deposit: u64,
+        price_schedule: Vec<PriceStep>,
+        lottery: bool,
     ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
 
@@ -101,7 +386,15 @@ impl Processor {
             return Err(ProgramError::IncorrectProgramId);
         }
 
-        
+        let rent = &Rent::from_account_info(next_account_info(accounts_iter)?)?;
+        if !rent.is_exempt(
+            solana_data_account.lamports(),
+            solana_data_account.data_len(),
+        ) {
+            msg!("[RentShare] Event account is not rent exempt");
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+
         // Initialize the Rent Agreement Account with the initial data
         // Note: the structure of the data state must match the `space` reserved when account created
         let solana_data_account_data =
@@ -121,6 +414,154 @@ impl Processor {
             return Err(ProgramError::AccountAlreadyInitialized);
         }
 
+        // Without explicit tiers the event sells a single tier at the fixed deposit
+        let tiers = if tiers.is_empty() {
+            vec![TicketTier {
+                price: fix_deposit_amount_per_person,
+                capacity: total_partcipator,
+            }]
+        } else {
+            tiers
+        };
+
+        if tiers.len() > MAX_EVENT_TIERS {
+            msg!(
+                "[RentShare] Too many ticket tiers: {} (max {})",
+                tiers.len(),
+                MAX_EVENT_TIERS
+            );
+            return Err(RentShareError::InvalidTicketTier.into());
+        }
+
+        for (index, tier) in tiers.iter().enumerate() {
+            if tier.capacity == 0 {
+                msg!("[RentShare] Ticket tier {} has no capacity", index);
+                return Err(RentShareError::InvalidTicketTier.into());
+            }
+            data_of_solana_ac.tiers[index] = EventTier {
+                price: tier.price,
+                capacity: tier.capacity,
+                ..EventTier::default()
+            };
+        }
+
+        if refund_schedule.len() > MAX_REFUND_STEPS {
+            msg!(
+                "[RentShare] Too many refund steps: {} (max {})",
+                refund_schedule.len(),
+                MAX_REFUND_STEPS
+            );
+            return Err(RentShareError::InvalidRefundSchedule.into());
+        }
+
+        if !refund_schedule.is_empty() && start_time == 0 {
+            msg!("[RentShare] A refund schedule needs the event start time");
+            return Err(RentShareError::InvalidRefundSchedule.into());
+        }
+
+        for (index, step) in refund_schedule.iter().enumerate() {
+            let ordered = index == 0
+                || step.seconds_before_start < refund_schedule[index - 1].seconds_before_start;
+            if !ordered || step.refund_bps > FULL_REFUND_BPS {
+                msg!("[RentShare] Invalid refund step {}", index);
+                return Err(RentShareError::InvalidRefundSchedule.into());
+            }
+            data_of_solana_ac.refund_schedule[index] = *step;
+        }
+
+        if dispute_window < 0 || (dispute_window > 0 && arbiter == Pubkey::default()) {
+            msg!("[RentShare] A dispute window needs a positive duration and an arbiter");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        if confirmation_quorum_bps > FULL_REFUND_BPS
+            || (confirmation_quorum_bps > 0 && confirmation_window <= 0)
+        {
+            msg!("[RentShare] A confirmation quorum needs at most 10000 bps and a positive window");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        if !payees.is_empty() {
+            let total_bps: u32 = payees.iter().map(|share| share.share_bps as u32).sum();
+            if payees.len() > MAX_EVENT_PAYEES
+                || total_bps != FULL_REFUND_BPS as u32
+                || payees[0].payee != payee_pubkey
+            {
+                msg!(
+                    "[RentShare] Invalid split across {} payees totalling {} bps",
+                    payees.len(),
+                    total_bps
+                );
+                return Err(RentShareError::InvalidPayeeSplit.into());
+            }
+            for (index, share) in payees.iter().enumerate() {
+                data_of_solana_ac.payees[index] = *share;
+            }
+        }
+
+        if vesting != Vesting::None && vesting_duration <= 0 {
+            msg!("[RentShare] Vesting needs a positive duration");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        if !prize_table.is_empty() {
+            let total_bps: u32 = prize_table.iter().map(|bps| *bps as u32).sum();
+            if prize_table.len() > MAX_PRIZE_RANKS || total_bps != FULL_REFUND_BPS as u32 {
+                msg!(
+                    "[RentShare] Invalid prize table of {} ranks totalling {} bps",
+                    prize_table.len(),
+                    total_bps
+                );
+                return Err(RentShareError::InvalidPrizeTable.into());
+            }
+            if results_window <= 0 {
+                msg!("[RentShare] Prize tables need a positive results window");
+                return Err(ProgramError::InvalidArgument);
+            }
+            data_of_solana_ac.prize_table[..prize_table.len()].copy_from_slice(&prize_table);
+            data_of_solana_ac.sponsor_pool_destination = SponsorPoolDestination::Ranked as u8;
+        }
+
+        if registration_deadline < 0 || (funding_goal > 0 && registration_deadline == 0) {
+            msg!("[RentShare] A funding goal needs a registration deadline");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        if min_deposit > max_deposit && max_deposit > 0 {
+            msg!(
+                "[RentShare] Minimum deposit {} exceeds maximum {}",
+                min_deposit,
+                max_deposit
+            );
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        if price_schedule.len() > MAX_PRICE_STEPS {
+            msg!(
+                "[RentShare] Too many price steps: {} (max {})",
+                price_schedule.len(),
+                MAX_PRICE_STEPS
+            );
+            return Err(RentShareError::InvalidPriceSchedule.into());
+        }
+
+        if !price_schedule.is_empty() && (tiers.len() > 1 || max_deposit > 0) {
+            msg!("[RentShare] A price schedule needs a single fixed price tier");
+            return Err(RentShareError::InvalidPriceSchedule.into());
+        }
+
+        for (index, step) in price_schedule.iter().enumerate() {
+            if index > 0 && step.starts_at <= price_schedule[index - 1].starts_at {
+                msg!("[RentShare] Invalid price step {}", index);
+                return Err(RentShareError::InvalidPriceSchedule.into());
+            }
+            data_of_solana_ac.price_schedule[index] = *step;
+        }
+
+        if lottery && (registration_deadline == 0 || tiers.len() > 1 || funding_goal > 0) {
+            msg!("[RentShare] A lottery needs a registration deadline, a single tier and no funding goal");
+            return Err(ProgramError::InvalidArgument);
+        }
 
         data_of_solana_ac.status = EventStatus::Active as u8;
         data_of_solana_ac.payee_pubkey = payee_pubkey;
@@ -128,261 +569,3679 @@ impl Processor {
         data_of_solana_ac.event_id = event_id;
         data_of_solana_ac.fix_deposit_amount_per_person = fix_deposit_amount_per_person;
         data_of_solana_ac.total_partcipator = total_partcipator;
+        data_of_solana_ac.participant_count = 0;
+        data_of_solana_ac.total_deposits = 0;
+        data_of_solana_ac.tier_count = tiers.len() as u8;
+        data_of_solana_ac.allowlist_root = allowlist_root;
+        data_of_solana_ac.permit_signer = permit_signer;
+        data_of_solana_ac.start_time = start_time;
+        data_of_solana_ac.refund_step_count = refund_schedule.len() as u8;
+        data_of_solana_ac.bond = bond;
+        data_of_solana_ac.arbiter = arbiter;
+        data_of_solana_ac.dispute_window = dispute_window;
+        data_of_solana_ac.confirmation_quorum_bps = confirmation_quorum_bps;
+        data_of_solana_ac.confirmation_window = confirmation_window;
+        data_of_solana_ac.payee_count = payees.len() as u8;
+        data_of_solana_ac.vesting = vesting as u8;
+        data_of_solana_ac.vesting_duration = vesting_duration;
+        data_of_solana_ac.prize_rank_count = prize_table.len() as u8;
+        data_of_solana_ac.results_window = results_window;
+        data_of_solana_ac.funding_goal = funding_goal;
+        data_of_solana_ac.registration_deadline = registration_deadline;
+        data_of_solana_ac.min_deposit = min_deposit;
+        data_of_solana_ac.max_deposit = max_deposit;
+        data_of_solana_ac.price_step_count = price_schedule.len() as u8;
+        data_of_solana_ac.lottery = lottery;
         data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
 
-        msg!(
-            "Initialized event account: {:?}",
-            data_of_solana_ac
-        );
-
-        Ok(())
-    }
-
+        if bond > 0 {
+            let creator_account = next_account_info(accounts_iter)?;
+            let system_program_account = next_account_info(accounts_iter)?;
+            if !creator_account.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
 
-    // cancel event-----------
-    // fn cancel_event(
-    //     accounts: &[AccountInfo],
-    //     program_id: &Pubkey,
-    //     event_creator:pubkey,
-    //     event_id:u64
-    // ){
-    //     let accounts_iter = &mut accounts.iter();
+            if *creator_account.key != event_creator {
+                msg!("[RentShare] This is not the event creator.");
+                return Err(RentShareError::EventCreatorMismatch.into());
+            }
 
-    //     let solana_data_account = next_account_info(accounts_iter)?;
-    //     if solana_data_account.owner != program_id {
-    //         msg!("[RentShare] Rent agreement account is not owned by this program");
-    //         return Err(ProgramError::IncorrectProgramId);
-    //     }
+            Self::escrow_deposit(
+                creator_account,
+                solana_data_account,
+                system_program_account,
+                bond,
+            )?;
+        }
 
-    //     // let payee_account: &AccountInfo = next_account_info(accounts_iter)?;
-    //     // let payer_account = next_account_info(accounts_iter)?;
-    //     let system_program_account = next_account_info(accounts_iter)?;
+        EventRecord::EventCreated {
+            event: *solana_data_account.key,
+            event_id,
+            event_creator,
+            payee: payee_pubkey,
+            total_partcipator,
+            tier_count: data_of_solana_ac.tier_count,
+        }
+        .emit()?;
+        if bond > 0 {
+            EventRecord::BondPosted {
+                event: *solana_data_account.key,
+                event_creator,
+                amount: bond,
+            }
+            .emit()?;
+        }
 
-    //     if !event_creator.is_signer {
-    //         return Err(ProgramError::MissingRequiredSignature);
-    //     }
-    //     let solana_data_account_data =
-    //     InitEvent::try_from_slice(&solana_data_account.data.borrow());
+        Ok(())
+    }
 
-    // if solana_data_account_data.is_err() {
-    //     msg!(
-    //         "[RentShare] Rent agreement account data size incorrect: {}",
-    //         solana_data_account.try_data_len()?
-    //     );
-    //     return Err(ProgramError::InvalidAccountData);
-    // }
+    fn cancel_event(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_creator: Pubkey,
+        event_id: u64,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
 
-    // let mut data_of_solana_ac = solana_data_account_data.unwrap();
-    // // EventStatus::Active
-    // //************************************************************88
-    // if data_of_solana_ac.is_initialized() {
-    //     msg!("[RentShare] Rent agreement already initialized");
-    //     return Err(ProgramError::AccountAlreadyInitialized);
-    // }
-    // //*************************************************************
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let creator_account = next_account_info(accounts_iter)?;
 
-    // if data_of_solana_ac.event_creator != event_creator {
-    //     msg!(
-    //         "[RentShare] Rent amount does not match agreement amount: {} vs {}",
-    //         data_of_solana_ac.rent_amount,
-    //         rent_amount
-    //     );
-    //     return Err(RentShareError::RentPaymentAmountMismatch.into());
-    // }
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_creator(
+            &data_of_solana_ac,
+            event_id,
+            &event_creator,
+            creator_account,
+        )?;
 
-    // if data_of_solana_ac.event_id != event_id {
-    //     msg!(
-    //         "[RentShare] Rent amount does not match agreement amount: {} vs {}",
-    //         data_of_solana_ac.rent_amount,
-    //         rent_amount
-    //     );
-    //     return Err(RentShareError::RentPaymentAmountMismatch.into());
-    // }
-    // data_of_solana_ac.status = EventStatus::Completed as u8;
-    // data_of_solana_ac.status = EventStatus::Active as u8;
-    // data_of_solana_ac.payee_pubkey = payee_pubkey;
-    // // data_of_solana_ac.payer_pubkey = payer_pubkey;
-    // // data_of_solana_ac.rent_amount = rent_amount;
-    // data_of_solana_ac.event_creator = event_creator;
-    // data_of_solana_ac.event_id = event_id;
-    // data_of_solana_ac.fix_deposit_amount_per_person = fix_deposit_amount_per_person;
-    // data_of_solana_ac.total_partcipator = total_partcipator;
-    // // data_of_solana_ac.remaining_payments = duration;
-    // // data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+        if !data_of_solana_ac.is_open() && !data_of_solana_ac.is_started() {
+            msg!("[RentShare] Only open or started events can be cancelled");
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
 
-    // msg!(
-    //     "[RentShare] Initialized rent agreement account: {:?}",
-    //     data_of_solana_ac
-    // );
+        // The creator isn't to blame for a missed funding goal, so the bond goes back in full
+        let funding_failed = data_of_solana_ac.is_funding_failed(Clock::get()?.unix_timestamp);
+        let returned_bond = if funding_failed {
+            data_of_solana_ac.bond
+        } else {
+            0
+        };
+        data_of_solana_ac.status = EventStatus::Terminated as u8;
+        if funding_failed {
+            Self::transfer_from_escrow(solana_data_account, creator_account, returned_bond)?;
+            data_of_solana_ac.bond = 0;
+        } else {
+            Self::slash_bond(solana_data_account, &mut data_of_solana_ac, creator_account)?;
+        }
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
 
+        if returned_bond > 0 {
+            EventRecord::BondReturned {
+                event: *solana_data_account.key,
+                event_creator,
+                amount: returned_bond,
+            }
+            .emit()?;
+        }
+        EventRecord::Cancelled {
+            event: *solana_data_account.key,
+        }
+        .emit()?;
 
+        Ok(())
+    }
 
-    // }
     fn start_event(
         accounts: &[AccountInfo],
         program_id: &Pubkey,
-        event_id:u64,
-        particpate_amount:u64,
-        event_creator:Pubkey
+        event_id: u64,
+        event_creator: Pubkey,
     ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
 
         let solana_data_account = next_account_info(accounts_iter)?;
-        if solana_data_account.owner != program_id {
-            msg!("Account is not owned by this program");
-            return Err(ProgramError::IncorrectProgramId);
-        }
+        let creator_account = next_account_info(accounts_iter)?;
 
-     
-        if !event_creator.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_creator(
+            &data_of_solana_ac,
+            event_id,
+            &event_creator,
+            creator_account,
+        )?;
+
+        if !data_of_solana_ac.is_open() {
+            msg!("[RentShare] Only open events can be started");
+            return Err(RentShareError::InvalidEventStatus.into());
         }
 
-     
-        // Note: the structure of the data state must match the `space` the client used to create the account
-        let solana_data_account_data =
-            StartEvent::try_from_slice(&solana_data_account.data.borrow());
+        if data_of_solana_ac.has_funding_goal() {
+            if !data_of_solana_ac.is_registration_closed(Clock::get()?.unix_timestamp) {
+                msg!(
+                    "[RentShare] Funding is open until {}",
+                    data_of_solana_ac.registration_deadline
+                );
+                return Err(RentShareError::FundingOpen.into());
+            }
+            if data_of_solana_ac.total_deposits < data_of_solana_ac.funding_goal {
+                msg!(
+                    "[RentShare] Deposits of {} missed the funding goal of {}",
+                    data_of_solana_ac.total_deposits,
+                    data_of_solana_ac.funding_goal
+                );
+                return Err(RentShareError::FundingGoalNotMet.into());
+            }
+        }
 
-        if solana_data_account_data.is_err() {
-            msg!(
-                "account data size incorrect: {}",
-                solana_data_account.try_data_len()?
-            );
-            return Err(ProgramError::InvalidAccountData);
+        if data_of_solana_ac.is_lottery()
+            && (!data_of_solana_ac.lottery_drawn || data_of_solana_ac.lottery_unresolved > 0)
+        {
+            msg!("[RentShare] Lottery entries have to be drawn and resolved first");
+            return Err(RentShareError::LotteryPending.into());
         }
 
-        let mut data_of_solana_ac = solana_data_account_data.unwrap();
-       //****************doubt it will run or not **********
-        if !data_of_solana_ac.is_initialized() {
-            msg!("[RentShare] Rent agreement account not initialized");
-            return Err(ProgramError::UninitializedAccount);
+        data_of_solana_ac.status = EventStatus::Started as u8;
+        if data_of_solana_ac.has_vesting() {
+            data_of_solana_ac.vesting_start = Clock::get()?.unix_timestamp;
+            data_of_solana_ac.vesting_total = data_of_solana_ac.total_deposits;
         }
-//**********************************************************
-        // Make sure we pay the same account used during the agreement initialization
-        if data_of_solana_ac.event_creator != *event_creator.key {
-            msg!("This is not the event creator.");
-            return Err(ProgramError::InvalidAccountData);
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        EventRecord::Started {
+            event: *solana_data_account.key,
         }
-        if data_of_solana_ac.event_creator != event_creator {
-            msg!(
-                "This is not the event creator.",
-                data_of_solana_ac.event_creator,
-                event_creator
-            );
-            return Err(RentShareError::RentPaymentAmountMismatch.into());
+        .emit()?;
+        if data_of_solana_ac.has_vesting() {
+            EventRecord::VestingStarted {
+                event: *solana_data_account.key,
+                vesting_total: data_of_solana_ac.vesting_total,
+                vesting_start: data_of_solana_ac.vesting_start,
+            }
+            .emit()?;
         }
-        data_of_solana_ac.event_id = event_id;
-        data_of_solana_ac.particpate_amount = particpate_amount;
-        data_of_solana_ac.event_creator = event_creator;
-        
-        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
 
         Ok(())
-
     }
-    fn pay_rent(accounts: &[AccountInfo], program_id: &Pubkey, rent_amount: u64) -> ProgramResult {
+
+    fn end_event(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
 
         let solana_data_account = next_account_info(accounts_iter)?;
-        if solana_data_account.owner != program_id {
-            msg!("[RentShare] Rent agreement account is not owned by this program");
-            return Err(ProgramError::IncorrectProgramId);
-        }
+        let creator_account = next_account_info(accounts_iter)?;
+        let payee_account = next_account_info(accounts_iter)?;
 
-        let payee_account: &AccountInfo = next_account_info(accounts_iter)?;
-        let system_program_account = next_account_info(accounts_iter)?;
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_creator(
+            &data_of_solana_ac,
+            event_id,
+            &event_creator,
+            creator_account,
+        )?;
 
-        if !payer_account.is_signer {
-            return Err(ProgramError::MissingRequiredSignature);
+        if !data_of_solana_ac.is_started() {
+            msg!("[RentShare] Only started events can be ended");
+            return Err(RentShareError::InvalidEventStatus.into());
         }
 
-        if payer_account.lamports() < rent_amount {
-            return Err(ProgramError::InsufficientFunds);
+        if data_of_solana_ac.has_vesting()
+            && !data_of_solana_ac.is_fully_vested(Clock::get()?.unix_timestamp)
+        {
+            msg!(
+                "[RentShare] Event escrow vests until {}",
+                data_of_solana_ac.vesting_start + data_of_solana_ac.vesting_duration
+            );
+            return Err(RentShareError::VestingIncomplete.into());
         }
 
-        // Transfer to self - do nothing
-        if payer_account.key == payee_account.key {
-            return Ok(());
-        }
+        if data_of_solana_ac.has_dispute_window() || data_of_solana_ac.requires_confirmation() {
+            let now = Clock::get()?.unix_timestamp;
+            data_of_solana_ac.status = EventStatus::Ended as u8;
 
-        // Initialize the Rent Agreement Account with the initial data
-        // Note: the structure of the data state must match the `space` the client used to create the account
-        let solana_data_account_data =
-            InitEvent::try_from_slice(&solana_data_account.data.borrow());
+            if data_of_solana_ac.has_dispute_window() {
+                let dispute_deadline = now
+                    .checked_add(data_of_solana_ac.dispute_window)
+                    .ok_or(ProgramError::InvalidAccountData)?;
+                data_of_solana_ac.dispute_deadline = dispute_deadline;
 
-        if solana_data_account_data.is_err() {
-            msg!(
-                "[RentShare] Rent agreement account data size incorrect: {}",
-                solana_data_account.try_data_len()?
-            );
-            return Err(ProgramError::InvalidAccountData);
+                EventRecord::DisputeWindowOpened {
+                    event: *solana_data_account.key,
+                    dispute_deadline,
+                }
+                .emit()?;
+            }
+
+            if data_of_solana_ac.requires_confirmation() {
+                let confirmation_deadline = now
+                    .checked_add(data_of_solana_ac.confirmation_window)
+                    .ok_or(ProgramError::InvalidAccountData)?;
+                data_of_solana_ac.confirmation_deadline = confirmation_deadline;
+
+                EventRecord::ConfirmationWindowOpened {
+                    event: *solana_data_account.key,
+                    confirmation_deadline,
+                }
+                .emit()?;
+            }
+
+            data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+        } else {
+            Self::settle(
+                accounts_iter,
+                solana_data_account,
+                &mut data_of_solana_ac,
+                payee_account,
+                creator_account,
+            )?;
         }
 
-        let mut data_of_solana_ac = solana_data_account_data.unwrap();
+        EventRecord::Ended {
+            event: *solana_data_account.key,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn particpate_in_event(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        particpate_amount: u64,
+        event_creator: Pubkey,
+        tier: u8,
+        allowlist_proof: Vec<[u8; 32]>,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let participant_account = next_account_info(accounts_iter)?;
+        let participant_wallet = next_account_info(accounts_iter)?;
+        let system_program_account = next_account_info(accounts_iter)?;
+
+        if !participant_wallet.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        if !data_of_solana_ac.is_open()
+            || data_of_solana_ac.is_registration_closed(Clock::get()?.unix_timestamp)
+        {
+            msg!("[RentShare] Event is not accepting registrations");
+            return Err(RentShareError::EventRegistrationClosed.into());
+        }
+
+        let mut participant = Self::load_participant(participant_account, program_id)?;
+
+        if participant.is_initialized() {
+            msg!("[RentShare] Participant account already initialized");
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        Self::check_registration_gates(
+            accounts_iter,
+            solana_data_account,
+            &data_of_solana_ac,
+            participant_wallet,
+            system_program_account,
+            &allowlist_proof,
+            program_id,
+        )?;
+
+        // Lottery entries only take a seat once drawn, so capacity isn't checked here
+        if data_of_solana_ac.is_lottery() {
+            Self::check_deposit(&data_of_solana_ac, tier, particpate_amount)?;
+
+            Self::escrow_deposit(
+                participant_wallet,
+                solana_data_account,
+                system_program_account,
+                particpate_amount,
+            )?;
+
+            let entry = data_of_solana_ac.lottery_entries;
+            data_of_solana_ac.lottery_entries += 1;
+            data_of_solana_ac.lottery_unresolved += 1;
+            data_of_solana_ac.lottery_pool = data_of_solana_ac
+                .lottery_pool
+                .checked_add(particpate_amount)
+                .ok_or(ProgramError::InvalidArgument)?;
+            data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+            participant.status = ParticipantStatus::Entered as u8;
+            participant.event = *solana_data_account.key;
+            participant.participant = *participant_wallet.key;
+            participant.tier = tier;
+            participant.deposit = particpate_amount;
+            participant.lottery_entry = entry;
+            participant.serialize(&mut &mut participant_account.data.borrow_mut()[..])?;
+
+            EventRecord::LotteryEntered {
+                event: *solana_data_account.key,
+                participant: *participant_wallet.key,
+                participant_account: *participant_account.key,
+                entry,
+                deposit: particpate_amount,
+            }
+            .emit()?;
+
+            return Ok(());
+        }
+
+        if data_of_solana_ac.participant_count >= data_of_solana_ac.total_partcipator {
+            msg!(
+                "[RentShare] Event already has {} participants",
+                data_of_solana_ac.total_partcipator
+            );
+            return Err(RentShareError::EventFull.into());
+        }
+
+        Self::check_deposit(&data_of_solana_ac, tier, particpate_amount)?;
+
+        let ticket_tier = match data_of_solana_ac.tier_mut(tier) {
+            Some(ticket_tier) => ticket_tier,
+            None => {
+                msg!("[RentShare] Unknown ticket tier: {}", tier);
+                return Err(RentShareError::InvalidTicketTier.into());
+            }
+        };
+
+        if ticket_tier.is_sold_out() {
+            msg!("[RentShare] Ticket tier {} is sold out", tier);
+            return Err(RentShareError::TicketTierSoldOut.into());
+        }
+        ticket_tier.sold += 1;
+
+        Self::escrow_deposit(
+            participant_wallet,
+            solana_data_account,
+            system_program_account,
+            particpate_amount,
+        )?;
+
+        data_of_solana_ac.participant_count += 1;
+        data_of_solana_ac.total_deposits = data_of_solana_ac
+            .total_deposits
+            .checked_add(particpate_amount)
+            .ok_or(ProgramError::InvalidArgument)?;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        participant.status = ParticipantStatus::Joined as u8;
+        participant.event = *solana_data_account.key;
+        participant.participant = *participant_wallet.key;
+        participant.tier = tier;
+        participant.deposit = particpate_amount;
+        if data_of_solana_ac.has_tickets() {
+            Self::issue_ticket(
+                accounts_iter,
+                solana_data_account.key,
+                &data_of_solana_ac,
+                &mut participant,
+                program_id,
+            )?;
+        }
+        participant.serialize(&mut &mut participant_account.data.borrow_mut()[..])?;
+
+        EventRecord::ParticipantJoined {
+            event: *solana_data_account.key,
+            participant: *participant_wallet.key,
+            participant_account: *participant_account.key,
+            tier,
+            deposit: particpate_amount,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn set_allowlist_root(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+        allowlist_root: [u8; 32],
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let creator_account = next_account_info(accounts_iter)?;
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_creator(
+            &data_of_solana_ac,
+            event_id,
+            &event_creator,
+            creator_account,
+        )?;
+
+        if !data_of_solana_ac.is_open() {
+            msg!("[RentShare] Allowlist can only change while registration is open");
+            return Err(RentShareError::EventRegistrationClosed.into());
+        }
+
+        data_of_solana_ac.allowlist_root = allowlist_root;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        EventRecord::AllowlistUpdated {
+            event: *solana_data_account.key,
+            allowlist_root,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn initialize_waitlist(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+        tier: u8,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let waitlist_account = next_account_info(accounts_iter)?;
+        let creator_account = next_account_info(accounts_iter)?;
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_creator(
+            &data_of_solana_ac,
+            event_id,
+            &event_creator,
+            creator_account,
+        )?;
+
+        if !data_of_solana_ac.is_open() {
+            msg!("[RentShare] Waitlists can only be added while registration is open");
+            return Err(RentShareError::EventRegistrationClosed.into());
+        }
+
+        if data_of_solana_ac.is_lottery() {
+            msg!("[RentShare] Seats of lottery events are only allocated by the draw");
+            return Err(RentShareError::LotteryUnsupported.into());
+        }
+
+        let mut waitlist = Self::load_waitlist(waitlist_account, program_id)?;
+        if waitlist.is_initialized() {
+            msg!("[RentShare] Waitlist account already initialized");
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        let ticket_tier = match data_of_solana_ac.tier_mut(tier) {
+            Some(ticket_tier) => ticket_tier,
+            None => {
+                msg!("[RentShare] Unknown ticket tier: {}", tier);
+                return Err(RentShareError::InvalidTicketTier.into());
+            }
+        };
+
+        if ticket_tier.has_waitlist() {
+            msg!("[RentShare] Ticket tier {} already has a waitlist", tier);
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        ticket_tier.waitlist = *waitlist_account.key;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        waitlist.status = WaitlistStatus::Active as u8;
+        waitlist.event = *solana_data_account.key;
+        waitlist.tier = tier;
+        waitlist.len = 0;
+        waitlist.serialize(&mut &mut waitlist_account.data.borrow_mut()[..])?;
+
+        EventRecord::WaitlistCreated {
+            event: *solana_data_account.key,
+            waitlist: *waitlist_account.key,
+            tier,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn join_waitlist(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+        tier: u8,
+        deposit: u64,
+        allowlist_proof: Vec<[u8; 32]>,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let waitlist_account = next_account_info(accounts_iter)?;
+        let participant_account = next_account_info(accounts_iter)?;
+        let waiting_wallet = next_account_info(accounts_iter)?;
+        let system_program_account = next_account_info(accounts_iter)?;
+
+        if !waiting_wallet.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        if !data_of_solana_ac.is_open()
+            || data_of_solana_ac.is_registration_closed(Clock::get()?.unix_timestamp)
+        {
+            msg!("[RentShare] Event is not accepting registrations");
+            return Err(RentShareError::EventRegistrationClosed.into());
+        }
+
+        let ticket_tier = match data_of_solana_ac.tier(tier) {
+            Some(ticket_tier) => *ticket_tier,
+            None => {
+                msg!("[RentShare] Unknown ticket tier: {}", tier);
+                return Err(RentShareError::InvalidTicketTier.into());
+            }
+        };
+
+        if !ticket_tier.has_waitlist() || ticket_tier.waitlist != *waitlist_account.key {
+            msg!(
+                "[RentShare] Waitlist account is not the waitlist of tier {}",
+                tier
+            );
+            return Err(RentShareError::WaitlistMismatch.into());
+        }
+
+        if !ticket_tier.is_sold_out()
+            && data_of_solana_ac.participant_count < data_of_solana_ac.total_partcipator
+        {
+            msg!("[RentShare] Ticket tier {} still has seats available", tier);
+            return Err(RentShareError::SeatsStillAvailable.into());
+        }
+
+        Self::check_registration_gates(
+            accounts_iter,
+            solana_data_account,
+            &data_of_solana_ac,
+            waiting_wallet,
+            system_program_account,
+            &allowlist_proof,
+            program_id,
+        )?;
+
+        let mut waitlist = Self::load_waitlist(waitlist_account, program_id)?;
+        if waitlist.position(waiting_wallet.key).is_some() {
+            msg!("[RentShare] Wallet is already on the waitlist");
+            return Err(RentShareError::AlreadyOnWaitlist.into());
+        }
+
+        if waitlist.is_full() {
+            msg!("[RentShare] Waitlist already has {} wallets", waitlist.len);
+            return Err(RentShareError::WaitlistFull.into());
+        }
+
+        let mut participant = Self::load_participant(participant_account, program_id)?;
+        if participant.is_initialized() {
+            msg!("[RentShare] Participant account already initialized");
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        Self::check_deposit(&data_of_solana_ac, tier, deposit)?;
+
+        Self::escrow_deposit(
+            waiting_wallet,
+            waitlist_account,
+            system_program_account,
+            deposit,
+        )?;
+
+        waitlist.push(WaitlistEntry {
+            wallet: *waiting_wallet.key,
+            deposit,
+            participant_account: *participant_account.key,
+        });
+        waitlist.serialize(&mut &mut waitlist_account.data.borrow_mut()[..])?;
+
+        participant.status = ParticipantStatus::Waiting as u8;
+        participant.event = *solana_data_account.key;
+        participant.participant = *waiting_wallet.key;
+        participant.tier = tier;
+        participant.serialize(&mut &mut participant_account.data.borrow_mut()[..])?;
+
+        EventRecord::WaitlistJoined {
+            event: *solana_data_account.key,
+            waitlist: *waitlist_account.key,
+            wallet: *waiting_wallet.key,
+            deposit,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn leave_waitlist(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let waitlist_account = next_account_info(accounts_iter)?;
+        let participant_account = next_account_info(accounts_iter)?;
+        let waiting_wallet = next_account_info(accounts_iter)?;
+
+        if !waiting_wallet.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        let mut waitlist = Self::load_waitlist(waitlist_account, program_id)?;
+        if !waitlist.is_initialized() || waitlist.event != *solana_data_account.key {
+            msg!("[RentShare] Waitlist account does not belong to this event");
+            return Err(RentShareError::WaitlistMismatch.into());
+        }
+
+        let index = match waitlist.position(waiting_wallet.key) {
+            Some(index) => index,
+            None => {
+                msg!("[RentShare] Wallet is not on the waitlist");
+                return Err(RentShareError::NotOnWaitlist.into());
+            }
+        };
+
+        if waitlist.entries[index].participant_account != *participant_account.key
+            || participant_account.owner != program_id
+        {
+            msg!("[RentShare] Participant account is not the one held on the waitlist");
+            return Err(RentShareError::ParticipantMismatch.into());
+        }
+
+        let entry = waitlist.remove(index);
+        Self::transfer_from_escrow(waitlist_account, waiting_wallet, entry.deposit)?;
+        waitlist.serialize(&mut &mut waitlist_account.data.borrow_mut()[..])?;
+        Self::close_account(participant_account, waiting_wallet)?;
+
+        EventRecord::WaitlistLeft {
+            event: *solana_data_account.key,
+            waitlist: *waitlist_account.key,
+            wallet: *waiting_wallet.key,
+        }
+        .emit()?;
+        if entry.deposit > 0 {
+            EventRecord::Refunded {
+                event: *solana_data_account.key,
+                recipient: *waiting_wallet.key,
+                amount: entry.deposit,
+            }
+            .emit()?;
+        }
+
+        Ok(())
+    }
+
+    fn withdraw_from_event(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let participant_account = next_account_info(accounts_iter)?;
+        let participant_wallet = next_account_info(accounts_iter)?;
+
+        if !participant_wallet.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        let mut participant =
+            Self::load_event_participant(participant_account, program_id, solana_data_account.key)?;
+        if participant.participant != *participant_wallet.key {
+            msg!("[RentShare] Participant account belongs to another wallet");
+            return Err(RentShareError::ParticipantMismatch.into());
+        }
+
+        if !data_of_solana_ac.is_open() && !data_of_solana_ac.is_terminated() {
+            msg!("[RentShare] Deposits can only be withdrawn before the event starts or after it is cancelled");
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
+
+        if participant.is_entered() {
+            if !data_of_solana_ac.is_terminated() {
+                msg!("[RentShare] Lottery entries are refunded once the lottery is drawn");
+                return Err(RentShareError::LotteryPending.into());
+            }
+
+            Self::refund_lottery_entry(
+                solana_data_account,
+                &mut data_of_solana_ac,
+                participant_account,
+                &participant,
+                participant_wallet,
+            )?;
+            data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+            EventRecord::Withdrawn {
+                event: *solana_data_account.key,
+                participant: *participant_wallet.key,
+                participant_account: *participant_account.key,
+            }
+            .emit()?;
+
+            return Ok(());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let funding_failed = data_of_solana_ac.is_funding_failed(now);
+        if data_of_solana_ac.is_open()
+            && data_of_solana_ac.has_funding_goal()
+            && data_of_solana_ac.is_registration_closed(now)
+            && !funding_failed
+        {
+            msg!("[RentShare] Event reached its funding goal, deposits are locked");
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
+
+        if participant.has_ticket() {
+            let ticket_accounts = TicketAccounts::next(accounts_iter)?;
+            Self::check_held_ticket(
+                &ticket_accounts,
+                solana_data_account.key,
+                &data_of_solana_ac,
+                participant_wallet.key,
+                program_id,
+            )?;
+            ticket_accounts.burn(participant_wallet)?;
+        }
+
+        // Cancelled and unfunded events refund in full, the schedule only covers participants backing out
+        if data_of_solana_ac.is_open() && !funding_failed && data_of_solana_ac.has_refund_schedule()
+        {
+            let payee_account = next_account_info(accounts_iter)?;
+            let retained =
+                participant.deposit - data_of_solana_ac.refund_amount(participant.deposit, now);
+
+            // The payee accounts are always taken, so the waitlist accounts keep their position
+            let split = Self::pay_payees(
+                accounts_iter,
+                solana_data_account,
+                &data_of_solana_ac,
+                payee_account,
+                retained,
+            )?;
+            if retained > 0 {
+                data_of_solana_ac.total_deposits = data_of_solana_ac
+                    .total_deposits
+                    .checked_sub(retained)
+                    .ok_or(ProgramError::InvalidAccountData)?;
+                participant.deposit -= retained;
+
+                for (payee, amount) in split {
+                    EventRecord::RefundRetained {
+                        event: *solana_data_account.key,
+                        participant: *participant_wallet.key,
+                        payee,
+                        amount,
+                    }
+                    .emit()?;
+                }
+            }
+        }
+
+        // Deposits already vested to the payees of a cancelled event aren't refunded
+        if data_of_solana_ac.is_terminated() {
+            participant.deposit = data_of_solana_ac.unvested_refund(participant.deposit);
+
+            // Unvested refunds round down, the last seat pays what they leave in escrow to the payees
+            if data_of_solana_ac.vested_claimed > 0 && data_of_solana_ac.participant_count == 1 {
+                let payee_account = next_account_info(accounts_iter)?;
+                let remainder = data_of_solana_ac
+                    .total_deposits
+                    .checked_sub(participant.deposit)
+                    .ok_or(ProgramError::InvalidAccountData)?;
+                let split = Self::pay_payees(
+                    accounts_iter,
+                    solana_data_account,
+                    &data_of_solana_ac,
+                    payee_account,
+                    remainder,
+                )?;
+                data_of_solana_ac.total_deposits -= remainder;
+
+                for (payee, amount) in split {
+                    EventRecord::PaidOut {
+                        event: *solana_data_account.key,
+                        payee,
+                        amount,
+                    }
+                    .emit()?;
+                }
+            }
+        }
+
+        let refund_account =
+            Self::next_refund_account(accounts_iter, &participant, participant_wallet)?;
+
+        // The bond compensates whoever paid for the seat, like its refund
+        if data_of_solana_ac.is_terminated() && data_of_solana_ac.bond_share > 0 {
+            let bond_share = data_of_solana_ac.bond_share;
+            Self::transfer_from_escrow(solana_data_account, refund_account, bond_share)?;
+            data_of_solana_ac.bond = data_of_solana_ac
+                .bond
+                .checked_sub(bond_share)
+                .ok_or(ProgramError::InvalidAccountData)?;
+
+            EventRecord::BondCompensation {
+                event: *solana_data_account.key,
+                recipient: *refund_account.key,
+                amount: bond_share,
+            }
+            .emit()?;
+        }
+
+        Self::release_seat(
+            accounts_iter,
+            solana_data_account,
+            &mut data_of_solana_ac,
+            participant_account,
+            participant,
+            refund_account,
+            program_id,
+        )?;
+
+        EventRecord::Withdrawn {
+            event: *solana_data_account.key,
+            participant: *participant_wallet.key,
+            participant_account: *participant_account.key,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn remove_participant(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let creator_account = next_account_info(accounts_iter)?;
+        let participant_account = next_account_info(accounts_iter)?;
+        let participant_wallet = next_account_info(accounts_iter)?;
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_creator(
+            &data_of_solana_ac,
+            event_id,
+            &event_creator,
+            creator_account,
+        )?;
+
+        let participant =
+            Self::load_event_participant(participant_account, program_id, solana_data_account.key)?;
+        if participant.participant != *participant_wallet.key {
+            msg!("[RentShare] Participant account belongs to another wallet");
+            return Err(RentShareError::ParticipantMismatch.into());
+        }
+
+        if !data_of_solana_ac.is_open() {
+            msg!("[RentShare] Participants can only be removed while registration is open");
+            return Err(RentShareError::EventRegistrationClosed.into());
+        }
+
+        if participant.is_entered() {
+            msg!("[RentShare] Lottery entries are refunded once the lottery is drawn");
+            return Err(RentShareError::LotteryPending.into());
+        }
+
+        // The creator can't burn a ticket held by someone else, so it is frozen instead
+        if participant.has_ticket() {
+            let ticket_accounts = TicketAccounts::next(accounts_iter)?;
+            let bump_seed = Self::check_held_ticket(
+                &ticket_accounts,
+                solana_data_account.key,
+                &data_of_solana_ac,
+                participant_wallet.key,
+                program_id,
+            )?;
+            ticket_accounts.freeze(solana_data_account.key, bump_seed)?;
+
+            EventRecord::TicketFrozen {
+                event: *solana_data_account.key,
+                ticket_account: *ticket_accounts.token_account.key,
+            }
+            .emit()?;
+        }
+
+        let refund_account =
+            Self::next_refund_account(accounts_iter, &participant, participant_wallet)?;
+        Self::release_seat(
+            accounts_iter,
+            solana_data_account,
+            &mut data_of_solana_ac,
+            participant_account,
+            participant,
+            refund_account,
+            program_id,
+        )?;
+
+        EventRecord::ParticipantRemoved {
+            event: *solana_data_account.key,
+            participant: *participant_wallet.key,
+            participant_account: *participant_account.key,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn set_transfer_policy(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+        transfer_policy: TransferPolicy,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let creator_account = next_account_info(accounts_iter)?;
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_creator(
+            &data_of_solana_ac,
+            event_id,
+            &event_creator,
+            creator_account,
+        )?;
+
+        data_of_solana_ac.transfer_policy = transfer_policy as u8;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        EventRecord::TransferPolicyUpdated {
+            event: *solana_data_account.key,
+            transfer_policy: transfer_policy as u8,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn transfer_participation(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+        allowlist_proof: Vec<[u8; 32]>,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let participant_account = next_account_info(accounts_iter)?;
+        let participant_wallet = next_account_info(accounts_iter)?;
+        let new_participant_wallet = next_account_info(accounts_iter)?;
+
+        if !participant_wallet.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        if data_of_solana_ac.transfer_policy == TransferPolicy::Disabled as u8 {
+            msg!(
+                "[RentShare] Seat transfers are disabled for event {}",
+                event_id
+            );
+            return Err(RentShareError::TransfersDisabled.into());
+        }
+
+        // The permit signer only vouched for the wallets it issued permits to
+        if data_of_solana_ac.requires_permit() {
+            msg!(
+                "[RentShare] Seats of event {} require a join permit and can't be transferred",
+                event_id
+            );
+            return Err(RentShareError::TransfersDisabled.into());
+        }
+
+        if data_of_solana_ac.transfer_policy == TransferPolicy::RequiresCreator as u8 {
+            let creator_account = next_account_info(accounts_iter)?;
+            Self::check_event_creator(
+                &data_of_solana_ac,
+                event_id,
+                &event_creator,
+                creator_account,
+            )?;
+        }
+
+        if !data_of_solana_ac.is_open() && !data_of_solana_ac.is_started() {
+            msg!("[RentShare] Seats can only be transferred before the event ends");
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
+
+        let mut participant =
+            Self::load_event_participant(participant_account, program_id, solana_data_account.key)?;
+        if participant.participant != *participant_wallet.key || !participant.is_joined() {
+            msg!("[RentShare] Participant account is not a paid seat of this wallet");
+            return Err(RentShareError::ParticipantMismatch.into());
+        }
+
+        if data_of_solana_ac.has_allowlist()
+            && !allowlist::verify(
+                &data_of_solana_ac.allowlist_root,
+                new_participant_wallet.key,
+                &allowlist_proof,
+            )
+        {
+            msg!("[RentShare] New participant is not on the event allowlist");
+            return Err(RentShareError::NotOnAllowlist.into());
+        }
+
+        // The ticket stays with the old wallet's token account, so burn it and let the new wallet claim one
+        if participant.has_ticket() {
+            let ticket_accounts = TicketAccounts::next(accounts_iter)?;
+            Self::check_held_ticket(
+                &ticket_accounts,
+                solana_data_account.key,
+                &data_of_solana_ac,
+                participant_wallet.key,
+                program_id,
+            )?;
+            ticket_accounts.burn(participant_wallet)?;
+            participant.ticket_account = Pubkey::default();
+        }
+
+        // The group payer funded the seat, so only it can hand the refunds over to the new wallet
+        if participant.has_group_payer() {
+            let payer_account = next_account_info(accounts_iter)?;
+            if *payer_account.key != participant.payer {
+                msg!("[RentShare] Payer account is not the group payer of the seat");
+                return Err(RentShareError::ParticipantMismatch.into());
+            }
+            if !payer_account.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            participant.payer = Pubkey::default();
+        }
+
+        participant.participant = *new_participant_wallet.key;
+        participant.serialize(&mut &mut participant_account.data.borrow_mut()[..])?;
+
+        EventRecord::ParticipationTransferred {
+            event: *solana_data_account.key,
+            participant_account: *participant_account.key,
+            from: *participant_wallet.key,
+            to: *new_participant_wallet.key,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn initialize_ticket_mint(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+        freeze_tickets: bool,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let mint_account = next_account_info(accounts_iter)?;
+        let creator_account = next_account_info(accounts_iter)?;
+        let rent_sysvar = next_account_info(accounts_iter)?;
+        let token_program_account = next_account_info(accounts_iter)?;
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_creator(
+            &data_of_solana_ac,
+            event_id,
+            &event_creator,
+            creator_account,
+        )?;
+
+        // Every participant must get a ticket, so the mint has to exist before the first join
+        if !data_of_solana_ac.is_open() || data_of_solana_ac.participant_count > 0 {
+            msg!("[RentShare] Tickets can only be enabled before anyone joins the event");
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
+
+        if data_of_solana_ac.is_lottery() {
+            msg!("[RentShare] Lottery events don't issue tickets");
+            return Err(RentShareError::LotteryUnsupported.into());
+        }
+
+        if data_of_solana_ac.has_tickets() {
+            msg!("[RentShare] Event already issues tickets");
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        ticket::initialize_mint(
+            mint_account,
+            rent_sysvar,
+            token_program_account,
+            solana_data_account.key,
+            program_id,
+        )?;
+
+        data_of_solana_ac.ticket_mint = *mint_account.key;
+        data_of_solana_ac.freeze_tickets = freeze_tickets;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        EventRecord::TicketMintCreated {
+            event: *solana_data_account.key,
+            ticket_mint: *mint_account.key,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn claim_ticket(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let participant_account = next_account_info(accounts_iter)?;
+        let participant_wallet = next_account_info(accounts_iter)?;
+
+        if !participant_wallet.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        if !data_of_solana_ac.has_tickets() {
+            msg!("[RentShare] Event does not issue tickets");
+            return Err(RentShareError::TicketsNotEnabled.into());
+        }
+
+        if !data_of_solana_ac.is_open() && !data_of_solana_ac.is_started() {
+            msg!("[RentShare] Tickets can only be claimed before the event ends");
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
+
+        let mut participant =
+            Self::load_event_participant(participant_account, program_id, solana_data_account.key)?;
+        if participant.participant != *participant_wallet.key || !participant.is_joined() {
+            msg!("[RentShare] Participant account is not a paid seat of this wallet");
+            return Err(RentShareError::ParticipantMismatch.into());
+        }
+
+        if participant.has_ticket() {
+            msg!("[RentShare] Participant already holds a ticket");
+            return Err(RentShareError::TicketAlreadyIssued.into());
+        }
+
+        Self::issue_ticket(
+            accounts_iter,
+            solana_data_account.key,
+            &data_of_solana_ac,
+            &mut participant,
+            program_id,
+        )?;
+        participant.serialize(&mut &mut participant_account.data.borrow_mut()[..])?;
+
+        Ok(())
+    }
+
+    fn freeze_ticket(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let participant_account = next_account_info(accounts_iter)?;
+        let ticket_accounts = TicketAccounts::next(accounts_iter)?;
+
+        let data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        if !data_of_solana_ac.has_tickets() || !data_of_solana_ac.freeze_tickets {
+            msg!("[RentShare] Event tickets can not be frozen");
+            return Err(RentShareError::TicketsNotEnabled.into());
+        }
+
+        if !data_of_solana_ac.is_complete() && !data_of_solana_ac.is_ended() {
+            msg!("[RentShare] Tickets can only be frozen after the event ends");
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
+
+        let participant =
+            Self::load_event_participant(participant_account, program_id, solana_data_account.key)?;
+        let bump_seed = Self::check_ticket(
+            &ticket_accounts,
+            solana_data_account.key,
+            &data_of_solana_ac,
+            &participant,
+            program_id,
+        )?;
+        ticket_accounts.freeze(solana_data_account.key, bump_seed)?;
+
+        EventRecord::TicketFrozen {
+            event: *solana_data_account.key,
+            ticket_account: participant.ticket_account,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn initialize_event_series(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        series_creator: Pubkey,
+        first_event_id: u64,
+        payee_pubkey: Pubkey,
+        fix_deposit_amount_per_person: u64,
+        total_partcipator: u64,
+        organizers: Vec<Pubkey>,
+        first_start: i64,
+        interval: i64,
+        max_occurrences: u64,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let series_account = next_account_info(accounts_iter)?;
+        let creator_account = next_account_info(accounts_iter)?;
+
+        if !creator_account.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if *creator_account.key != series_creator {
+            msg!("[RentShare] Signer is not the series creator");
+            return Err(RentShareError::EventSeriesMismatch.into());
+        }
+
+        let rent = &Rent::from_account_info(next_account_info(accounts_iter)?)?;
+        if !rent.is_exempt(series_account.lamports(), series_account.data_len()) {
+            msg!("[RentShare] Series account is not rent exempt");
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+
+        let mut series = Self::load_series(series_account, program_id)?;
+        if series.is_initialized() {
+            msg!("[RentShare] Series account already initialized");
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        if organizers.len() > MAX_SERIES_ORGANIZERS {
+            msg!(
+                "[RentShare] Too many series organizers: {} (max {})",
+                organizers.len(),
+                MAX_SERIES_ORGANIZERS
+            );
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        if total_partcipator == 0 {
+            msg!("[RentShare] Series occurrences have no capacity");
+            return Err(RentShareError::InvalidTicketTier.into());
+        }
+
+        series.status = SeriesStatus::Active as u8;
+        series.series_creator = series_creator;
+        series.first_event_id = first_event_id;
+        series.payee_pubkey = payee_pubkey;
+        series.fix_deposit_amount_per_person = fix_deposit_amount_per_person;
+        series.total_partcipator = total_partcipator;
+        series.organizer_count = organizers.len() as u8;
+        series.organizers[..organizers.len()].copy_from_slice(&organizers);
+        series.first_start = first_start;
+        series.interval = interval;
+        series.max_occurrences = max_occurrences;
+        series.occurrence_count = 0;
+
+        if interval <= 0
+            || series
+                .occurrence_start(max_occurrences.saturating_sub(1))
+                .is_none()
+        {
+            msg!("[RentShare] Invalid recurrence interval: {}", interval);
+            return Err(RentShareError::InvalidRecurrence.into());
+        }
+
+        series.serialize(&mut &mut series_account.data.borrow_mut()[..])?;
+
+        EventRecord::SeriesCreated {
+            series: *series_account.key,
+            series_creator,
+            first_event_id,
+            first_start,
+            interval,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn instantiate_occurrence(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        series_creator: Pubkey,
+        first_event_id: u64,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let series_account = next_account_info(accounts_iter)?;
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let organizer_account = next_account_info(accounts_iter)?;
+        let system_program_account = next_account_info(accounts_iter)?;
+
+        let mut series = Self::load_series(series_account, program_id)?;
+        if !series.is_initialized() {
+            msg!("[RentShare] Series account not initialized");
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        if series.series_creator != series_creator || series.first_event_id != first_event_id {
+            msg!("[RentShare] Instruction does not match the series account");
+            return Err(RentShareError::EventSeriesMismatch.into());
+        }
+
+        if !organizer_account.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if !series.is_organizer(organizer_account.key) {
+            msg!(
+                "[RentShare] {} is not an organizer of the series",
+                organizer_account.key
+            );
+            return Err(RentShareError::NotSeriesOrganizer.into());
+        }
+
+        if series.is_finished() {
+            msg!(
+                "[RentShare] All {} occurrences of the series were instantiated",
+                series.max_occurrences
+            );
+            return Err(RentShareError::EventSeriesFinished.into());
+        }
+
+        let occurrence = series.occurrence_count;
+        let (event_id, start_time) = match (
+            series.occurrence_event_id(occurrence),
+            series.occurrence_start(occurrence),
+        ) {
+            (Some(event_id), Some(start_time)) => (event_id, start_time),
+            _ => {
+                msg!("[RentShare] Occurrence {} is out of range", occurrence);
+                return Err(RentShareError::EventSeriesFinished.into());
+            }
+        };
+
+        let index = occurrence.to_le_bytes();
+        let seeds = series::occurrence_seeds(series_account.key, &index);
+        let (occurrence_address, bump_seed) = Pubkey::find_program_address(&seeds, program_id);
+        if occurrence_address != *solana_data_account.key {
+            msg!(
+                "[RentShare] Event account is not the address of occurrence {}",
+                occurrence
+            );
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        Self::create_pda_account(
+            organizer_account,
+            solana_data_account,
+            system_program_account,
+            InitEvent::LEN,
+            program_id,
+            &[seeds[0], seeds[1], seeds[2], &[bump_seed]],
+        )?;
+
+        let mut data_of_solana_ac = InitEvent::try_from_slice(&solana_data_account.data.borrow())?;
+        data_of_solana_ac.status = EventStatus::Active as u8;
+        data_of_solana_ac.payee_pubkey = series.payee_pubkey;
+        data_of_solana_ac.event_creator = *organizer_account.key;
+        data_of_solana_ac.event_id = event_id;
+        data_of_solana_ac.fix_deposit_amount_per_person = series.fix_deposit_amount_per_person;
+        data_of_solana_ac.total_partcipator = series.total_partcipator;
+        data_of_solana_ac.tier_count = 1;
+        data_of_solana_ac.tiers[0] = EventTier {
+            price: series.fix_deposit_amount_per_person,
+            capacity: series.total_partcipator,
+            ..EventTier::default()
+        };
+        data_of_solana_ac.series = *series_account.key;
+        data_of_solana_ac.start_time = start_time;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        series.occurrence_count += 1;
+        series.serialize(&mut &mut series_account.data.borrow_mut()[..])?;
+
+        EventRecord::EventCreated {
+            event: *solana_data_account.key,
+            event_id,
+            event_creator: *organizer_account.key,
+            payee: series.payee_pubkey,
+            total_partcipator: series.total_partcipator,
+            tier_count: 1,
+        }
+        .emit()?;
+        EventRecord::OccurrenceCreated {
+            series: *series_account.key,
+            event: *solana_data_account.key,
+            event_id,
+            occurrence,
+            start_time,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn open_dispute(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let participant_account = next_account_info(accounts_iter)?;
+        let participant_wallet = next_account_info(accounts_iter)?;
+
+        if !participant_wallet.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        let participant =
+            Self::load_event_participant(participant_account, program_id, solana_data_account.key)?;
+        if participant.participant != *participant_wallet.key || !participant.is_joined() {
+            msg!("[RentShare] Participant account is not a paid seat of this wallet");
+            return Err(RentShareError::ParticipantMismatch.into());
+        }
+
+        if !data_of_solana_ac.is_ended()
+            || Clock::get()?.unix_timestamp > data_of_solana_ac.dispute_deadline
+        {
+            msg!("[RentShare] Event payout can no longer be disputed");
+            return Err(RentShareError::DisputeWindowClosed.into());
+        }
+
+        if data_of_solana_ac.dispute_status != DisputeStatus::None as u8 {
+            msg!(
+                "[RentShare] Event was already disputed by {}",
+                data_of_solana_ac.disputant
+            );
+            return Err(RentShareError::DisputeAlreadyOpened.into());
+        }
+
+        data_of_solana_ac.dispute_status = DisputeStatus::Open as u8;
+        data_of_solana_ac.disputant = *participant_wallet.key;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        EventRecord::DisputeOpened {
+            event: *solana_data_account.key,
+            disputant: *participant_wallet.key,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn resolve_dispute(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+        refund_bps: u16,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let arbiter_account = next_account_info(accounts_iter)?;
+
+        if !arbiter_account.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        if data_of_solana_ac.arbiter != *arbiter_account.key {
+            msg!("[RentShare] Signer is not the event arbiter");
+            return Err(RentShareError::ArbiterMismatch.into());
+        }
+
+        if !data_of_solana_ac.is_disputed() {
+            msg!("[RentShare] Event has no open dispute");
+            return Err(RentShareError::NoOpenDispute.into());
+        }
+
+        if refund_bps > FULL_REFUND_BPS {
+            msg!(
+                "[RentShare] Refund of {} bps exceeds the deposits",
+                refund_bps
+            );
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // Rounding every refund down keeps their sum within the reserve
+        let reserve =
+            data_of_solana_ac.total_deposits as u128 * refund_bps as u128 / FULL_REFUND_BPS as u128;
+        data_of_solana_ac.dispute_status = DisputeStatus::Resolved as u8;
+        data_of_solana_ac.dispute_refund_bps = refund_bps;
+        data_of_solana_ac.dispute_refund_reserve = reserve as u64;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        EventRecord::DisputeResolved {
+            event: *solana_data_account.key,
+            arbiter: *arbiter_account.key,
+            refund_bps,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn settle_event(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let payee_account = next_account_info(accounts_iter)?;
+        let creator_account = next_account_info(accounts_iter)?;
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        if !data_of_solana_ac.is_ended() {
+            msg!("[RentShare] Only ended events waiting for their payout can be settled");
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
+
+        if data_of_solana_ac.event_creator != *creator_account.key {
+            msg!("[RentShare] This is not the event creator.");
+            return Err(RentShareError::EventCreatorMismatch.into());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        if data_of_solana_ac.requires_confirmation() && !data_of_solana_ac.is_confirmed() {
+            if now <= data_of_solana_ac.confirmation_deadline {
+                msg!(
+                    "[RentShare] {} confirmations so far, confirmations close at {}",
+                    data_of_solana_ac.confirmations,
+                    data_of_solana_ac.confirmation_deadline
+                );
+                return Err(RentShareError::ConfirmationPending.into());
+            }
+
+            // Without quorum the event is treated as cancelled, dispute refunds are superseded
+            data_of_solana_ac.status = EventStatus::Terminated as u8;
+            data_of_solana_ac.dispute_refund_reserve = 0;
+            Self::slash_bond(solana_data_account, &mut data_of_solana_ac, creator_account)?;
+            data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+            EventRecord::ConfirmationFailed {
+                event: *solana_data_account.key,
+                confirmations: data_of_solana_ac.confirmations,
+            }
+            .emit()?;
+
+            return Ok(());
+        }
+
+        if now <= data_of_solana_ac.dispute_deadline {
+            msg!(
+                "[RentShare] Dispute window closes at {}",
+                data_of_solana_ac.dispute_deadline
+            );
+            return Err(RentShareError::DisputeWindowOpen.into());
+        }
+
+        if data_of_solana_ac.is_disputed() {
+            msg!("[RentShare] Event payout is held by an open dispute");
+            return Err(RentShareError::DisputeUnresolved.into());
+        }
+
+        Self::settle(
+            accounts_iter,
+            solana_data_account,
+            &mut data_of_solana_ac,
+            payee_account,
+            creator_account,
+        )?;
+
+        EventRecord::Settled {
+            event: *solana_data_account.key,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn claim_dispute_refund(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let participant_account = next_account_info(accounts_iter)?;
+        let participant_wallet = next_account_info(accounts_iter)?;
+
+        if !participant_wallet.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        let mut participant =
+            Self::load_event_participant(participant_account, program_id, solana_data_account.key)?;
+        if participant.participant != *participant_wallet.key {
+            msg!("[RentShare] Participant account belongs to another wallet");
+            return Err(RentShareError::ParticipantMismatch.into());
+        }
+
+        // The granted share of the deposit, less what vested payees already claimed of it
+        let granted = (participant.deposit as u128 * data_of_solana_ac.dispute_refund_bps as u128
+            / FULL_REFUND_BPS as u128) as u64;
+        let refund = data_of_solana_ac.unvested_refund(granted);
+        if !data_of_solana_ac.is_dispute_resolved()
+            || data_of_solana_ac.is_terminated()
+            || participant.dispute_refund_claimed
+            || refund == 0
+        {
+            msg!("[RentShare] No dispute refund to claim");
+            return Err(RentShareError::NothingToClaim.into());
+        }
+
+        let refund_account =
+            Self::next_refund_account(accounts_iter, &participant, participant_wallet)?;
+        Self::transfer_from_escrow(solana_data_account, refund_account, refund)?;
+        data_of_solana_ac.total_deposits = data_of_solana_ac
+            .total_deposits
+            .checked_sub(refund)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        data_of_solana_ac.dispute_refund_reserve = data_of_solana_ac
+            .dispute_refund_reserve
+            .checked_sub(refund)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        participant.deposit -= granted;
+        participant.dispute_refund_claimed = true;
+        participant.serialize(&mut &mut participant_account.data.borrow_mut()[..])?;
+
+        EventRecord::Refunded {
+            event: *solana_data_account.key,
+            recipient: *refund_account.key,
+            amount: refund,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn confirm_event(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let participant_account = next_account_info(accounts_iter)?;
+        let participant_wallet = next_account_info(accounts_iter)?;
+
+        if !participant_wallet.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        if !data_of_solana_ac.is_ended()
+            || !data_of_solana_ac.requires_confirmation()
+            || Clock::get()?.unix_timestamp > data_of_solana_ac.confirmation_deadline
+        {
+            msg!("[RentShare] Event is not taking confirmations");
+            return Err(RentShareError::ConfirmationClosed.into());
+        }
+
+        let mut participant =
+            Self::load_event_participant(participant_account, program_id, solana_data_account.key)?;
+        if participant.participant != *participant_wallet.key || !participant.is_joined() {
+            msg!("[RentShare] Participant account is not a paid seat of this wallet");
+            return Err(RentShareError::ParticipantMismatch.into());
+        }
+
+        if participant.confirmed {
+            msg!("[RentShare] Participant already confirmed the event");
+            return Err(RentShareError::AlreadyConfirmed.into());
+        }
+
+        participant.confirmed = true;
+        participant.serialize(&mut &mut participant_account.data.borrow_mut()[..])?;
+
+        data_of_solana_ac.confirmations += 1;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        EventRecord::ParticipantConfirmed {
+            event: *solana_data_account.key,
+            participant: *participant_wallet.key,
+            confirmations: data_of_solana_ac.confirmations,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn claim_vested(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let payee_account = next_account_info(accounts_iter)?;
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        if !data_of_solana_ac.is_started() || !data_of_solana_ac.has_vesting() {
+            msg!("[RentShare] Only started vesting events release vested funds");
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
+
+        let vested = data_of_solana_ac.vested_amount(Clock::get()?.unix_timestamp);
+        let amount = vested.saturating_sub(data_of_solana_ac.vested_claimed);
+        if amount == 0 {
+            msg!("[RentShare] Nothing vested since the last claim");
+            return Err(RentShareError::NothingToClaim.into());
+        }
+
+        let split = Self::pay_payees(
+            accounts_iter,
+            solana_data_account,
+            &data_of_solana_ac,
+            payee_account,
+            amount,
+        )?;
+
+        data_of_solana_ac.total_deposits = data_of_solana_ac
+            .total_deposits
+            .checked_sub(amount)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        data_of_solana_ac.vested_claimed += amount;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        for (payee, share) in split {
+            EventRecord::PaidOut {
+                event: *solana_data_account.key,
+                payee,
+                amount: share,
+            }
+            .emit()?;
+        }
+        EventRecord::VestedClaimed {
+            event: *solana_data_account.key,
+            amount,
+            vested_claimed: data_of_solana_ac.vested_claimed,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn batch_participate_in_event(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+        tier: u8,
+        particpate_amount: u64,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let payer_account = next_account_info(accounts_iter)?;
+        let system_program_account = next_account_info(accounts_iter)?;
+        let seat_accounts = accounts_iter.as_slice();
+
+        if !payer_account.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        if !data_of_solana_ac.is_open()
+            || data_of_solana_ac.is_registration_closed(Clock::get()?.unix_timestamp)
+        {
+            msg!("[RentShare] Event is not accepting registrations");
+            return Err(RentShareError::EventRegistrationClosed.into());
+        }
+
+        if data_of_solana_ac.has_allowlist()
+            || data_of_solana_ac.requires_permit()
+            || data_of_solana_ac.has_tickets()
+        {
+            msg!(
+                "[RentShare] Participants of event {} have to join individually",
+                event_id
+            );
+            return Err(RentShareError::GroupRegistrationUnsupported.into());
+        }
+
+        if data_of_solana_ac.is_lottery() {
+            msg!("[RentShare] Lottery entries have to be made individually");
+            return Err(RentShareError::LotteryUnsupported.into());
+        }
+
+        if seat_accounts.is_empty() || !seat_accounts.chunks_exact(2).remainder().is_empty() {
+            msg!("[RentShare] Expected participant account and wallet pairs");
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let seats = (seat_accounts.len() / 2) as u64;
+
+        // All seats are checked up front so the group is registered entirely or not at all
+        if data_of_solana_ac.participant_count + seats > data_of_solana_ac.total_partcipator {
+            msg!(
+                "[RentShare] Event has {} seats left, {} requested",
+                data_of_solana_ac.total_partcipator - data_of_solana_ac.participant_count,
+                seats
+            );
+            return Err(RentShareError::EventFull.into());
+        }
+
+        Self::check_deposit(&data_of_solana_ac, tier, particpate_amount)?;
+
+        let ticket_tier = match data_of_solana_ac.tier_mut(tier) {
+            Some(ticket_tier) => ticket_tier,
+            None => {
+                msg!("[RentShare] Unknown ticket tier: {}", tier);
+                return Err(RentShareError::InvalidTicketTier.into());
+            }
+        };
+
+        if ticket_tier.sold + seats > ticket_tier.capacity {
+            msg!(
+                "[RentShare] Ticket tier {} has fewer than {} seats left",
+                tier,
+                seats
+            );
+            return Err(RentShareError::TicketTierSoldOut.into());
+        }
+        ticket_tier.sold += seats;
+
+        let deposit = particpate_amount
+            .checked_mul(seats)
+            .ok_or(ProgramError::InvalidArgument)?;
+        Self::escrow_deposit(
+            payer_account,
+            solana_data_account,
+            system_program_account,
+            deposit,
+        )?;
+
+        for seat in seat_accounts.chunks_exact(2) {
+            let (participant_account, participant_wallet) = (&seat[0], &seat[1]);
+
+            let mut participant = Self::load_participant(participant_account, program_id)?;
+            if participant.is_initialized() {
+                msg!("[RentShare] Participant account already initialized");
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+
+            participant.status = ParticipantStatus::Joined as u8;
+            participant.event = *solana_data_account.key;
+            participant.participant = *participant_wallet.key;
+            participant.tier = tier;
+            participant.deposit = particpate_amount;
+            participant.payer = *payer_account.key;
+            participant.serialize(&mut &mut participant_account.data.borrow_mut()[..])?;
+
+            EventRecord::ParticipantJoined {
+                event: *solana_data_account.key,
+                participant: *participant_wallet.key,
+                participant_account: *participant_account.key,
+                tier,
+                deposit: particpate_amount,
+            }
+            .emit()?;
+        }
+
+        data_of_solana_ac.participant_count += seats;
+        data_of_solana_ac.total_deposits = data_of_solana_ac
+            .total_deposits
+            .checked_add(deposit)
+            .ok_or(ProgramError::InvalidArgument)?;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        EventRecord::GroupJoined {
+            event: *solana_data_account.key,
+            payer: *payer_account.key,
+            tier,
+            seats,
+            deposit,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn sponsor(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+        amount: u64,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let sponsorship_account = next_account_info(accounts_iter)?;
+        let sponsor_wallet = next_account_info(accounts_iter)?;
+        let system_program_account = next_account_info(accounts_iter)?;
+
+        if !sponsor_wallet.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        if !data_of_solana_ac.is_open() && !data_of_solana_ac.is_started() {
+            msg!("[RentShare] Only open or started events can be sponsored");
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
+
+        if amount == 0 {
+            msg!("[RentShare] Sponsorship amount must be positive");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let mut sponsorship = Self::load_sponsorship(sponsorship_account, program_id)?;
+        if !sponsorship.is_initialized() {
+            sponsorship.status = SponsorshipStatus::Active as u8;
+            sponsorship.event = *solana_data_account.key;
+            sponsorship.sponsor = *sponsor_wallet.key;
+        } else if sponsorship.event != *solana_data_account.key
+            || sponsorship.sponsor != *sponsor_wallet.key
+        {
+            msg!("[RentShare] Sponsorship account belongs to another event or sponsor");
+            return Err(RentShareError::SponsorshipMismatch.into());
+        }
+
+        Self::escrow_deposit(
+            sponsor_wallet,
+            solana_data_account,
+            system_program_account,
+            amount,
+        )?;
+
+        sponsorship.amount = sponsorship
+            .amount
+            .checked_add(amount)
+            .ok_or(ProgramError::InvalidArgument)?;
+        sponsorship.serialize(&mut &mut sponsorship_account.data.borrow_mut()[..])?;
+
+        data_of_solana_ac.sponsor_pool = data_of_solana_ac
+            .sponsor_pool
+            .checked_add(amount)
+            .ok_or(ProgramError::InvalidArgument)?;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        EventRecord::Sponsored {
+            event: *solana_data_account.key,
+            sponsor: *sponsor_wallet.key,
+            amount,
+            sponsor_pool: data_of_solana_ac.sponsor_pool,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn set_sponsor_pool_destination(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+        destination: SponsorPoolDestination,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let creator_account = next_account_info(accounts_iter)?;
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_creator(
+            &data_of_solana_ac,
+            event_id,
+            &event_creator,
+            creator_account,
+        )?;
+
+        if data_of_solana_ac.is_complete() || data_of_solana_ac.is_terminated() {
+            msg!(
+                "[RentShare] Sponsor pool of event {} was already distributed",
+                event_id
+            );
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
+
+        if destination == SponsorPoolDestination::Ranked && !data_of_solana_ac.has_prize_table() {
+            msg!("[RentShare] Event {} has no prize table", event_id);
+            return Err(RentShareError::InvalidPrizeTable.into());
+        }
+
+        data_of_solana_ac.sponsor_pool_destination = destination as u8;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        EventRecord::SponsorPoolDestinationUpdated {
+            event: *solana_data_account.key,
+            destination: destination as u8,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn claim_sponsor_share(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let participant_account = next_account_info(accounts_iter)?;
+        let participant_wallet = next_account_info(accounts_iter)?;
+
+        if !participant_wallet.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        let mut participant =
+            Self::load_event_participant(participant_account, program_id, solana_data_account.key)?;
+        if participant.participant != *participant_wallet.key || !participant.is_joined() {
+            msg!("[RentShare] Participant account is not a paid seat of this wallet");
+            return Err(RentShareError::ParticipantMismatch.into());
+        }
+
+        let sponsor_share = data_of_solana_ac.sponsor_share;
+        if !data_of_solana_ac.is_complete()
+            || participant.sponsor_share_claimed
+            || sponsor_share == 0
+        {
+            msg!("[RentShare] No sponsor pool share to claim");
+            return Err(RentShareError::NothingToClaim.into());
+        }
+
+        Self::transfer_from_escrow(solana_data_account, participant_wallet, sponsor_share)?;
+        data_of_solana_ac.sponsor_pool = data_of_solana_ac
+            .sponsor_pool
+            .checked_sub(sponsor_share)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        participant.sponsor_share_claimed = true;
+        participant.serialize(&mut &mut participant_account.data.borrow_mut()[..])?;
+
+        EventRecord::SponsorShareClaimed {
+            event: *solana_data_account.key,
+            participant: *participant_wallet.key,
+            amount: sponsor_share,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn withdraw_sponsorship(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let sponsorship_account = next_account_info(accounts_iter)?;
+        let sponsor_wallet = next_account_info(accounts_iter)?;
+
+        if !sponsor_wallet.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        if !data_of_solana_ac.is_terminated()
+            && !data_of_solana_ac.is_funding_failed(now)
+            && !data_of_solana_ac.is_results_deadline_missed(now)
+        {
+            msg!("[RentShare] Sponsorships are only refunded for cancelled or unfunded events and missed results");
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
+
+        let sponsorship = Self::load_sponsorship(sponsorship_account, program_id)?;
+        if !sponsorship.is_initialized()
+            || sponsorship.event != *solana_data_account.key
+            || sponsorship.sponsor != *sponsor_wallet.key
+        {
+            msg!("[RentShare] Sponsorship account belongs to another event or sponsor");
+            return Err(RentShareError::SponsorshipMismatch.into());
+        }
+
+        Self::transfer_from_escrow(solana_data_account, sponsor_wallet, sponsorship.amount)?;
+        data_of_solana_ac.sponsor_pool = data_of_solana_ac
+            .sponsor_pool
+            .checked_sub(sponsorship.amount)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+        Self::close_account(sponsorship_account, sponsor_wallet)?;
+
+        EventRecord::SponsorshipRefunded {
+            event: *solana_data_account.key,
+            sponsor: *sponsor_wallet.key,
+            amount: sponsorship.amount,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn submit_results(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let creator_account = next_account_info(accounts_iter)?;
+        let payee_account = next_account_info(accounts_iter)?;
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_creator(
+            &data_of_solana_ac,
+            event_id,
+            &event_creator,
+            creator_account,
+        )?;
+
+        if !data_of_solana_ac.is_complete()
+            || data_of_solana_ac.sponsor_pool_destination != SponsorPoolDestination::Ranked as u8
+        {
+            msg!("[RentShare] Results are submitted for paid out competitions only");
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
+
+        if data_of_solana_ac.results_submitted {
+            msg!(
+                "[RentShare] Results of event {} were already submitted",
+                event_id
+            );
+            return Err(RentShareError::ResultsAlreadySubmitted.into());
+        }
+
+        if Clock::get()?.unix_timestamp > data_of_solana_ac.results_deadline {
+            msg!(
+                "[RentShare] Results of event {} were due by {}",
+                event_id,
+                data_of_solana_ac.results_deadline
+            );
+            return Err(RentShareError::ResultsDeadlinePassed.into());
+        }
+
+        // Remaining payee accounts come first, so take them before the participant accounts
+        let remaining_payees: Vec<&AccountInfo> = accounts_iter
+            .take((data_of_solana_ac.payee_count as usize).saturating_sub(1))
+            .collect();
+        let participant_accounts = accounts_iter.as_slice();
+        if participant_accounts.len() > data_of_solana_ac.prize_rank_count as usize {
+            msg!(
+                "[RentShare] {} participants ranked for {} prizes",
+                participant_accounts.len(),
+                data_of_solana_ac.prize_rank_count
+            );
+            return Err(RentShareError::InvalidPrizeTable.into());
+        }
+
+        let mut awarded = 0;
+        for (index, participant_account) in participant_accounts.iter().enumerate() {
+            let mut participant = Self::load_event_participant(
+                participant_account,
+                program_id,
+                solana_data_account.key,
+            )?;
+            if !participant.is_joined() || participant.rank != 0 {
+                msg!(
+                    "[RentShare] Participant account {} can't be ranked",
+                    participant_account.key
+                );
+                return Err(RentShareError::ParticipantMismatch.into());
+            }
+
+            let rank = index as u8 + 1;
+            participant.rank = rank;
+            participant.serialize(&mut &mut participant_account.data.borrow_mut()[..])?;
+            awarded += data_of_solana_ac.prize_amount(rank);
+
+            EventRecord::ParticipantRanked {
+                event: *solana_data_account.key,
+                participant: participant.participant,
+                participant_account: *participant_account.key,
+                rank,
+            }
+            .emit()?;
+        }
+
+        // Unawarded prizes and the rounding dust don't stay locked in the escrow
+        let unawarded = data_of_solana_ac.prize_pool - awarded;
+        let split = Self::pay_payees(
+            &mut remaining_payees.into_iter(),
+            solana_data_account,
+            &data_of_solana_ac,
+            payee_account,
+            unawarded,
+        )?;
+
+        data_of_solana_ac.sponsor_pool = data_of_solana_ac
+            .sponsor_pool
+            .checked_sub(unawarded)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        data_of_solana_ac.results_submitted = true;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        if unawarded > 0 {
+            for (payee, amount) in split {
+                EventRecord::PaidOut {
+                    event: *solana_data_account.key,
+                    payee,
+                    amount,
+                }
+                .emit()?;
+            }
+        }
+        EventRecord::ResultsSubmitted {
+            event: *solana_data_account.key,
+            winners: participant_accounts.len() as u8,
+            prize_pool: data_of_solana_ac.prize_pool,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn claim_prize(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let participant_account = next_account_info(accounts_iter)?;
+        let participant_wallet = next_account_info(accounts_iter)?;
+
+        if !participant_wallet.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        let mut participant =
+            Self::load_event_participant(participant_account, program_id, solana_data_account.key)?;
+        if participant.participant != *participant_wallet.key {
+            msg!("[RentShare] Participant account belongs to another wallet");
+            return Err(RentShareError::ParticipantMismatch.into());
+        }
+
+        let prize = data_of_solana_ac.prize_amount(participant.rank);
+        if !data_of_solana_ac.results_submitted || participant.prize_claimed || prize == 0 {
+            msg!("[RentShare] No prize to claim");
+            return Err(RentShareError::NothingToClaim.into());
+        }
+
+        Self::transfer_from_escrow(solana_data_account, participant_wallet, prize)?;
+        data_of_solana_ac.sponsor_pool = data_of_solana_ac
+            .sponsor_pool
+            .checked_sub(prize)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        participant.prize_claimed = true;
+        participant.serialize(&mut &mut participant_account.data.borrow_mut()[..])?;
+
+        EventRecord::PrizeClaimed {
+            event: *solana_data_account.key,
+            participant: *participant_wallet.key,
+            rank: participant.rank,
+            amount: prize,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn commit_lottery_draw(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        Self::check_lottery_drawable(&data_of_solana_ac)?;
+
+        let clock = Clock::get()?;
+        if !data_of_solana_ac.is_registration_closed(clock.unix_timestamp) {
+            msg!(
+                "[RentShare] Lottery takes entries until {}",
+                data_of_solana_ac.registration_deadline
+            );
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
+
+        // A committed draw can only be replaced once its hash can't be read anymore, whatever
+        // that hash turned out to be
+        let draw_slot = data_of_solana_ac.lottery_draw_slot;
+        if draw_slot > 0 {
+            let slot_hashes_account = next_account_info(accounts_iter)?;
+            let draw_hash = Self::lottery_draw_hash(slot_hashes_account, draw_slot)?;
+            if draw_hash != lottery::DrawHash::Expired {
+                msg!(
+                    "[RentShare] Lottery draw is already committed to slot {}",
+                    draw_slot
+                );
+                return Err(RentShareError::LotteryPending.into());
+            }
+        }
+
+        data_of_solana_ac.lottery_draw_slot = clock.slot + lottery::DRAW_DELAY_SLOTS;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        EventRecord::LotteryDrawCommitted {
+            event: *solana_data_account.key,
+            draw_slot: data_of_solana_ac.lottery_draw_slot,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn draw_lottery(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let slot_hashes_account = next_account_info(accounts_iter)?;
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        Self::check_lottery_drawable(&data_of_solana_ac)?;
+
+        let draw_slot = data_of_solana_ac.lottery_draw_slot;
+        if draw_slot == 0 || Clock::get()?.slot <= draw_slot {
+            msg!("[RentShare] Lottery draw slot isn't committed or reached yet");
+            return Err(RentShareError::LotteryPending.into());
+        }
+
+        let slot_hash = match Self::lottery_draw_hash(slot_hashes_account, draw_slot)? {
+            lottery::DrawHash::Available(slot_hash) => slot_hash,
+            lottery::DrawHash::Pending => {
+                msg!(
+                    "[RentShare] No slot was produced since draw slot {}",
+                    draw_slot
+                );
+                return Err(RentShareError::LotteryPending.into());
+            }
+            lottery::DrawHash::Expired => {
+                msg!(
+                    "[RentShare] Hash of draw slot {} is no longer available",
+                    draw_slot
+                );
+                return Err(RentShareError::LotteryDrawExpired.into());
+            }
+        };
+        let seed = lottery::seed(&slot_hash, solana_data_account.key);
+
+        data_of_solana_ac.lottery_seed = seed;
+        data_of_solana_ac.lottery_drawn = true;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        EventRecord::LotteryDrawn {
+            event: *solana_data_account.key,
+            entries: data_of_solana_ac.lottery_entries,
+            winners: data_of_solana_ac
+                .lottery_entries
+                .min(data_of_solana_ac.lottery_seats()),
+            seed,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn resolve_lottery_entry(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let participant_account = next_account_info(accounts_iter)?;
+        let participant_wallet = next_account_info(accounts_iter)?;
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        let mut participant =
+            Self::load_event_participant(participant_account, program_id, solana_data_account.key)?;
+        if participant.participant != *participant_wallet.key || !participant.is_entered() {
+            msg!("[RentShare] Participant account is not a lottery entry of this wallet");
+            return Err(RentShareError::ParticipantMismatch.into());
+        }
+
+        if !data_of_solana_ac.is_open() {
+            msg!("[RentShare] Entries of cancelled lotteries are refunded with WithdrawFromEvent");
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
+
+        if !data_of_solana_ac.lottery_drawn {
+            msg!("[RentShare] Lottery isn't drawn yet");
+            return Err(RentShareError::LotteryPending.into());
+        }
+
+        let won = data_of_solana_ac.is_lottery_winner(participant.lottery_entry);
+        if won {
+            let ticket_tier = data_of_solana_ac
+                .tier_mut(participant.tier)
+                .ok_or(RentShareError::InvalidTicketTier)?;
+            ticket_tier.sold += 1;
+            data_of_solana_ac.participant_count += 1;
+            data_of_solana_ac.lottery_unresolved -= 1;
+            data_of_solana_ac.lottery_pool = data_of_solana_ac
+                .lottery_pool
+                .checked_sub(participant.deposit)
+                .ok_or(ProgramError::InvalidAccountData)?;
+            data_of_solana_ac.total_deposits = data_of_solana_ac
+                .total_deposits
+                .checked_add(participant.deposit)
+                .ok_or(ProgramError::InvalidArgument)?;
+
+            participant.status = ParticipantStatus::Joined as u8;
+            participant.serialize(&mut &mut participant_account.data.borrow_mut()[..])?;
+        } else {
+            Self::refund_lottery_entry(
+                solana_data_account,
+                &mut data_of_solana_ac,
+                participant_account,
+                &participant,
+                participant_wallet,
+            )?;
+        }
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        EventRecord::LotteryEntryResolved {
+            event: *solana_data_account.key,
+            participant: *participant_wallet.key,
+            participant_account: *participant_account.key,
+            won,
+        }
+        .emit()?;
+        if won {
+            EventRecord::ParticipantJoined {
+                event: *solana_data_account.key,
+                participant: *participant_wallet.key,
+                participant_account: *participant_account.key,
+                tier: participant.tier,
+                deposit: participant.deposit,
+            }
+            .emit()?;
+        }
+
+        Ok(())
+    }
+
+    /// Hash seeding a lottery committed to `draw_slot`, read from the SlotHashes sysvar account
+    fn lottery_draw_hash(
+        slot_hashes_account: &AccountInfo,
+        draw_slot: u64,
+    ) -> Result<lottery::DrawHash, ProgramError> {
+        if *slot_hashes_account.key != sysvar::slot_hashes::id() {
+            msg!("[RentShare] Expected the SlotHashes sysvar account");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        Ok(lottery::draw_hash(
+            &slot_hashes_account.data.borrow(),
+            draw_slot,
+        ))
+    }
+
+    /// Make sure the event runs a lottery that is still open and not drawn yet
+    fn check_lottery_drawable(data_of_solana_ac: &InitEvent) -> ProgramResult {
+        if !data_of_solana_ac.is_lottery() || !data_of_solana_ac.is_open() {
+            msg!("[RentShare] Only open lottery events can be drawn");
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
+
+        if data_of_solana_ac.lottery_drawn {
+            msg!("[RentShare] Lottery was already drawn");
+            return Err(RentShareError::LotteryAlreadyDrawn.into());
+        }
+
+        Ok(())
+    }
+
+    fn open_check_in(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+        code_commitment: [u8; 32],
+        checkin_window: i64,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let creator_account = next_account_info(accounts_iter)?;
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_creator(
+            &data_of_solana_ac,
+            event_id,
+            &event_creator,
+            creator_account,
+        )?;
+
+        if !data_of_solana_ac.is_started() {
+            msg!("[RentShare] Check-in only opens once the event started");
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
+
+        if data_of_solana_ac.has_checkin() {
+            msg!("[RentShare] Check-in was already opened");
+            return Err(RentShareError::InvalidEventStatus.into());
+        }
+
+        if code_commitment == [0; 32] || checkin_window <= 0 {
+            msg!("[RentShare] Check-in needs a code commitment and a positive window");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        data_of_solana_ac.checkin_commitment = code_commitment;
+        data_of_solana_ac.checkin_deadline = Clock::get()?
+            .unix_timestamp
+            .checked_add(checkin_window)
+            .ok_or(ProgramError::InvalidArgument)?;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        EventRecord::CheckInOpened {
+            event: *solana_data_account.key,
+            checkin_deadline: data_of_solana_ac.checkin_deadline,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn check_in(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+        checkin_commitment: [u8; 32],
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let participant_account = next_account_info(accounts_iter)?;
+        let participant_wallet = next_account_info(accounts_iter)?;
+
+        if !participant_wallet.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        if !data_of_solana_ac.is_checkin_open(Clock::get()?.unix_timestamp) {
+            msg!("[RentShare] Event is not taking check-ins");
+            return Err(RentShareError::CheckInClosed.into());
+        }
+
+        let mut participant = Self::load_checkin_participant(
+            participant_account,
+            participant_wallet,
+            program_id,
+            solana_data_account.key,
+        )?;
+        if participant.checkin_commitment != [0; 32] {
+            msg!("[RentShare] Participant already checked in");
+            return Err(RentShareError::AlreadyCheckedIn.into());
+        }
+
+        if checkin_commitment == [0; 32] {
+            msg!("[RentShare] Check-in needs a commitment to the code");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        participant.checkin_commitment = checkin_commitment;
+        participant.serialize(&mut &mut participant_account.data.borrow_mut()[..])?;
+
+        EventRecord::CheckInCommitted {
+            event: *solana_data_account.key,
+            participant: *participant_wallet.key,
+            participant_account: *participant_account.key,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    fn reveal_check_in(
+        accounts: &[AccountInfo],
+        program_id: &Pubkey,
+        event_id: u64,
+        event_creator: Pubkey,
+        code: Vec<u8>,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+
+        let solana_data_account = next_account_info(accounts_iter)?;
+        let participant_account = next_account_info(accounts_iter)?;
+        let participant_wallet = next_account_info(accounts_iter)?;
+
+        if !participant_wallet.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut data_of_solana_ac = Self::load_event(solana_data_account, program_id)?;
+        Self::check_event_args(&data_of_solana_ac, event_id, &event_creator)?;
+
+        // The code goes on chain here, so it is only revealed once nobody can commit to it anymore
+        let now = Clock::get()?.unix_timestamp;
+        if !data_of_solana_ac.has_checkin() || now <= data_of_solana_ac.checkin_deadline {
+            msg!("[RentShare] Check-in codes are revealed once check-in closed");
+            return Err(RentShareError::CheckInOpen.into());
+        }
+
+        let mut participant = Self::load_checkin_participant(
+            participant_account,
+            participant_wallet,
+            program_id,
+            solana_data_account.key,
+        )?;
+        if participant.attended {
+            msg!("[RentShare] Participant already checked in");
+            return Err(RentShareError::AlreadyCheckedIn.into());
+        }
+
+        if !checkin::verify(
+            &data_of_solana_ac.checkin_commitment,
+            solana_data_account.key,
+            &code,
+        ) || !checkin::verify_participant(
+            &participant.checkin_commitment,
+            &code,
+            participant_wallet.key,
+        ) {
+            msg!("[RentShare] Check-in code doesn't match the commitments");
+            return Err(RentShareError::InvalidCheckInCode.into());
+        }
+
+        participant.attended = true;
+        participant.serialize(&mut &mut participant_account.data.borrow_mut()[..])?;
+
+        data_of_solana_ac.attendance += 1;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        EventRecord::CheckedIn {
+            event: *solana_data_account.key,
+            participant: *participant_wallet.key,
+            participant_account: *participant_account.key,
+            attendance: data_of_solana_ac.attendance,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    /// Load the paid seat of a wallet checking in at the event
+    fn load_checkin_participant(
+        participant_account: &AccountInfo,
+        participant_wallet: &AccountInfo,
+        program_id: &Pubkey,
+        event: &Pubkey,
+    ) -> Result<Participant, ProgramError> {
+        let participant = Self::load_event_participant(participant_account, program_id, event)?;
+        if participant.participant != *participant_wallet.key || !participant.is_joined() {
+            msg!("[RentShare] Participant account is not a paid seat of this wallet");
+            return Err(RentShareError::ParticipantMismatch.into());
+        }
+
+        Ok(participant)
+    }
+
+    /// Refund the deposit of a lottery entry out of the lottery pool and close its participant
+    /// account, returning everything to the participant wallet
+    fn refund_lottery_entry<'a>(
+        solana_data_account: &AccountInfo<'a>,
+        data_of_solana_ac: &mut InitEvent,
+        participant_account: &AccountInfo<'a>,
+        participant: &Participant,
+        participant_wallet: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        Self::transfer_from_escrow(solana_data_account, participant_wallet, participant.deposit)?;
+        data_of_solana_ac.lottery_unresolved -= 1;
+        data_of_solana_ac.lottery_pool = data_of_solana_ac
+            .lottery_pool
+            .checked_sub(participant.deposit)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        Self::close_account(participant_account, participant_wallet)?;
+
+        if participant.deposit > 0 {
+            EventRecord::Refunded {
+                event: *solana_data_account.key,
+                recipient: *participant_wallet.key,
+                amount: participant.deposit,
+            }
+            .emit()?;
+        }
+
+        Ok(())
+    }
+
+    /// Split the creator bond evenly among the seats of an event that didn't take place,
+    /// returning to the creator what doesn't divide, or the whole bond when nobody joined
+    fn slash_bond(
+        solana_data_account: &AccountInfo,
+        data_of_solana_ac: &mut InitEvent,
+        creator_account: &AccountInfo,
+    ) -> ProgramResult {
+        let participant_count = data_of_solana_ac.participant_count;
+        data_of_solana_ac.bond_share = data_of_solana_ac
+            .bond
+            .checked_div(participant_count)
+            .unwrap_or(0);
+        let returned = data_of_solana_ac.bond - data_of_solana_ac.bond_share * participant_count;
+        if returned > 0 {
+            Self::transfer_from_escrow(solana_data_account, creator_account, returned)?;
+            data_of_solana_ac.bond -= returned;
+        }
+
+        if data_of_solana_ac.bond_share > 0 {
+            EventRecord::BondSlashed {
+                event: *solana_data_account.key,
+                participant_count,
+                bond_share: data_of_solana_ac.bond_share,
+            }
+            .emit()?;
+        }
+        if returned > 0 {
+            EventRecord::BondReturned {
+                event: *solana_data_account.key,
+                event_creator: data_of_solana_ac.event_creator,
+                amount: returned,
+            }
+            .emit()?;
+        }
+
+        Ok(())
+    }
+
+    /// Pay the deposits not reserved for dispute refunds to the payee, return the creator bond
+    /// and complete the event
+    fn settle<'a, 'b: 'a, I: Iterator<Item = &'a AccountInfo<'b>>>(
+        accounts_iter: &mut I,
+        solana_data_account: &AccountInfo<'b>,
+        data_of_solana_ac: &mut InitEvent,
+        payee_account: &AccountInfo<'b>,
+        creator_account: &AccountInfo<'b>,
+    ) -> ProgramResult {
+        // The sponsor pool goes to the payees too, except the part split among the attendees
+        // or kept for prizes
+        let sponsor_pool = data_of_solana_ac.sponsor_pool;
+        let participant_count = data_of_solana_ac.participant_count;
+        let destination = data_of_solana_ac.sponsor_pool_destination;
+        let attendee_pool = if destination == SponsorPoolDestination::Attendees as u8 {
+            data_of_solana_ac.sponsor_share =
+                sponsor_pool.checked_div(participant_count).unwrap_or(0);
+            data_of_solana_ac.sponsor_share * participant_count
+        } else if destination == SponsorPoolDestination::Ranked as u8 {
+            data_of_solana_ac.prize_pool = sponsor_pool;
+            data_of_solana_ac.results_deadline = Clock::get()?
+                .unix_timestamp
+                .checked_add(data_of_solana_ac.results_window)
+                .ok_or(ProgramError::InvalidAccountData)?;
+            sponsor_pool
+        } else {
+            0
+        };
+        data_of_solana_ac.sponsor_pool = attendee_pool;
+
+        let payout = data_of_solana_ac.total_deposits - data_of_solana_ac.dispute_refund_reserve;
+        let split = Self::pay_payees(
+            accounts_iter,
+            solana_data_account,
+            data_of_solana_ac,
+            payee_account,
+            payout + sponsor_pool - attendee_pool,
+        )?;
+
+        let bond = data_of_solana_ac.bond;
+        if bond > 0 {
+            Self::transfer_from_escrow(solana_data_account, creator_account, bond)?;
+        }
+
+        data_of_solana_ac.total_deposits -= payout;
+        data_of_solana_ac.bond = 0;
+        data_of_solana_ac.status = EventStatus::Completed as u8;
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        for (payee, amount) in split {
+            EventRecord::PaidOut {
+                event: *solana_data_account.key,
+                payee,
+                amount,
+            }
+            .emit()?;
+        }
+        if sponsor_pool > 0 {
+            EventRecord::SponsorPoolDistributed {
+                event: *solana_data_account.key,
+                destination: data_of_solana_ac.sponsor_pool_destination,
+                amount: sponsor_pool,
+                sponsor_share: data_of_solana_ac.sponsor_share,
+            }
+            .emit()?;
+        }
+        if bond > 0 {
+            EventRecord::BondReturned {
+                event: *solana_data_account.key,
+                event_creator: data_of_solana_ac.event_creator,
+                amount: bond,
+            }
+            .emit()?;
+        }
+
+        Ok(())
+    }
+
+    /// Split `amount` of the escrow across the event payees, taking the accounts of the payees
+    /// after `payee_pubkey` from `accounts_iter`
+    fn pay_payees<'a, 'b: 'a, I: Iterator<Item = &'a AccountInfo<'b>>>(
+        accounts_iter: &mut I,
+        solana_data_account: &AccountInfo<'b>,
+        data_of_solana_ac: &InitEvent,
+        payee_account: &AccountInfo<'b>,
+        amount: u64,
+    ) -> Result<Vec<(Pubkey, u64)>, ProgramError> {
+        let split = data_of_solana_ac.split_payout(amount);
+        for (index, (payee, share)) in split.iter().enumerate() {
+            let account = if index == 0 {
+                payee_account
+            } else {
+                next_account_info(accounts_iter)?
+            };
+
+            // Make sure we pay the same accounts used during the event initialization
+            if *account.key != *payee {
+                msg!("[RentShare] Payee must match payee key used during event initialization");
+                return Err(ProgramError::InvalidAccountData);
+            }
+            Self::transfer_from_escrow(solana_data_account, account, *share)?;
+        }
+        Ok(split)
+    }
+
+    /// Refund a participant's deposit and the rent of their participant account to `refund_account`,
+    /// and hand their seat to the head of the tier waitlist, or free the seat when nobody is waiting
+    fn release_seat<'a, 'b: 'a, I: Iterator<Item = &'a AccountInfo<'b>>>(
+        accounts_iter: &mut I,
+        solana_data_account: &AccountInfo<'b>,
+        data_of_solana_ac: &mut InitEvent,
+        participant_account: &AccountInfo<'b>,
+        participant: Participant,
+        refund_account: &AccountInfo<'b>,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        Self::transfer_from_escrow(solana_data_account, refund_account, participant.deposit)?;
+        data_of_solana_ac.total_deposits = data_of_solana_ac
+            .total_deposits
+            .checked_sub(participant.deposit)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        if participant.deposit > 0 {
+            EventRecord::Refunded {
+                event: *solana_data_account.key,
+                recipient: *refund_account.key,
+                amount: participant.deposit,
+            }
+            .emit()?;
+        }
+
+        let ticket_tier = *data_of_solana_ac
+            .tier(participant.tier)
+            .ok_or(RentShareError::InvalidTicketTier)?;
+
+        // Seats only move to the waitlist while registration is open
+        let registration_open = data_of_solana_ac.is_open()
+            && !data_of_solana_ac.is_registration_closed(Clock::get()?.unix_timestamp);
+        let promoted = if registration_open && ticket_tier.has_waitlist() {
+            let waitlist_account = next_account_info(accounts_iter)?;
+            if *waitlist_account.key != ticket_tier.waitlist {
+                msg!(
+                    "[RentShare] Waitlist account is not the waitlist of tier {}",
+                    participant.tier
+                );
+                return Err(RentShareError::WaitlistMismatch.into());
+            }
+
+            let mut waitlist = Self::load_waitlist(waitlist_account, program_id)?;
+            let head = waitlist.pop_front();
+            if let Some(entry) = head {
+                Self::transfer_from_escrow(waitlist_account, solana_data_account, entry.deposit)?;
+                waitlist.serialize(&mut &mut waitlist_account.data.borrow_mut()[..])?;
+            }
+            head
+        } else {
+            None
+        };
+
+        match promoted {
+            Some(entry) => {
+                data_of_solana_ac.total_deposits = data_of_solana_ac
+                    .total_deposits
+                    .checked_add(entry.deposit)
+                    .ok_or(ProgramError::InvalidArgument)?;
+
+                // The promoted wallet takes the seat with the account it queued with
+                let promoted_account = next_account_info(accounts_iter)?;
+                if *promoted_account.key != entry.participant_account {
+                    msg!(
+                        "[RentShare] Participant account is not the one held by the waitlist head"
+                    );
+                    return Err(RentShareError::ParticipantMismatch.into());
+                }
+
+                let mut promoted_participant =
+                    Self::load_participant(promoted_account, program_id)?;
+                if !promoted_participant.is_waiting() {
+                    msg!("[RentShare] Participant account of the waitlist head is not waiting");
+                    return Err(ProgramError::InvalidAccountData);
+                }
+                promoted_participant.status = ParticipantStatus::Joined as u8;
+                promoted_participant.deposit = entry.deposit;
+                promoted_participant.serialize(&mut &mut promoted_account.data.borrow_mut()[..])?;
+
+                EventRecord::SeatPromoted {
+                    event: *solana_data_account.key,
+                    participant_account: *promoted_account.key,
+                    participant: entry.wallet,
+                    deposit: entry.deposit,
+                }
+                .emit()?;
+            }
+            None => {
+                if let Some(ticket_tier) = data_of_solana_ac.tier_mut(participant.tier) {
+                    ticket_tier.sold -= 1;
+                }
+                data_of_solana_ac.participant_count -= 1;
+            }
+        }
+        Self::close_account(participant_account, refund_account)?;
+
+        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+
+        Ok(())
+    }
+
+    /// Account refunds of the seat go to: the group payer passed next in `accounts_iter` when
+    /// the seat was paid by one, otherwise the participant wallet
+    fn next_refund_account<'a, 'b: 'a, I: Iterator<Item = &'a AccountInfo<'b>>>(
+        accounts_iter: &mut I,
+        participant: &Participant,
+        participant_wallet: &'a AccountInfo<'b>,
+    ) -> Result<&'a AccountInfo<'b>, ProgramError> {
+        if !participant.has_group_payer() {
+            return Ok(participant_wallet);
+        }
+
+        let payer_account = next_account_info(accounts_iter)?;
+        if *payer_account.key != participant.payer {
+            msg!("[RentShare] Refund must go to the wallet that paid for the seat");
+            return Err(RentShareError::ParticipantMismatch.into());
+        }
+        Ok(payer_account)
+    }
+
+    /// Mint a ticket to the participant's token account passed next in `accounts_iter`
+    fn issue_ticket<'a, 'b: 'a, I: Iterator<Item = &'a AccountInfo<'b>>>(
+        accounts_iter: &mut I,
+        event: &Pubkey,
+        data_of_solana_ac: &InitEvent,
+        participant: &mut Participant,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        let ticket_accounts = TicketAccounts::next(accounts_iter)?;
+        let bump_seed = ticket_accounts.check(
+            event,
+            &data_of_solana_ac.ticket_mint,
+            &participant.participant,
+            program_id,
+        )?;
+        ticket_accounts.mint_to(event, bump_seed)?;
+
+        participant.ticket_account = *ticket_accounts.token_account.key;
+
+        EventRecord::TicketIssued {
+            event: *event,
+            participant: participant.participant,
+            ticket_account: participant.ticket_account,
+        }
+        .emit()?;
+
+        Ok(())
+    }
+
+    /// Make sure the ticket accounts hold the ticket recorded on the participant account
+    fn check_ticket(
+        ticket_accounts: &TicketAccounts,
+        event: &Pubkey,
+        data_of_solana_ac: &InitEvent,
+        participant: &Participant,
+        program_id: &Pubkey,
+    ) -> Result<u8, ProgramError> {
+        if *ticket_accounts.token_account.key != participant.ticket_account {
+            msg!("[RentShare] Token account does not hold the participant ticket");
+            return Err(RentShareError::InvalidTicketAccount.into());
+        }
+
+        ticket_accounts.check(
+            event,
+            &data_of_solana_ac.ticket_mint,
+            &participant.participant,
+            program_id,
+        )
+    }
+
+    /// Make sure the ticket accounts hold a ticket of the event in a token account of `holder`,
+    /// returning the bump seed of the ticket authority. Tickets are fungible, so any token account
+    /// of the mint will do, but a wallet that moved its ticket away has to get one back first:
+    /// skipping the burn or freeze would leave a valid ticket behind the released seat.
+    fn check_held_ticket(
+        ticket_accounts: &TicketAccounts,
+        event: &Pubkey,
+        data_of_solana_ac: &InitEvent,
+        holder: &Pubkey,
+        program_id: &Pubkey,
+    ) -> Result<u8, ProgramError> {
+        let bump_seed =
+            ticket_accounts.check(event, &data_of_solana_ac.ticket_mint, holder, program_id)?;
+        if ticket_accounts.balance()? == 0 {
+            msg!("[RentShare] Participant wallet no longer holds a ticket of the event");
+            return Err(RentShareError::TicketNotHeld.into());
+        }
+
+        Ok(bump_seed)
+    }
+
+    /// Run the allowlist and permit checks the event requires before a wallet can take or queue for a seat
+    fn check_registration_gates<'a, 'b: 'a, I: Iterator<Item = &'a AccountInfo<'b>>>(
+        accounts_iter: &mut I,
+        solana_data_account: &AccountInfo<'b>,
+        data_of_solana_ac: &InitEvent,
+        participant_wallet: &AccountInfo<'b>,
+        system_program_account: &AccountInfo<'b>,
+        allowlist_proof: &[[u8; 32]],
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        if data_of_solana_ac.has_allowlist()
+            && !allowlist::verify(
+                &data_of_solana_ac.allowlist_root,
+                participant_wallet.key,
+                allowlist_proof,
+            )
+        {
+            msg!("[RentShare] Participant is not on the event allowlist");
+            return Err(RentShareError::NotOnAllowlist.into());
+        }
+
+        if data_of_solana_ac.requires_permit() {
+            let instructions_sysvar = next_account_info(accounts_iter)?;
+            let permit_nonce_account = next_account_info(accounts_iter)?;
+            Self::use_join_permit(
+                solana_data_account,
+                participant_wallet,
+                instructions_sysvar,
+                permit_nonce_account,
+                system_program_account,
+                program_id,
+                &data_of_solana_ac.permit_signer,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Verify the join permit signed for this participant and record its nonce so it can't be replayed
+    fn use_join_permit<'a>(
+        solana_data_account: &AccountInfo<'a>,
+        participant_wallet: &AccountInfo<'a>,
+        instructions_sysvar: &AccountInfo<'a>,
+        permit_nonce_account: &AccountInfo<'a>,
+        system_program_account: &AccountInfo<'a>,
+        program_id: &Pubkey,
+        permit_signer: &Pubkey,
+    ) -> ProgramResult {
+        let join_permit = permit::load_signed_permit(instructions_sysvar, permit_signer)?;
+
+        if join_permit.event != *solana_data_account.key
+            || join_permit.participant != *participant_wallet.key
+        {
+            msg!("[RentShare] Permit was issued for another event or participant");
+            return Err(RentShareError::InvalidPermit.into());
+        }
+
+        if Clock::get()?.unix_timestamp > join_permit.expiry {
+            msg!("[RentShare] Permit expired at {}", join_permit.expiry);
+            return Err(RentShareError::PermitExpired.into());
+        }
+
+        let nonce = join_permit.nonce.to_le_bytes();
+        let seeds = permit::nonce_seeds(solana_data_account.key, &nonce);
+        let (nonce_address, bump_seed) = Pubkey::find_program_address(&seeds, program_id);
+        if nonce_address != *permit_nonce_account.key {
+            msg!("[RentShare] Permit nonce account does not match the permit nonce");
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        // Lamports alone don't mark the nonce as used, anyone can send them to the address
+        if permit_nonce_account.owner == program_id {
+            msg!(
+                "[RentShare] Permit nonce {} already used",
+                join_permit.nonce
+            );
+            return Err(RentShareError::PermitAlreadyUsed.into());
+        }
+
+        // The nonce account holds no data, its ownership by this program marks the permit as used
+        Self::create_pda_account(
+            participant_wallet,
+            permit_nonce_account,
+            system_program_account,
+            0,
+            program_id,
+            &[seeds[0], seeds[1], seeds[2], &[bump_seed]],
+        )
+    }
+
+    /// Create a program account at a PDA. Lamports already sent to the address are kept and only
+    /// topped up to rent exemption by `payer_account`, so prefunding the address can't block the
+    /// creation as it would with `create_account`.
+    fn create_pda_account<'a>(
+        payer_account: &AccountInfo<'a>,
+        new_account: &AccountInfo<'a>,
+        system_program_account: &AccountInfo<'a>,
+        space: usize,
+        program_id: &Pubkey,
+        signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        let required_lamports = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(new_account.lamports());
+        if required_lamports > 0 {
+            invoke(
+                &system_instruction::transfer(
+                    payer_account.key,
+                    new_account.key,
+                    required_lamports,
+                ),
+                &[
+                    payer_account.clone(),
+                    new_account.clone(),
+                    system_program_account.clone(),
+                ],
+            )?;
+        }
+
+        let accounts = &[new_account.clone(), system_program_account.clone()];
+        invoke_signed(
+            &system_instruction::allocate(new_account.key, space as u64),
+            accounts,
+            &[signer_seeds],
+        )?;
+        invoke_signed(
+            &system_instruction::assign(new_account.key, program_id),
+            accounts,
+            &[signer_seeds],
+        )
+    }
+
+    /// Deserialize an initialized event account owned by this program
+    fn load_event(
+        solana_data_account: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<InitEvent, ProgramError> {
+        if solana_data_account.owner != program_id {
+            msg!("[RentShare] Event account is not owned by this program");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // Note: the structure of the data state must match the `space` the client used to create the account
+        let solana_data_account_data =
+            InitEvent::try_from_slice(&solana_data_account.data.borrow());
+
+        if solana_data_account_data.is_err() {
+            msg!(
+                "[RentShare] Event account data size incorrect: {}",
+                solana_data_account.try_data_len()?
+            );
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let data_of_solana_ac = solana_data_account_data.unwrap();
         if !data_of_solana_ac.is_initialized() {
-            msg!("[RentShare] Rent agreement account not initialized");
+            msg!("[RentShare] Event account not initialized");
             return Err(ProgramError::UninitializedAccount);
         }
 
-        // Make sure we pay the same account used during the agreement initialization
-        if data_of_solana_ac.payee_pubkey != *payee_account.key {
-            msg!("[RentShare] Payee must match payee key used during agreement initialization");
+        Ok(data_of_solana_ac)
+    }
+
+    /// Deserialize a participant account owned by this program, which may not be used yet
+    fn load_participant(
+        participant_account: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<Participant, ProgramError> {
+        if participant_account.owner != program_id {
+            msg!("[RentShare] Participant account is not owned by this program");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let participant_data = Participant::try_from_slice(&participant_account.data.borrow());
+        if participant_data.is_err() {
+            msg!(
+                "[RentShare] Participant account data size incorrect: {}",
+                participant_account.try_data_len()?
+            );
             return Err(ProgramError::InvalidAccountData);
         }
 
-        msg!(
-            "[RentShare] Transfer {} lamports from payer with balance: {}",
-            rent_amount,
-            payer_account.lamports()
-        );
+        Ok(participant_data.unwrap())
+    }
+
+    /// Deserialize a participant account holding a seat in the given event
+    fn load_event_participant(
+        participant_account: &AccountInfo,
+        program_id: &Pubkey,
+        event: &Pubkey,
+    ) -> Result<Participant, ProgramError> {
+        let participant = Self::load_participant(participant_account, program_id)?;
+        if !participant.is_initialized() {
+            msg!("[RentShare] Participant account not initialized");
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        if participant.is_waiting() {
+            msg!("[RentShare] Participant account is still queued on the waitlist");
+            return Err(RentShareError::AlreadyOnWaitlist.into());
+        }
 
-        if data_of_solana_ac.is_complete() {
-            msg!("[RentShare] Rent already paid in full");
-            return Err(RentShareError::RentAlreadyPaidInFull.into());
+        if participant.event != *event {
+            msg!("[RentShare] Participant account belongs to another event");
+            return Err(RentShareError::ParticipantMismatch.into());
         }
 
-        if data_of_solana_ac.is_terminated() {
-            msg!("[RentShare] Rent agreement already terminated");
-            return Err(RentShareError::RentAgreementTerminated.into());
+        Ok(participant)
+    }
+
+    /// Check `amount` pays for a seat in the given tier: its price in effect, or a deposit
+    /// within the bounds of pay-what-you-want events
+    fn check_deposit(data_of_solana_ac: &InitEvent, tier: u8, amount: u64) -> ProgramResult {
+        let price = match data_of_solana_ac.tier(tier) {
+            Some(ticket_tier) if data_of_solana_ac.has_price_schedule() => {
+                data_of_solana_ac.price_at(ticket_tier.price, Clock::get()?.unix_timestamp)
+            }
+            Some(ticket_tier) => ticket_tier.price,
+            None => {
+                msg!("[RentShare] Unknown ticket tier: {}", tier);
+                return Err(RentShareError::InvalidTicketTier.into());
+            }
+        };
+
+        if !data_of_solana_ac.accepts_deposit(price, amount) {
+            if data_of_solana_ac.has_flexible_pricing() {
+                msg!(
+                    "[RentShare] Deposit {} is outside the bounds {}..={}",
+                    amount,
+                    data_of_solana_ac.min_deposit,
+                    data_of_solana_ac.max_deposit
+                );
+            } else {
+                msg!(
+                    "[RentShare] Deposit does not match ticket price: {} vs {}",
+                    price,
+                    amount
+                );
+            }
+            return Err(RentShareError::DepositAmountMismatch.into());
+        }
+
+        Ok(())
+    }
+
+    /// Deserialize a sponsorship account owned by this program, which may not be used yet
+    fn load_sponsorship(
+        sponsorship_account: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<Sponsorship, ProgramError> {
+        if sponsorship_account.owner != program_id {
+            msg!("[RentShare] Sponsorship account is not owned by this program");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let sponsorship_data = Sponsorship::try_from_slice(&sponsorship_account.data.borrow());
+        if sponsorship_data.is_err() {
+            msg!(
+                "[RentShare] Sponsorship account data size incorrect: {}",
+                sponsorship_account.try_data_len()?
+            );
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(sponsorship_data.unwrap())
+    }
+
+    /// Deserialize a waitlist account owned by this program, which may not be used yet
+    fn load_waitlist(
+        waitlist_account: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<Waitlist, ProgramError> {
+        if waitlist_account.owner != program_id {
+            msg!("[RentShare] Waitlist account is not owned by this program");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        Waitlist::try_from_slice(&waitlist_account.data.borrow()).map_err(|_| {
+            msg!(
+                "[RentShare] Waitlist account data size incorrect: {}",
+                waitlist_account.data_len()
+            );
+            ProgramError::InvalidAccountData
+        })
+    }
+
+    /// Deserialize a series account owned by this program, which may not be used yet
+    fn load_series(
+        series_account: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> Result<EventSeries, ProgramError> {
+        if series_account.owner != program_id {
+            msg!("[RentShare] Series account is not owned by this program");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        EventSeries::try_from_slice(&series_account.data.borrow()).map_err(|_| {
+            msg!(
+                "[RentShare] Series account data size incorrect: {}",
+                series_account.data_len()
+            );
+            ProgramError::InvalidAccountData
+        })
+    }
+
+    /// Make sure the instruction targets the event stored in the account
+    fn check_event_args(
+        data_of_solana_ac: &InitEvent,
+        event_id: u64,
+        event_creator: &Pubkey,
+    ) -> ProgramResult {
+        if data_of_solana_ac.event_creator != *event_creator {
+            msg!("[RentShare] This is not the event creator.");
+            return Err(RentShareError::EventCreatorMismatch.into());
         }
 
-        if data_of_solana_ac.rent_amount != rent_amount {
+        if data_of_solana_ac.event_id != event_id {
             msg!(
-                "[RentShare] Rent amount does not match agreement amount: {} vs {}",
-                data_of_solana_ac.rent_amount,
-                rent_amount
+                "[RentShare] Event id does not match event account: {} vs {}",
+                data_of_solana_ac.event_id,
+                event_id
             );
-            return Err(RentShareError::RentPaymentAmountMismatch.into());
+            return Err(RentShareError::EventIdMismatch.into());
+        }
+
+        Ok(())
+    }
+
+    /// Make sure the event creator signed an instruction targeting their event
+    fn check_event_creator(
+        data_of_solana_ac: &InitEvent,
+        event_id: u64,
+        event_creator: &Pubkey,
+        creator_account: &AccountInfo,
+    ) -> ProgramResult {
+        if !creator_account.is_signer {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if data_of_solana_ac.event_creator != *creator_account.key {
+            msg!("[RentShare] This is not the event creator.");
+            return Err(RentShareError::EventCreatorMismatch.into());
         }
 
-        let instruction =
-            system_instruction::transfer(payer_account.key, payee_account.key, rent_amount);
+        Self::check_event_args(data_of_solana_ac, event_id, event_creator)
+    }
+
+    /// Transfer a deposit from a wallet into an escrow account owned by this program
+    fn escrow_deposit<'a>(
+        wallet: &AccountInfo<'a>,
+        escrow_account: &AccountInfo<'a>,
+        system_program_account: &AccountInfo<'a>,
+        amount: u64,
+    ) -> ProgramResult {
+        let instruction = system_instruction::transfer(wallet.key, escrow_account.key, amount);
 
-        // Invoke the system program to transfer funds
+        // Invoke the system program to escrow the deposit
         invoke(
             &instruction,
             &[
                 system_program_account.clone(),
-                payee_account.clone(),
-                payer_account.clone(),
+                wallet.clone(),
+                escrow_account.clone(),
             ],
-        )?;
+        )
+    }
 
-        msg!(
-            "[RentShare] Transfer completed. New payer balance: {}",
-            payer_account.lamports()
-        );
+    /// Close an account owned by this program, returning its lamports to `destination_account`
+    fn close_account(account: &AccountInfo, destination_account: &AccountInfo) -> ProgramResult {
+        Self::transfer_from_escrow(account, destination_account, account.lamports())?;
+        account.data.borrow_mut().fill(0);
 
-        // Decrement the number of payment
-        data_of_solana_ac.remaining_payments -= 1;
-        if data_of_solana_ac.remaining_payments == 0 {
-            data_of_solana_ac.status = EventStatus::Completed as u8;
-        }
-        data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+        Ok(())
+    }
+
+    /// Move escrowed lamports out of an account owned by this program
+    fn transfer_from_escrow(
+        escrow_account: &AccountInfo,
+        destination_account: &AccountInfo,
+        amount: u64,
+    ) -> ProgramResult {
+        let escrow_balance = escrow_account
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        let destination_balance = destination_account
+            .lamports()
+            .checked_add(amount)
+            .ok_or(ProgramError::InvalidArgument)?;
+
+        **escrow_account.try_borrow_mut_lamports()? = escrow_balance;
+        **destination_account.try_borrow_mut_lamports()? = destination_balance;
 
         Ok(())
     }
 
+    // fn pay_rent(accounts: &[AccountInfo], program_id: &Pubkey, rent_amount: u64) -> ProgramResult {
+    //     let accounts_iter = &mut accounts.iter();
+    //
+    //     let solana_data_account = next_account_info(accounts_iter)?;
+    //     if solana_data_account.owner != program_id {
+    //         msg!("[RentShare] Rent agreement account is not owned by this program");
+    //         return Err(ProgramError::IncorrectProgramId);
+    //     }
+    //
+    //     let payee_account: &AccountInfo = next_account_info(accounts_iter)?;
+    //     let system_program_account = next_account_info(accounts_iter)?;
+    //
+    //     if !payer_account.is_signer {
+    //         return Err(ProgramError::MissingRequiredSignature);
+    //     }
+    //
+    //     if payer_account.lamports() < rent_amount {
+    //         return Err(ProgramError::InsufficientFunds);
+    //     }
+    //
+    //     // Transfer to self - do nothing
+    //     if payer_account.key == payee_account.key {
+    //         return Ok(());
+    //     }
+    //
+    //     // Initialize the Rent Agreement Account with the initial data
+    //     // Note: the structure of the data state must match the `space` the client used to create the account
+    //     let solana_data_account_data =
+    //         InitEvent::try_from_slice(&solana_data_account.data.borrow());
+    //
+    //     if solana_data_account_data.is_err() {
+    //         msg!(
+    //             "[RentShare] Rent agreement account data size incorrect: {}",
+    //             solana_data_account.try_data_len()?
+    //         );
+    //         return Err(ProgramError::InvalidAccountData);
+    //     }
+    //
+    //     let mut data_of_solana_ac = solana_data_account_data.unwrap();
+    //     if !data_of_solana_ac.is_initialized() {
+    //         msg!("[RentShare] Rent agreement account not initialized");
+    //         return Err(ProgramError::UninitializedAccount);
+    //     }
+    //
+    //     // Make sure we pay the same account used during the agreement initialization
+    //     if data_of_solana_ac.payee_pubkey != *payee_account.key {
+    //         msg!("[RentShare] Payee must match payee key used during agreement initialization");
+    //         return Err(ProgramError::InvalidAccountData);
+    //     }
+    //
+    //     msg!(
+    //         "[RentShare] Transfer {} lamports from payer with balance: {}",
+    //         rent_amount,
+    //         payer_account.lamports()
+    //     );
+    //
+    //     if data_of_solana_ac.is_complete() {
+    //         msg!("[RentShare] Rent already paid in full");
+    //         return Err(RentShareError::RentAlreadyPaidInFull.into());
+    //     }
+    //
+    //     if data_of_solana_ac.is_terminated() {
+    //         msg!("[RentShare] Rent agreement already terminated");
+    //         return Err(RentShareError::RentAgreementTerminated.into());
+    //     }
+    //
+    //     if data_of_solana_ac.rent_amount != rent_amount {
+    //         msg!(
+    //             "[RentShare] Rent amount does not match agreement amount: {} vs {}",
+    //             data_of_solana_ac.rent_amount,
+    //             rent_amount
+    //         );
+    //         return Err(RentShareError::RentPaymentAmountMismatch.into());
+    //     }
+    //
+    //     let instruction =
+    //         system_instruction::transfer(payer_account.key, payee_account.key, rent_amount);
+    //
+    //     // Invoke the system program to transfer funds
+    //     invoke(
+    //         &instruction,
+    //         &[
+    //             system_program_account.clone(),
+    //             payee_account.clone(),
+    //             payer_account.clone(),
+    //         ],
+    //     )?;
+    //
+    //     msg!(
+    //         "[RentShare] Transfer completed. New payer balance: {}",
+    //         payer_account.lamports()
+    //     );
+    //
+    //     // Decrement the number of payment
+    //     data_of_solana_ac.remaining_payments -= 1;
+    //     if data_of_solana_ac.remaining_payments == 0 {
+    //         data_of_solana_ac.status = EventStatus::Completed as u8;
+    //     }
+    //     data_of_solana_ac.serialize(&mut &mut solana_data_account.data.borrow_mut()[..])?;
+    //
+    //     Ok(())
+    // }
+
     // fn terminate_early(accounts: &[AccountInfo], program_id: &Pubkey) -> ProgramResult {
     //     let accounts_iter = &mut accounts.iter();
 
@@ -0,0 +1,21 @@
+//! Occurrences of recurring event series.
+//!
+//! Each occurrence is an event account created by the program at the PDA of
+//! `["occurrence", series, index]`, with `index` the little-endian `u64` position
+//! of the occurrence in the series, starting at 0.
+
+use solana_program::pubkey::Pubkey;
+
+/// Seed prefix of the event accounts created for series occurrences
+pub const OCCURRENCE_SEED: &[u8] = b"occurrence";
+
+/// Seeds of the event account of the occurrence at `index` in `series`
+pub fn occurrence_seeds<'a>(series: &'a Pubkey, index: &'a [u8; 8]) -> [&'a [u8]; 3] {
+    [OCCURRENCE_SEED, series.as_ref(), index]
+}
+
+/// Address and bump seed of the event account of the occurrence at `index` in `series`
+pub fn find_occurrence_address(series: &Pubkey, index: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    let index = index.to_le_bytes();
+    Pubkey::find_program_address(&occurrence_seeds(series, &index), program_id)
+}
@@ -1,19 +1,100 @@
+use std::convert::TryFrom;
+
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     program_pack::{IsInitialized, Sealed},
     pubkey::Pubkey,
 };
+
+/// Maximum number of ticket tiers (early, standard, VIP, ...) an event can define
+pub const MAX_EVENT_TIERS: usize = 4;
+
+/// Maximum number of wallets queued on a single tier waitlist
+pub const MAX_WAITLIST_ENTRIES: usize = 16;
+
+/// Maximum number of steps in an event refund schedule
+pub const MAX_REFUND_STEPS: usize = 4;
+
+/// Basis points of a whole deposit
+pub const FULL_REFUND_BPS: u16 = 10_000;
+
+/// Maximum number of organizers, besides its creator, allowed to run occurrences of a series
+pub const MAX_SERIES_ORGANIZERS: usize = 4;
+
+/// Maximum number of payees the payout of an event is split across
+pub const MAX_EVENT_PAYEES: usize = 4;
+
+/// Maximum number of ranks awarded a prize in a competition event
+pub const MAX_PRIZE_RANKS: usize = 8;
+
+/// Maximum number of steps in an event price schedule
+pub const MAX_PRICE_STEPS: usize = 4;
+
 // #[derive(BorshSerialize, BorshDeserialize, Debug)]
 // pub struct InitlializeEvent{
 //     pub event_id:u64,
 //     pub event_struct:InitEvent
 // }
-#[derive(BorshSerialize, BorshDeserialize, Debug)]
-pub struct StartEvent{
-    pub event_id: u8,
-    pub particpate_amount: u64,
-    pub event_creator:pubkey
+// #[derive(BorshSerialize, BorshDeserialize, Debug)]
+// pub struct StartEvent{
+//     pub event_id: u8,
+//     pub particpate_amount: u64,
+//     pub event_creator:pubkey
+// }
+
+/// Price and capacity of a single ticket tier, along with the seats already sold
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, Clone, Copy)]
+pub struct EventTier {
+    pub price: u64,
+    pub capacity: u64,
+    pub sold: u64,
+    /// Waitlist account promoting into this tier; default pubkey when the tier has no waitlist
+    pub waitlist: Pubkey,
+}
+
+impl EventTier {
+    pub const LEN: usize = 8 + 8 + 8 + 32;
+
+    pub fn is_sold_out(&self) -> bool {
+        self.sold >= self.capacity
+    }
 
+    pub fn has_waitlist(&self) -> bool {
+        self.waitlist != Pubkey::default()
+    }
+}
+
+/// Share of the deposit refunded on withdrawal until `seconds_before_start` before the event starts
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, Clone, Copy)]
+pub struct RefundStep {
+    pub seconds_before_start: i64,
+    pub refund_bps: u16,
+}
+
+impl RefundStep {
+    pub const LEN: usize = 8 + 2;
+}
+
+/// Price of a seat from `starts_at` until the next step
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, Clone, Copy)]
+pub struct PriceStep {
+    pub starts_at: i64,
+    pub price: u64,
+}
+
+impl PriceStep {
+    pub const LEN: usize = 8 + 8;
+}
+
+/// Payee receiving `share_bps` basis points of the event payout
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, Clone, Copy)]
+pub struct PayeeShare {
+    pub payee: Pubkey,
+    pub share_bps: u16,
+}
+
+impl PayeeShare {
+    pub const LEN: usize = 32 + 2;
 }
 
 /// Rent Share Account state stored in the Agreement Account
@@ -23,17 +104,118 @@ pub struct InitEvent {
     pub payee_pubkey: Pubkey,
     // pub payer_pubkey: Pubkey,
     pub event_id: u64,
-    pub event_creator:Pubkey,
+    pub event_creator: Pubkey,
 
     pub fix_deposit_amount_per_person: u64,
     pub total_partcipator: u64,
     // pub duration_unit: u8,
     // pub remaining_payments: u64,
+    pub participant_count: u64,
+    pub total_deposits: u64,
+    pub tier_count: u8,
+    pub tiers: [EventTier; MAX_EVENT_TIERS],
+    /// Merkle root of the wallets allowed to participate; all zeros when registration is open to anyone
+    pub allowlist_root: [u8; 32],
+    /// Key that signs off-chain join permits; default pubkey when permits are not required
+    pub permit_signer: Pubkey,
+    /// Whether participants may hand their seat to another wallet, see `TransferPolicy`
+    pub transfer_policy: u8,
+    /// SPL token mint issuing one fungible ticket unit to each participant; default pubkey when tickets are disabled
+    pub ticket_mint: Pubkey,
+    /// Whether tickets can be frozen as non-transferable attendance badges after `EndEvent`
+    pub freeze_tickets: bool,
+    /// Series this event is an occurrence of; default pubkey for standalone events
+    pub series: Pubkey,
+    /// Unix timestamp the event starts at; 0 when not scheduled
+    pub start_time: i64,
+    pub refund_step_count: u8,
+    /// Refund steps ordered by decreasing `seconds_before_start`; deposits are refunded in full without steps
+    pub refund_schedule: [RefundStep; MAX_REFUND_STEPS],
+    /// Creator bond still escrowed in the event account, on top of `total_deposits`
+    pub bond: u64,
+    /// Bond compensation owed to each seat once the creator cancelled the event
+    pub bond_share: u64,
+    /// Key resolving disputes; default pubkey when the event has no dispute window
+    pub arbiter: Pubkey,
+    /// Seconds after `EndEvent` during which participants can dispute the payout; 0 pays out right away
+    pub dispute_window: i64,
+    /// Unix timestamp the dispute window of an ended event closes at
+    pub dispute_deadline: i64,
+    /// See `DisputeStatus`
+    pub dispute_status: u8,
+    pub disputant: Pubkey,
+    /// Basis points of their deposit the arbiter refunds to every participant
+    pub dispute_refund_bps: u16,
+    /// Deposits set aside for dispute refunds that weren't claimed yet
+    pub dispute_refund_reserve: u64,
+    /// Basis points of the seats that must confirm the event took place before the payout; 0 when not required
+    pub confirmation_quorum_bps: u16,
+    /// Seconds after `EndEvent` participants have to confirm the event
+    pub confirmation_window: i64,
+    /// Unix timestamp confirmations close at; without quorum by then deposits become refundable
+    pub confirmation_deadline: i64,
+    pub confirmations: u64,
+    pub payee_count: u8,
+    /// Payout split, the first payee being `payee_pubkey`; the whole payout goes to `payee_pubkey` without shares
+    pub payees: [PayeeShare; MAX_EVENT_PAYEES],
+    /// How the escrow is released to the payees while the event runs, see `Vesting`
+    pub vesting: u8,
+    pub vesting_duration: i64,
+    /// Unix timestamp of `StartEvent`, vesting starts at
+    pub vesting_start: i64,
+    /// Deposits escrowed when the event started, released over `vesting_duration`
+    pub vesting_total: u64,
+    /// Part of `vesting_total` already paid out with `ClaimVested`
+    pub vested_claimed: u64,
+    /// Sponsor contributions escrowed on top of `total_deposits`
+    pub sponsor_pool: u64,
+    /// Who receives the sponsor pool when the event is paid out, see `SponsorPoolDestination`
+    pub sponsor_pool_destination: u8,
+    /// Sponsor pool share each seat can claim once the pool went to the attendees
+    pub sponsor_share: u64,
+    pub prize_rank_count: u8,
+    /// Basis points of the sponsor pool awarded to each rank, starting with the winner
+    pub prize_table: [u16; MAX_PRIZE_RANKS],
+    /// Sponsor pool set aside for prizes when the event was paid out
+    pub prize_pool: u64,
+    pub results_submitted: bool,
+    /// Seconds after the payout within which the results of a competition are submitted
+    pub results_window: i64,
+    /// Unix timestamp after which unsubmitted results leave the prize pool to the sponsors
+    pub results_deadline: i64,
+    /// Deposits required by the registration deadline for the event to take place; 0 without a goal
+    pub funding_goal: u64,
+    /// Unix timestamp registration closes at; 0 when it stays open until `StartEvent`
+    pub registration_deadline: i64,
+    /// Bounds of the deposit each participant chooses, in place of the tier prices, when
+    /// `max_deposit` isn't 0
+    pub min_deposit: u64,
+    pub max_deposit: u64,
+    pub price_step_count: u8,
+    /// Price steps ordered by increasing `starts_at`, replacing the tier price once the first one starts
+    pub price_schedule: [PriceStep; MAX_PRICE_STEPS],
+    /// Whether seats are allocated by a lottery drawn after the registration deadline
+    pub lottery: bool,
+    pub lottery_entries: u64,
+    /// Lottery entries still waiting for `ResolveLotteryEntry`
+    pub lottery_unresolved: u64,
+    /// Deposits of unresolved lottery entries escrowed in the event account, on top of `total_deposits`
+    pub lottery_pool: u64,
+    pub lottery_drawn: bool,
+    /// Randomness derived by `DrawLottery` from the hash of `lottery_draw_slot`
+    pub lottery_seed: [u8; 32],
+    /// Slot whose hash seeds the lottery, set by `CommitLotteryDraw`; 0 until committed
+    pub lottery_draw_slot: u64,
+    /// Commitment to the check-in code, see `checkin::commitment`; all zeros until check-in opens
+    pub checkin_commitment: [u8; 32],
+    /// Unix timestamp check-in closes at
+    pub checkin_deadline: i64,
+    /// Participants who checked in with the code
+    pub attendance: u64,
 }
 
 impl Sealed for InitEvent {}
 
-
 impl IsInitialized for InitEvent {
     fn is_initialized(&self) -> bool {
         self.status != EventStatus::Uninitialized as u8
@@ -41,6 +223,72 @@ impl IsInitialized for InitEvent {
 }
 
 impl InitEvent {
+    /// Space the client must reserve when creating the event account
+    pub const LEN: usize = 1
+        + 32
+        + 8
+        + 32
+        + 8
+        + 8
+        + 8
+        + 8
+        + 1
+        + EventTier::LEN * MAX_EVENT_TIERS
+        + 32
+        + 32
+        + 1
+        + 32
+        + 1
+        + 32
+        + 8
+        + 1
+        + RefundStep::LEN * MAX_REFUND_STEPS
+        + 8
+        + 8
+        + 32
+        + 8
+        + 8
+        + 1
+        + 32
+        + 2
+        + 8
+        + 2
+        + 8
+        + 8
+        + 8
+        + 1
+        + PayeeShare::LEN * MAX_EVENT_PAYEES
+        + 1
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 1
+        + 8
+        + 1
+        + 2 * MAX_PRIZE_RANKS
+        + 8
+        + 1
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 1
+        + PriceStep::LEN * MAX_PRICE_STEPS
+        + 1
+        + 8
+        + 8
+        + 8
+        + 1
+        + 32
+        + 8
+        + 32
+        + 8
+        + 8;
+
     pub fn is_complete(&self) -> bool {
         self.status == EventStatus::Completed as u8
     }
@@ -48,6 +296,464 @@ impl InitEvent {
     pub fn is_terminated(&self) -> bool {
         self.status == EventStatus::Terminated as u8
     }
+
+    pub fn is_open(&self) -> bool {
+        self.status == EventStatus::Active as u8
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.status == EventStatus::Started as u8
+    }
+
+    /// Ended with the payout waiting for the dispute window to close
+    pub fn is_ended(&self) -> bool {
+        self.status == EventStatus::Ended as u8
+    }
+
+    pub fn has_dispute_window(&self) -> bool {
+        self.dispute_window > 0
+    }
+
+    pub fn is_disputed(&self) -> bool {
+        self.dispute_status == DisputeStatus::Open as u8
+    }
+
+    pub fn is_dispute_resolved(&self) -> bool {
+        self.dispute_status == DisputeStatus::Resolved as u8
+    }
+
+    pub fn requires_confirmation(&self) -> bool {
+        self.confirmation_quorum_bps > 0
+    }
+
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmations as u128 * FULL_REFUND_BPS as u128
+            >= self.participant_count as u128 * self.confirmation_quorum_bps as u128
+    }
+
+    /// Amount of `payout` owed to each payee, in payee order. Shares are rounded down and the
+    /// first payee receives the rounding dust.
+    pub fn split_payout(&self, payout: u64) -> Vec<(Pubkey, u64)> {
+        if self.payee_count == 0 {
+            return vec![(self.payee_pubkey, payout)];
+        }
+
+        let mut split: Vec<(Pubkey, u64)> = self.payees[..self.payee_count as usize]
+            .iter()
+            .map(|share| {
+                let amount = payout as u128 * share.share_bps as u128 / FULL_REFUND_BPS as u128;
+                (share.payee, amount as u64)
+            })
+            .collect();
+        let dust = payout - split.iter().map(|(_, amount)| amount).sum::<u64>();
+        split[0].1 += dust;
+        split
+    }
+
+    pub fn has_allowlist(&self) -> bool {
+        self.allowlist_root != [0; 32]
+    }
+
+    pub fn requires_permit(&self) -> bool {
+        self.permit_signer != Pubkey::default()
+    }
+
+    pub fn has_tickets(&self) -> bool {
+        self.ticket_mint != Pubkey::default()
+    }
+
+    pub fn is_occurrence(&self) -> bool {
+        self.series != Pubkey::default()
+    }
+
+    pub fn has_refund_schedule(&self) -> bool {
+        self.refund_step_count > 0
+    }
+
+    /// Basis points of a deposit refunded when withdrawing at `now`: those of the first step whose
+    /// deadline hasn't passed, or nothing once all of them have
+    pub fn refund_bps(&self, now: i64) -> u16 {
+        if !self.has_refund_schedule() {
+            return FULL_REFUND_BPS;
+        }
+
+        self.refund_schedule[..self.refund_step_count as usize]
+            .iter()
+            .find(|step| now <= self.start_time.saturating_sub(step.seconds_before_start))
+            .map_or(0, |step| step.refund_bps)
+    }
+
+    /// Part of `deposit` refunded when withdrawing at `now`, the rest is retained for the payees
+    pub fn refund_amount(&self, deposit: u64, now: i64) -> u64 {
+        (deposit as u128 * self.refund_bps(now) as u128 / FULL_REFUND_BPS as u128) as u64
+    }
+
+    pub fn has_vesting(&self) -> bool {
+        self.vesting != Vesting::None as u8
+    }
+
+    pub fn is_fully_vested(&self, now: i64) -> bool {
+        now >= self.vesting_start.saturating_add(self.vesting_duration)
+    }
+
+    /// Part of `vesting_total` unlocked for the payees at `now`
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        if !self.has_vesting() || now < self.vesting_start {
+            return 0;
+        }
+        if self.is_fully_vested(now) {
+            return self.vesting_total;
+        }
+        if self.vesting == Vesting::Linear as u8 {
+            let elapsed = (now - self.vesting_start) as u128;
+            (self.vesting_total as u128 * elapsed / self.vesting_duration as u128) as u64
+        } else {
+            0
+        }
+    }
+
+    /// Part of `deposit` still escrowed after the payees claimed vested funds
+    pub fn unvested_refund(&self, deposit: u64) -> u64 {
+        if self.vesting_total == 0 {
+            return deposit;
+        }
+        let unvested = self.vesting_total - self.vested_claimed;
+        (deposit as u128 * unvested as u128 / self.vesting_total as u128) as u64
+    }
+
+    pub fn has_funding_goal(&self) -> bool {
+        self.funding_goal > 0
+    }
+
+    pub fn is_registration_closed(&self, now: i64) -> bool {
+        self.registration_deadline > 0 && now > self.registration_deadline
+    }
+
+    /// Registration closed on an open event without the deposits reaching the funding goal
+    pub fn is_funding_failed(&self, now: i64) -> bool {
+        self.has_funding_goal()
+            && self.is_open()
+            && self.is_registration_closed(now)
+            && self.total_deposits < self.funding_goal
+    }
+
+    /// Pay-what-you-want event, participants choose their deposit between `min_deposit` and `max_deposit`
+    pub fn has_flexible_pricing(&self) -> bool {
+        self.max_deposit > 0
+    }
+
+    pub fn has_price_schedule(&self) -> bool {
+        self.price_step_count > 0
+    }
+
+    /// Price of a seat at `now`: that of the last step already started, or `tier_price` before
+    /// the first one
+    pub fn price_at(&self, tier_price: u64, now: i64) -> u64 {
+        self.price_schedule[..self.price_step_count as usize]
+            .iter()
+            .take_while(|step| step.starts_at <= now)
+            .last()
+            .map_or(tier_price, |step| step.price)
+    }
+
+    pub fn has_checkin(&self) -> bool {
+        self.checkin_commitment != [0; 32]
+    }
+
+    pub fn is_checkin_open(&self, now: i64) -> bool {
+        self.is_started() && self.has_checkin() && now <= self.checkin_deadline
+    }
+
+    pub fn is_lottery(&self) -> bool {
+        self.lottery
+    }
+
+    /// Seats allocated by the lottery, capped by the capacity of its single tier
+    pub fn lottery_seats(&self) -> u64 {
+        self.tier(0)
+            .map_or(0, |ticket_tier| ticket_tier.capacity)
+            .min(self.total_partcipator)
+    }
+
+    /// Whether the lottery entry numbered `entry` won a seat. Entries are shuffled by an affine
+    /// permutation keyed by `lottery_seed`, and the first `lottery_seats` positions win, so each
+    /// entry wins with the same probability.
+    pub fn is_lottery_winner(&self, entry: u64) -> bool {
+        let entries = self.lottery_entries;
+        let seats = self.lottery_seats();
+        if entries <= seats {
+            return true;
+        }
+        if seats == 0 {
+            return false;
+        }
+
+        let word = |offset: usize| {
+            u64::from_le_bytes(<[u8; 8]>::try_from(&self.lottery_seed[offset..offset + 8]).unwrap())
+        };
+        let shift = word(0) % entries;
+        // entries - 1 is always coprime with entries, so the search stops before it
+        let mut stride = word(8) % (entries - 1) + 1;
+        while gcd(stride, entries) != 1 {
+            stride += 1;
+        }
+
+        let position = (stride as u128 * entry as u128 + shift as u128) % entries as u128;
+        position < seats as u128
+    }
+
+    /// Whether `amount` pays for a seat in a tier priced at `price`
+    pub fn accepts_deposit(&self, price: u64, amount: u64) -> bool {
+        if self.has_flexible_pricing() {
+            (self.min_deposit..=self.max_deposit).contains(&amount)
+        } else {
+            amount == price
+        }
+    }
+
+    pub fn has_prize_table(&self) -> bool {
+        self.prize_rank_count > 0
+    }
+
+    /// Whether a paid out competition missed its results deadline, so its sponsors get their
+    /// contributions back instead
+    pub fn is_results_deadline_missed(&self, now: i64) -> bool {
+        self.is_complete()
+            && self.sponsor_pool_destination == SponsorPoolDestination::Ranked as u8
+            && !self.results_submitted
+            && now > self.results_deadline
+    }
+
+    /// Prize of the given rank, starting at 1, out of the prize pool
+    pub fn prize_amount(&self, rank: u8) -> u64 {
+        match self.prize_table[..self.prize_rank_count as usize]
+            .get((rank as usize).wrapping_sub(1))
+        {
+            Some(bps) => (self.prize_pool as u128 * *bps as u128 / FULL_REFUND_BPS as u128) as u64,
+            None => 0,
+        }
+    }
+
+    pub fn tier(&self, tier: u8) -> Option<&EventTier> {
+        self.tiers[..self.tier_count as usize].get(tier as usize)
+    }
+
+    pub fn tier_mut(&mut self, tier: u8) -> Option<&mut EventTier> {
+        self.tiers[..self.tier_count as usize].get_mut(tier as usize)
+    }
+}
+
+/// Participant record stored in an account created by the participant for a single seat
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct Participant {
+    pub status: u8,
+    pub event: Pubkey,
+    pub participant: Pubkey,
+    pub tier: u8,
+    pub deposit: u64,
+    /// Token account holding the participant's ticket; default pubkey when no ticket is issued
+    pub ticket_account: Pubkey,
+    pub dispute_refund_claimed: bool,
+    /// Whether the participant confirmed the event took place
+    pub confirmed: bool,
+    /// Wallet that paid the deposit in a group registration and receives its refunds;
+    /// default pubkey when the participant paid for the seat
+    pub payer: Pubkey,
+    pub sponsor_share_claimed: bool,
+    /// Placement assigned by `SubmitResults`, starting at 1; 0 when unranked
+    pub rank: u8,
+    pub prize_claimed: bool,
+    /// Entry number in the event lottery, in order of entry
+    pub lottery_entry: u64,
+    /// Commitment to the check-in code made by `CheckIn`, see `checkin::participant_commitment`
+    pub checkin_commitment: [u8; 32],
+    /// Whether the participant revealed the check-in code it committed to
+    pub attended: bool,
+}
+
+impl Sealed for Participant {}
+
+impl IsInitialized for Participant {
+    fn is_initialized(&self) -> bool {
+        self.status != ParticipantStatus::Uninitialized as u8
+    }
+}
+
+impl Participant {
+    /// Space the client must reserve when creating the participant account
+    pub const LEN: usize = 1 + 32 + 32 + 1 + 8 + 32 + 1 + 1 + 32 + 1 + 1 + 1 + 8 + 32 + 1;
+
+    pub fn has_ticket(&self) -> bool {
+        self.ticket_account != Pubkey::default()
+    }
+
+    pub fn is_joined(&self) -> bool {
+        self.status == ParticipantStatus::Joined as u8
+    }
+
+    pub fn has_group_payer(&self) -> bool {
+        self.payer != Pubkey::default()
+    }
+
+    /// Account of a wallet queued on a tier waitlist, taking its seat once promoted
+    pub fn is_waiting(&self) -> bool {
+        self.status == ParticipantStatus::Waiting as u8
+    }
+
+    /// Lottery entry waiting for `ResolveLotteryEntry`
+    pub fn is_entered(&self) -> bool {
+        self.status == ParticipantStatus::Entered as u8
+    }
+}
+
+/// Wallet queued for a seat, with the deposit escrowed in the waitlist account
+#[derive(BorshSerialize, BorshDeserialize, Debug, Default, Clone, Copy)]
+pub struct WaitlistEntry {
+    pub wallet: Pubkey,
+    pub deposit: u64,
+    /// Participant account created by the wallet when queueing, holding its seat once promoted
+    pub participant_account: Pubkey,
+}
+
+impl WaitlistEntry {
+    pub const LEN: usize = 32 + 8 + 32;
+}
+
+/// Ordered queue of wallets waiting for a seat in one ticket tier
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct Waitlist {
+    pub status: u8,
+    pub event: Pubkey,
+    pub tier: u8,
+    pub len: u8,
+    pub entries: [WaitlistEntry; MAX_WAITLIST_ENTRIES],
+}
+
+impl Sealed for Waitlist {}
+
+impl IsInitialized for Waitlist {
+    fn is_initialized(&self) -> bool {
+        self.status != WaitlistStatus::Uninitialized as u8
+    }
+}
+
+impl Waitlist {
+    /// Space the client must reserve when creating the waitlist account
+    pub const LEN: usize = 1 + 32 + 1 + 1 + WaitlistEntry::LEN * MAX_WAITLIST_ENTRIES;
+
+    pub fn is_full(&self) -> bool {
+        self.len as usize >= MAX_WAITLIST_ENTRIES
+    }
+
+    pub fn position(&self, wallet: &Pubkey) -> Option<usize> {
+        self.entries[..self.len as usize]
+            .iter()
+            .position(|entry| entry.wallet == *wallet)
+    }
+
+    /// Append a wallet to the back of the queue; the caller checks `is_full` first
+    pub fn push(&mut self, entry: WaitlistEntry) {
+        self.entries[self.len as usize] = entry;
+        self.len += 1;
+    }
+
+    /// Take the entry at `index` out of the queue, keeping the order of the others
+    pub fn remove(&mut self, index: usize) -> WaitlistEntry {
+        let len = self.len as usize;
+        let entry = self.entries[index];
+        self.entries.copy_within(index + 1..len, index);
+        self.entries[len - 1] = WaitlistEntry::default();
+        self.len -= 1;
+        entry
+    }
+
+    pub fn pop_front(&mut self) -> Option<WaitlistEntry> {
+        if self.len == 0 {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+}
+
+/// Template and recurrence rule of a series of events, e.g. a weekly meetup
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct EventSeries {
+    pub status: u8,
+    pub series_creator: Pubkey,
+    /// Event id of the first occurrence, the following ones count up from it
+    pub first_event_id: u64,
+    pub payee_pubkey: Pubkey,
+    pub fix_deposit_amount_per_person: u64,
+    pub total_partcipator: u64,
+    pub organizer_count: u8,
+    /// Wallets allowed to instantiate and run occurrences, besides the series creator
+    pub organizers: [Pubkey; MAX_SERIES_ORGANIZERS],
+    /// Unix timestamp the first occurrence starts at
+    pub first_start: i64,
+    /// Seconds between the starts of two occurrences, e.g. 604800 for a weekly series
+    pub interval: i64,
+    /// Number of occurrences in the series; 0 when it recurs indefinitely
+    pub max_occurrences: u64,
+    pub occurrence_count: u64,
+}
+
+impl Sealed for EventSeries {}
+
+impl IsInitialized for EventSeries {
+    fn is_initialized(&self) -> bool {
+        self.status != SeriesStatus::Uninitialized as u8
+    }
+}
+
+impl EventSeries {
+    /// Space the client must reserve when creating the series account
+    pub const LEN: usize = 1 + 32 + 8 + 32 + 8 + 8 + 1 + 32 * MAX_SERIES_ORGANIZERS + 8 + 8 + 8 + 8;
+
+    pub fn is_organizer(&self, wallet: &Pubkey) -> bool {
+        self.series_creator == *wallet
+            || self.organizers[..self.organizer_count as usize].contains(wallet)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.max_occurrences != 0 && self.occurrence_count >= self.max_occurrences
+    }
+
+    /// Event id of the occurrence at `index`, `None` on overflow
+    pub fn occurrence_event_id(&self, index: u64) -> Option<u64> {
+        self.first_event_id.checked_add(index)
+    }
+
+    /// Start of the occurrence at `index`, `None` on overflow
+    pub fn occurrence_start(&self, index: u64) -> Option<i64> {
+        i64::try_from(index)
+            .ok()?
+            .checked_mul(self.interval)?
+            .checked_add(self.first_start)
+    }
+}
+
+/// Contribution of a sponsor to the sponsor pool of an event, stored in an account created by the sponsor
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct Sponsorship {
+    pub status: u8,
+    pub event: Pubkey,
+    pub sponsor: Pubkey,
+    /// Lamports contributed to the sponsor pool, refunded if the event is cancelled
+    pub amount: u64,
+}
+
+impl Sealed for Sponsorship {}
+
+impl IsInitialized for Sponsorship {
+    fn is_initialized(&self) -> bool {
+        self.status != SponsorshipStatus::Uninitialized as u8
+    }
+}
+
+impl Sponsorship {
+    /// Space the client must reserve when creating the sponsorship account
+    pub const LEN: usize = 1 + 32 + 32 + 8;
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug)]
@@ -61,4 +767,381 @@ pub enum EventStatus {
     Active,
     Completed,
     Terminated,
+    Started,
+    Ended,
+}
+
+/// Release of the escrow to the payees between `StartEvent` and `EndEvent`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Copy, Clone, PartialEq)]
+pub enum Vesting {
+    /// Everything is paid out when the event ends
+    None = 0,
+    /// Unlocks proportionally to the time elapsed since the event started
+    Linear,
+    /// Unlocks all at once when the vesting duration has elapsed
+    Cliff,
+}
+
+/// Recipient of the sponsor pool of a paid out event; cancelled events refund the sponsors
+#[derive(BorshSerialize, BorshDeserialize, Debug, Copy, Clone, PartialEq)]
+pub enum SponsorPoolDestination {
+    /// Paid to the payees along with the deposits
+    Payee = 0,
+    /// Split equally among the seats, the payees receiving what doesn't divide
+    Attendees,
+    /// Awarded by placement following the prize table, see `SubmitResults`
+    Ranked,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Copy, Clone, PartialEq)]
+pub enum TransferPolicy {
+    Allowed = 0,
+    Disabled,
+    RequiresCreator,
+}
+
+#[derive(Copy, Clone)]
+pub enum ParticipantStatus {
+    Uninitialized = 0,
+    Joined,
+    Waiting,
+    Entered,
+}
+
+#[derive(Copy, Clone)]
+pub enum WaitlistStatus {
+    Uninitialized = 0,
+    Active,
+}
+
+#[derive(Copy, Clone)]
+pub enum SponsorshipStatus {
+    Uninitialized = 0,
+    Active,
+}
+
+#[derive(Copy, Clone)]
+pub enum SeriesStatus {
+    Uninitialized = 0,
+    Active,
+}
+
+#[derive(Copy, Clone)]
+pub enum DisputeStatus {
+    None = 0,
+    Open,
+    Resolved,
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event() -> InitEvent {
+        let mut event = InitEvent::try_from_slice(&[0; InitEvent::LEN]).unwrap();
+        event.status = EventStatus::Active as u8;
+        event
+    }
+
+    #[test]
+    fn tiers_are_bounded_by_the_tier_count() {
+        let mut event = event();
+        event.tier_count = 2;
+        event.tiers[0] = EventTier {
+            price: 100,
+            capacity: 2,
+            ..EventTier::default()
+        };
+        event.tiers[1] = EventTier {
+            price: 250,
+            capacity: 1,
+            ..EventTier::default()
+        };
+
+        assert_eq!(event.tier(1).unwrap().price, 250);
+        assert!(event.tier(2).is_none());
+        assert!(event.tier_mut(MAX_EVENT_TIERS as u8).is_none());
+
+        let tier = event.tier_mut(0).unwrap();
+        tier.sold += 1;
+        assert!(!tier.is_sold_out());
+        tier.sold += 1;
+        assert!(tier.is_sold_out());
+        assert!(!event.tier(1).unwrap().is_sold_out());
+    }
+
+    #[test]
+    fn refund_steps_apply_until_their_deadline() {
+        let mut event = event();
+        assert_eq!(event.refund_bps(i64::MAX), FULL_REFUND_BPS);
+
+        event.start_time = 1_000;
+        event.refund_step_count = 2;
+        event.refund_schedule[0] = RefundStep {
+            seconds_before_start: 500,
+            refund_bps: 10_000,
+        };
+        event.refund_schedule[1] = RefundStep {
+            seconds_before_start: 100,
+            refund_bps: 5_000,
+        };
+
+        assert_eq!(event.refund_bps(500), 10_000);
+        assert_eq!(event.refund_bps(501), 5_000);
+        assert_eq!(event.refund_bps(900), 5_000);
+        assert_eq!(event.refund_bps(901), 0);
+        assert_eq!(event.refund_amount(101, 600), 50);
+    }
+
+    #[test]
+    fn confirmation_quorum_is_a_share_of_the_seats() {
+        let mut event = event();
+        event.participant_count = 3;
+        event.confirmation_quorum_bps = 5_000;
+        event.confirmations = 1;
+        assert!(!event.is_confirmed());
+        event.confirmations = 2;
+        assert!(event.is_confirmed());
+
+        event.confirmation_quorum_bps = FULL_REFUND_BPS;
+        assert!(!event.is_confirmed());
+        event.confirmations = 3;
+        assert!(event.is_confirmed());
+    }
+
+    #[test]
+    fn split_payout_gives_the_dust_to_the_first_payee() {
+        let mut event = event();
+        let payees = [
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        ];
+        event.payee_pubkey = payees[0];
+        assert_eq!(event.split_payout(7), vec![(payees[0], 7)]);
+
+        event.payee_count = 3;
+        for (index, payee) in payees.iter().enumerate() {
+            event.payees[index] = PayeeShare {
+                payee: *payee,
+                share_bps: if index == 0 { 3_334 } else { 3_333 },
+            };
+        }
+
+        let split = event.split_payout(100);
+        assert_eq!(
+            split,
+            vec![(payees[0], 34), (payees[1], 33), (payees[2], 33)]
+        );
+        assert_eq!(split.iter().map(|(_, amount)| amount).sum::<u64>(), 100);
+        assert_eq!(event.split_payout(2)[0], (payees[0], 2));
+    }
+
+    #[test]
+    fn lottery_winners_are_a_permutation_of_the_seats() {
+        let mut event = event();
+        event.tier_count = 1;
+        event.tiers[0].capacity = 7;
+        event.total_partcipator = 7;
+
+        event.lottery_entries = 7;
+        assert!((0..7).all(|entry| event.is_lottery_winner(entry)));
+
+        for (entries, seed_byte) in [(8u64, 0u8), (23, 7), (64, 42), (100, 255)] {
+            event.lottery_entries = entries;
+            event.lottery_seed = [seed_byte; 32];
+            event.lottery_seed[8] = seed_byte.wrapping_mul(31);
+            let winners = (0..entries)
+                .filter(|entry| event.is_lottery_winner(*entry))
+                .count();
+            assert_eq!(winners, 7, "{} entries", entries);
+        }
+
+        event.tiers[0].capacity = 0;
+        assert!(!(0..100).any(|entry| event.is_lottery_winner(entry)));
+    }
+
+    #[test]
+    fn checkin_is_open_until_its_deadline() {
+        let mut event = event();
+        event.status = EventStatus::Started as u8;
+        event.checkin_deadline = 1_000;
+        assert!(!event.is_checkin_open(0));
+
+        event.checkin_commitment = [1; 32];
+        assert!(event.is_checkin_open(1_000));
+        assert!(!event.is_checkin_open(1_001));
+
+        event.status = EventStatus::Completed as u8;
+        assert!(!event.is_checkin_open(1_000));
+    }
+
+    #[test]
+    fn waitlist_keeps_the_queue_order() {
+        let mut waitlist = Waitlist::try_from_slice(&[0; Waitlist::LEN]).unwrap();
+        let wallets: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        for wallet in &wallets {
+            waitlist.push(WaitlistEntry {
+                wallet: *wallet,
+                ..WaitlistEntry::default()
+            });
+        }
+
+        assert_eq!(waitlist.remove(1).wallet, wallets[1]);
+        assert_eq!(waitlist.position(&wallets[2]), Some(1));
+        assert_eq!(waitlist.pop_front().unwrap().wallet, wallets[0]);
+        assert_eq!(waitlist.pop_front().unwrap().wallet, wallets[2]);
+        assert!(waitlist.pop_front().is_none());
+    }
+
+    #[test]
+    fn series_occurrences_count_up_from_the_first_one() {
+        let creator = Pubkey::new_unique();
+        let organizer = Pubkey::new_unique();
+        let mut series = EventSeries::try_from_slice(&[0; EventSeries::LEN]).unwrap();
+        series.series_creator = creator;
+        series.organizer_count = 1;
+        series.organizers[0] = organizer;
+        series.first_event_id = 10;
+        series.first_start = 1_000;
+        series.interval = 100;
+        series.max_occurrences = 2;
+
+        assert_eq!(series.occurrence_event_id(1), Some(11));
+        assert_eq!(series.occurrence_start(1), Some(1_100));
+        assert_eq!(series.occurrence_start(u64::MAX), None);
+
+        assert!(series.is_organizer(&creator));
+        assert!(series.is_organizer(&organizer));
+        assert!(!series.is_organizer(&Pubkey::default()));
+
+        assert!(!series.is_finished());
+        series.occurrence_count = 2;
+        assert!(series.is_finished());
+        series.max_occurrences = 0;
+        assert!(!series.is_finished());
+    }
+
+    #[test]
+    fn vesting_unlocks_over_the_duration() {
+        let mut event = event();
+        event.vesting_start = 1_000;
+        event.vesting_duration = 100;
+        event.vesting_total = 1_000;
+        assert_eq!(event.vested_amount(2_000), 0);
+
+        event.vesting = Vesting::Linear as u8;
+        assert_eq!(event.vested_amount(999), 0);
+        assert_eq!(event.vested_amount(1_025), 250);
+        assert_eq!(event.vested_amount(1_100), 1_000);
+
+        event.vesting = Vesting::Cliff as u8;
+        assert_eq!(event.vested_amount(1_099), 0);
+        assert_eq!(event.vested_amount(1_100), 1_000);
+    }
+
+    #[test]
+    fn unvested_refund_excludes_claimed_funds() {
+        let mut event = event();
+        assert_eq!(event.unvested_refund(300), 300);
+
+        event.vesting_total = 1_000;
+        event.vested_claimed = 250;
+        assert_eq!(event.unvested_refund(300), 225);
+
+        event.vested_claimed = 1_000;
+        assert_eq!(event.unvested_refund(300), 0);
+    }
+
+    #[test]
+    fn prizes_are_shares_of_the_prize_pool() {
+        let mut event = event();
+        event.prize_rank_count = 2;
+        event.prize_table[0] = 7_000;
+        event.prize_table[1] = 3_000;
+        event.prize_pool = 1_001;
+
+        assert_eq!(event.prize_amount(0), 0);
+        assert_eq!(event.prize_amount(1), 700);
+        assert_eq!(event.prize_amount(2), 300);
+        assert_eq!(event.prize_amount(3), 0);
+    }
+
+    #[test]
+    fn results_deadline_only_binds_paid_out_competitions() {
+        let mut event = event();
+        event.results_deadline = 1_000;
+        assert!(!event.is_results_deadline_missed(1_001));
+
+        event.status = EventStatus::Completed as u8;
+        event.sponsor_pool_destination = SponsorPoolDestination::Ranked as u8;
+        assert!(!event.is_results_deadline_missed(1_000));
+        assert!(event.is_results_deadline_missed(1_001));
+
+        event.results_submitted = true;
+        assert!(!event.is_results_deadline_missed(1_001));
+    }
+
+    #[test]
+    fn price_follows_the_last_started_step() {
+        let mut event = event();
+        assert_eq!(event.price_at(100, i64::MAX), 100);
+
+        event.price_step_count = 2;
+        event.price_schedule[0] = PriceStep {
+            starts_at: 1_000,
+            price: 150,
+        };
+        event.price_schedule[1] = PriceStep {
+            starts_at: 2_000,
+            price: 200,
+        };
+
+        assert_eq!(event.price_at(100, 999), 100);
+        assert_eq!(event.price_at(100, 1_000), 150);
+        assert_eq!(event.price_at(100, 1_999), 150);
+        assert_eq!(event.price_at(100, 2_000), 200);
+    }
+
+    #[test]
+    fn deposits_match_the_price_or_the_flexible_bounds() {
+        let mut event = event();
+        assert!(event.accepts_deposit(100, 100));
+        assert!(!event.accepts_deposit(100, 101));
+
+        event.min_deposit = 50;
+        event.max_deposit = 150;
+        assert!(event.accepts_deposit(100, 50));
+        assert!(event.accepts_deposit(100, 150));
+        assert!(!event.accepts_deposit(100, 49));
+        assert!(!event.accepts_deposit(100, 151));
+    }
+
+    #[test]
+    fn funding_fails_once_registration_closes_below_the_goal() {
+        let mut event = event();
+        event.registration_deadline = 1_000;
+        assert!(!event.is_funding_failed(1_001));
+
+        event.funding_goal = 500;
+        event.total_deposits = 499;
+        assert!(!event.is_funding_failed(1_000));
+        assert!(event.is_funding_failed(1_001));
+
+        event.total_deposits = 500;
+        assert!(!event.is_funding_failed(1_001));
+
+        event.total_deposits = 0;
+        event.status = EventStatus::Terminated as u8;
+        assert!(!event.is_funding_failed(1_001));
+    }
 }
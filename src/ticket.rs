@@ -0,0 +1,314 @@
+//! Fungible event tickets minted to participants from an event-scoped SPL token mint.
+//!
+//! The ticket mint has 0 decimals and both its mint and freeze authority are the
+//! PDA of `["tickets", event]`, so only this program can issue or freeze tickets.
+//! Each participant receives one unit of the mint: tickets of an event are
+//! interchangeable and prove registration to the event, not to a given seat.
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use spl_token::state::Account as TokenAccount;
+
+use crate::error::RentShareError;
+
+/// Seed prefix of the PDA holding the ticket mint and freeze authority
+pub const TICKET_AUTHORITY_SEED: &[u8] = b"tickets";
+
+/// Ticket authority PDA of an event and its bump seed
+pub fn find_authority(event: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TICKET_AUTHORITY_SEED, event.as_ref()], program_id)
+}
+
+/// Initialize a client-created mint account as the ticket mint of an event
+pub fn initialize_mint<'a>(
+    mint: &AccountInfo<'a>,
+    rent_sysvar: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    event: &Pubkey,
+    program_id: &Pubkey,
+) -> ProgramResult {
+    if *token_program.key != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let (authority, _) = find_authority(event, program_id);
+    let instruction = spl_token::instruction::initialize_mint(
+        token_program.key,
+        mint.key,
+        &authority,
+        Some(&authority),
+        0,
+    )?;
+
+    invoke(
+        &instruction,
+        &[mint.clone(), rent_sysvar.clone(), token_program.clone()],
+    )
+}
+
+/// Accounts passed to every instruction that issues, burns or freezes a ticket:
+/// ticket mint, the holder's token account, ticket authority PDA and token program
+pub struct TicketAccounts<'a, 'b> {
+    pub mint: &'a AccountInfo<'b>,
+    pub token_account: &'a AccountInfo<'b>,
+    pub authority: &'a AccountInfo<'b>,
+    pub token_program: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> TicketAccounts<'a, 'b> {
+    pub fn next<I: Iterator<Item = &'a AccountInfo<'b>>>(
+        accounts_iter: &mut I,
+    ) -> Result<Self, ProgramError> {
+        Ok(Self {
+            mint: next_account_info(accounts_iter)?,
+            token_account: next_account_info(accounts_iter)?,
+            authority: next_account_info(accounts_iter)?,
+            token_program: next_account_info(accounts_iter)?,
+        })
+    }
+
+    /// Make sure the accounts belong to the event ticket mint and the token account is held by
+    /// `holder`, returning the bump seed of the ticket authority
+    pub fn check(
+        &self,
+        event: &Pubkey,
+        ticket_mint: &Pubkey,
+        holder: &Pubkey,
+        program_id: &Pubkey,
+    ) -> Result<u8, ProgramError> {
+        if *self.token_program.key != spl_token::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let (authority, bump_seed) = find_authority(event, program_id);
+        if *self.authority.key != authority {
+            msg!("[RentShare] Ticket authority does not match the event");
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if self.mint.key != ticket_mint || self.token_account.owner != self.token_program.key {
+            msg!("[RentShare] Token account is not an account of the event ticket mint");
+            return Err(RentShareError::InvalidTicketAccount.into());
+        }
+
+        let token_account = TokenAccount::unpack(&self.token_account.data.borrow())?;
+        if token_account.mint != *ticket_mint || token_account.owner != *holder {
+            msg!("[RentShare] Token account is not a ticket account of the participant");
+            return Err(RentShareError::InvalidTicketAccount.into());
+        }
+
+        Ok(bump_seed)
+    }
+
+    /// Tickets held in the token account
+    pub fn balance(&self) -> Result<u64, ProgramError> {
+        Ok(TokenAccount::unpack(&self.token_account.data.borrow())?.amount)
+    }
+
+    pub fn mint_to(&self, event: &Pubkey, bump_seed: u8) -> ProgramResult {
+        let instruction = spl_token::instruction::mint_to(
+            self.token_program.key,
+            self.mint.key,
+            self.token_account.key,
+            self.authority.key,
+            &[],
+            1,
+        )?;
+
+        invoke_signed(
+            &instruction,
+            &[
+                self.mint.clone(),
+                self.token_account.clone(),
+                self.authority.clone(),
+                self.token_program.clone(),
+            ],
+            &[&[TICKET_AUTHORITY_SEED, event.as_ref(), &[bump_seed]]],
+        )
+    }
+
+    /// Burn the ticket with the signature of the wallet holding it
+    pub fn burn(&self, holder: &AccountInfo<'b>) -> ProgramResult {
+        let instruction = spl_token::instruction::burn(
+            self.token_program.key,
+            self.token_account.key,
+            self.mint.key,
+            holder.key,
+            &[],
+            1,
+        )?;
+
+        invoke(
+            &instruction,
+            &[
+                self.token_account.clone(),
+                self.mint.clone(),
+                holder.clone(),
+                self.token_program.clone(),
+            ],
+        )
+    }
+
+    pub fn freeze(&self, event: &Pubkey, bump_seed: u8) -> ProgramResult {
+        let instruction = spl_token::instruction::freeze_account(
+            self.token_program.key,
+            self.token_account.key,
+            self.mint.key,
+            self.authority.key,
+            &[],
+        )?;
+
+        invoke_signed(
+            &instruction,
+            &[
+                self.token_account.clone(),
+                self.mint.clone(),
+                self.authority.clone(),
+                self.token_program.clone(),
+            ],
+            &[&[TICKET_AUTHORITY_SEED, event.as_ref(), &[bump_seed]]],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use spl_token::state::AccountState;
+
+    struct Accounts {
+        keys: [Pubkey; 4],
+        lamports: [u64; 4],
+        data: [Vec<u8>; 4],
+        owners: [Pubkey; 4],
+    }
+
+    impl Accounts {
+        /// Ticket accounts of `event` where the token account holds `amount` of `mint` for `holder`
+        fn new(
+            event: &Pubkey,
+            program_id: &Pubkey,
+            mint: Pubkey,
+            holder: Pubkey,
+            amount: u64,
+        ) -> Self {
+            let mut token_account = vec![0; TokenAccount::LEN];
+            TokenAccount {
+                mint,
+                owner: holder,
+                amount,
+                state: AccountState::Initialized,
+                ..TokenAccount::default()
+            }
+            .pack_into_slice(&mut token_account);
+
+            Accounts {
+                keys: [
+                    mint,
+                    Pubkey::new_unique(),
+                    find_authority(event, program_id).0,
+                    spl_token::id(),
+                ],
+                lamports: [0; 4],
+                data: [vec![], token_account, vec![], vec![]],
+                owners: [
+                    spl_token::id(),
+                    spl_token::id(),
+                    Pubkey::default(),
+                    Pubkey::default(),
+                ],
+            }
+        }
+
+        fn infos(&mut self) -> Vec<AccountInfo<'_>> {
+            let Accounts {
+                keys,
+                lamports,
+                data,
+                owners,
+            } = self;
+            keys.iter()
+                .zip(lamports.iter_mut())
+                .zip(data.iter_mut())
+                .zip(owners.iter())
+                .map(|(((key, lamports), data), owner)| {
+                    AccountInfo::new(key, false, true, lamports, data, owner, false, 0)
+                })
+                .collect()
+        }
+    }
+
+    fn ticket_accounts<'a, 'b>(infos: &'a [AccountInfo<'b>]) -> TicketAccounts<'a, 'b> {
+        TicketAccounts::next(&mut infos.iter()).unwrap()
+    }
+
+    #[test]
+    fn accepts_a_token_account_of_the_mint_held_by_the_holder() {
+        let (event, program_id, mint, holder) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let mut accounts = Accounts::new(&event, &program_id, mint, holder, 1);
+        let infos = accounts.infos();
+        let tickets = ticket_accounts(&infos);
+
+        let bump_seed = tickets.check(&event, &mint, &holder, &program_id).unwrap();
+        assert_eq!(bump_seed, find_authority(&event, &program_id).1);
+        assert_eq!(tickets.balance().unwrap(), 1);
+    }
+
+    #[test]
+    fn rejects_accounts_of_other_mints_holders_or_events() {
+        let (event, program_id, mint, holder) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let invalid = Err(RentShareError::InvalidTicketAccount.into());
+
+        let mut accounts = Accounts::new(&event, &program_id, mint, holder, 1);
+        let infos = accounts.infos();
+        let tickets = ticket_accounts(&infos);
+        assert_eq!(
+            tickets.check(&event, &mint, &Pubkey::new_unique(), &program_id),
+            invalid
+        );
+        assert_eq!(
+            tickets.check(&event, &Pubkey::new_unique(), &holder, &program_id),
+            invalid
+        );
+        assert_eq!(
+            tickets.check(&Pubkey::new_unique(), &mint, &holder, &program_id),
+            Err(ProgramError::InvalidSeeds)
+        );
+
+        // A token account of another mint passed along with the event mint
+        let mut accounts = Accounts::new(&event, &program_id, Pubkey::new_unique(), holder, 1);
+        accounts.keys[0] = mint;
+        let infos = accounts.infos();
+        assert_eq!(
+            ticket_accounts(&infos).check(&event, &mint, &holder, &program_id),
+            invalid
+        );
+
+        // A closed token account is no longer owned by the token program
+        let mut accounts = Accounts::new(&event, &program_id, mint, holder, 1);
+        accounts.owners[1] = Pubkey::default();
+        accounts.data[1] = vec![];
+        let infos = accounts.infos();
+        assert_eq!(
+            ticket_accounts(&infos).check(&event, &mint, &holder, &program_id),
+            invalid
+        );
+    }
+}
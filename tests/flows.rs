@@ -0,0 +1,2966 @@
+//! Event flows run through `Processor::process`, with the system program, the clock and
+//! the rent sysvars provided by syscall stubs.
+
+use std::{cell::Cell, convert::TryFrom, sync::Once};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use rentshare::{
+    allowlist, checkin,
+    error::RentShareError,
+    instruction::{Instruction, TicketTier},
+    lottery,
+    permit::{self, JoinPermit},
+    processor::Processor,
+    series,
+    state::{
+        EventSeries, InitEvent, Participant, PayeeShare, PriceStep, RefundStep,
+        SponsorPoolDestination, Sponsorship, TransferPolicy, Vesting, Waitlist,
+    },
+    ticket,
+};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    ed25519_program,
+    entrypoint::ProgramResult,
+    instruction::Instruction as SolanaInstruction,
+    program_error::ProgramError,
+    program_pack::Pack,
+    program_stubs::{self, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+    sysvar::{
+        self,
+        instructions::{construct_instructions_data, store_current_index, BorrowedInstruction},
+    },
+};
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+const PROGRAM_ID: Pubkey = Pubkey::new_from_array([7; 32]);
+const EVENT_ID: u64 = 1;
+const PRICE: u64 = 1_000_000;
+const WALLET_LAMPORTS: u64 = 10_000_000_000;
+
+thread_local! {
+    static NOW: Cell<i64> = const { Cell::new(1_000) };
+    static SLOT: Cell<u64> = const { Cell::new(0) };
+}
+
+fn set_now(now: i64) {
+    NOW.with(|cell| cell.set(now));
+}
+
+fn set_slot(slot: u64) {
+    SLOT.with(|cell| cell.set(slot));
+}
+
+/// Stubs for the syscalls the processor makes off chain: the clock and rent sysvars, the
+/// system program instructions escrowing deposits and creating PDAs, and the token program
+struct Stubs;
+
+/// Install the syscall stubs and reset the clock
+fn install_stubs() {
+    static STUBS: Once = Once::new();
+    STUBS.call_once(|| {
+        program_stubs::set_syscall_stubs(Box::new(Stubs));
+    });
+    set_now(1_000);
+    set_slot(0);
+}
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_data(&self, _fields: &[&[u8]]) {}
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock {
+            unix_timestamp: NOW.with(Cell::get),
+            slot: SLOT.with(Cell::get),
+            ..Clock::default()
+        };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        solana_program::entrypoint::SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        solana_program::entrypoint::SUCCESS
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &SolanaInstruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let account = |key: &Pubkey| account_infos.iter().find(|info| info.key == key).unwrap();
+        let signed = |info: &AccountInfo| {
+            info.is_signer
+                || signers_seeds.iter().any(|seeds| {
+                    Pubkey::create_program_address(seeds, &PROGRAM_ID) == Ok(*info.key)
+                })
+        };
+
+        if instruction.program_id == spl_token::id() {
+            let infos: Vec<AccountInfo> = instruction
+                .accounts
+                .iter()
+                .map(|meta| {
+                    let mut info = account(&meta.pubkey).clone();
+                    info.is_signer = signed(&info);
+                    info
+                })
+                .collect();
+            return spl_token::processor::Processor::process(
+                &instruction.program_id,
+                &infos,
+                &instruction.data,
+            );
+        }
+
+        assert_eq!(instruction.program_id, system_program::id());
+        let target = account(&instruction.accounts[0].pubkey);
+        if !signed(target) {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let tag = u32::from_le_bytes(<[u8; 4]>::try_from(&instruction.data[..4]).unwrap());
+        match tag {
+            // Assign: the new owner
+            1 => target.assign(&Pubkey::new(&instruction.data[4..36])),
+            // Transfer: the lamports to move to the second account
+            2 => {
+                let lamports =
+                    u64::from_le_bytes(<[u8; 8]>::try_from(&instruction.data[4..12]).unwrap());
+                let to = account(&instruction.accounts[1].pubkey);
+                let balance = target
+                    .lamports()
+                    .checked_sub(lamports)
+                    .ok_or(ProgramError::InsufficientFunds)?;
+                **target.lamports.borrow_mut() = balance;
+                **to.lamports.borrow_mut() += lamports;
+            }
+            // Allocate: account data can't grow off chain, so tests pass it pre-sized
+            8 => {
+                let space =
+                    u64::from_le_bytes(<[u8; 8]>::try_from(&instruction.data[4..12]).unwrap());
+                assert_eq!(target.data_len() as u64, space);
+            }
+            _ => panic!("unexpected system instruction {}", tag),
+        }
+        Ok(())
+    }
+}
+
+struct TestAccount {
+    key: Pubkey,
+    lamports: u64,
+    data: Vec<u8>,
+    owner: Pubkey,
+    is_signer: bool,
+}
+
+impl TestAccount {
+    fn wallet() -> Self {
+        TestAccount {
+            key: Pubkey::new_unique(),
+            lamports: WALLET_LAMPORTS,
+            data: vec![],
+            owner: system_program::id(),
+            is_signer: true,
+        }
+    }
+
+    /// Rent exempt account created by the client for the program to initialize
+    fn program_account(len: usize) -> Self {
+        TestAccount {
+            key: Pubkey::new_unique(),
+            lamports: Rent::default().minimum_balance(len),
+            data: vec![0; len],
+            owner: PROGRAM_ID,
+            is_signer: false,
+        }
+    }
+
+    fn system_program() -> Self {
+        TestAccount {
+            key: system_program::id(),
+            lamports: 1,
+            data: vec![],
+            owner: Pubkey::default(),
+            is_signer: false,
+        }
+    }
+
+    fn rent_sysvar() -> Self {
+        let rent = Rent::default();
+        TestAccount {
+            key: sysvar::rent::id(),
+            lamports: 1,
+            data: [
+                &rent.lamports_per_byte_year.to_le_bytes()[..],
+                &rent.exemption_threshold.to_le_bytes(),
+                &[rent.burn_percent],
+            ]
+            .concat(),
+            owner: sysvar::id(),
+            is_signer: false,
+        }
+    }
+
+    /// SlotHashes sysvar holding `entries`, from the most recent slot on
+    fn slot_hashes(entries: &[(u64, [u8; 32])]) -> Self {
+        let mut data = (entries.len() as u64).to_le_bytes().to_vec();
+        for (slot, hash) in entries {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(hash);
+        }
+        TestAccount {
+            key: sysvar::slot_hashes::id(),
+            lamports: 1,
+            data,
+            owner: sysvar::id(),
+            is_signer: false,
+        }
+    }
+
+    /// Account owned by the token program holding `state`
+    fn token_account<T: Pack>(key: Pubkey, state: T) -> Self {
+        let mut data = vec![0; T::LEN];
+        state.pack_into_slice(&mut data);
+        TestAccount {
+            key,
+            lamports: Rent::default().minimum_balance(T::LEN),
+            data,
+            owner: spl_token::id(),
+            is_signer: false,
+        }
+    }
+
+    fn token(&self) -> TokenAccount {
+        TokenAccount::unpack(&self.data).unwrap()
+    }
+
+    fn event(&self) -> InitEvent {
+        InitEvent::try_from_slice(&self.data).unwrap()
+    }
+
+    fn participant(&self) -> Participant {
+        Participant::try_from_slice(&self.data).unwrap()
+    }
+}
+
+fn process(instruction: &Instruction, accounts: &mut [&mut TestAccount]) -> ProgramResult {
+    let infos: Vec<AccountInfo> = accounts
+        .iter_mut()
+        .map(|account| {
+            let account = &mut **account;
+            AccountInfo::new(
+                &account.key,
+                account.is_signer,
+                true,
+                &mut account.lamports,
+                &mut account.data,
+                &account.owner,
+                false,
+                0,
+            )
+        })
+        .collect();
+    Processor::process(&PROGRAM_ID, &infos, &instruction.try_to_vec().unwrap())
+}
+
+fn custom(error: RentShareError) -> ProgramResult {
+    Err(error.into())
+}
+
+/// Event selling `seats` seats at `PRICE`, along with the accounts its instructions need
+struct Env {
+    event: TestAccount,
+    creator: TestAccount,
+    payee: TestAccount,
+    system_program: TestAccount,
+}
+
+impl Env {
+    fn new(seats: u64) -> Self {
+        Self::with(seats, |_| {})
+    }
+
+    /// Event whose `InitializeEvent` instruction is adjusted by `configure` before it is sent
+    fn with(seats: u64, configure: impl FnOnce(&mut Instruction)) -> Self {
+        Self::try_with(seats, configure).unwrap()
+    }
+
+    fn try_with(
+        seats: u64,
+        configure: impl FnOnce(&mut Instruction),
+    ) -> Result<Self, ProgramError> {
+        install_stubs();
+
+        let mut rent_sysvar = TestAccount::rent_sysvar();
+        let mut env = Env {
+            event: TestAccount::program_account(InitEvent::LEN),
+            creator: TestAccount::wallet(),
+            payee: TestAccount::wallet(),
+            system_program: TestAccount::system_program(),
+        };
+
+        let mut instruction = Instruction::InitializeEvent {
+            payee_pubkey: env.payee.key,
+            event_creator: env.creator.key,
+            event_id: EVENT_ID,
+            fix_deposit_amount_per_person: PRICE,
+            total_partcipator: seats,
+            tiers: vec![],
+            allowlist_root: [0; 32],
+            permit_signer: Pubkey::default(),
+            start_time: 0,
+            refund_schedule: vec![],
+            bond: 0,
+            arbiter: Pubkey::default(),
+            dispute_window: 0,
+            confirmation_quorum_bps: 0,
+            confirmation_window: 0,
+            payees: vec![],
+            vesting: Vesting::None,
+            vesting_duration: 0,
+            prize_table: vec![],
+            results_window: 0,
+            funding_goal: 0,
+            registration_deadline: 0,
+            min_deposit: 0,
+            max_deposit: 0,
+            price_schedule: vec![],
+            lottery: false,
+        };
+        configure(&mut instruction);
+        let mut accounts = vec![&mut env.event, &mut rent_sysvar];
+        if let Instruction::InitializeEvent { bond, .. } = &instruction {
+            if *bond > 0 {
+                accounts.push(&mut env.creator);
+                accounts.push(&mut env.system_program);
+            }
+        }
+        process(&instruction, &mut accounts)?;
+        Ok(env)
+    }
+
+    fn participation(&self, tier: u8, amount: u64) -> Instruction {
+        Instruction::ParticipateInEvent {
+            event_id: EVENT_ID,
+            particpate_amount: amount,
+            event_creator: self.creator.key,
+            tier,
+            allowlist_proof: vec![],
+        }
+    }
+
+    fn join_tier(
+        &mut self,
+        wallet: &mut TestAccount,
+        tier: u8,
+        amount: u64,
+    ) -> Result<TestAccount, ProgramError> {
+        let instruction = self.participation(tier, amount);
+        self.participate(wallet, &instruction)
+    }
+
+    fn participate(
+        &mut self,
+        wallet: &mut TestAccount,
+        instruction: &Instruction,
+    ) -> Result<TestAccount, ProgramError> {
+        self.participate_with(wallet, instruction, &mut [])
+    }
+
+    /// Join with the optional accounts following the system program
+    fn participate_with(
+        &mut self,
+        wallet: &mut TestAccount,
+        instruction: &Instruction,
+        optional_accounts: &mut [&mut TestAccount],
+    ) -> Result<TestAccount, ProgramError> {
+        let mut participant_account = TestAccount::program_account(Participant::LEN);
+        let mut accounts: Vec<&mut TestAccount> = vec![
+            &mut self.event,
+            &mut participant_account,
+            wallet,
+            &mut self.system_program,
+        ];
+        accounts.extend(optional_accounts.iter_mut().map(|account| &mut **account));
+        process(instruction, &mut accounts)?;
+        Ok(participant_account)
+    }
+
+    fn join(&mut self, wallet: &mut TestAccount) -> TestAccount {
+        self.join_tier(wallet, 0, PRICE).unwrap()
+    }
+
+    fn withdraw(
+        &mut self,
+        participant_account: &mut TestAccount,
+        wallet: &mut TestAccount,
+        optional_accounts: &mut [&mut TestAccount],
+    ) -> ProgramResult {
+        let instruction = Instruction::WithdrawFromEvent {
+            event_id: EVENT_ID,
+            event_creator: self.creator.key,
+        };
+        let mut accounts: Vec<&mut TestAccount> =
+            vec![&mut self.event, participant_account, wallet];
+        accounts.extend(optional_accounts.iter_mut().map(|account| &mut **account));
+        process(&instruction, &mut accounts)
+    }
+
+    /// Withdraw while registration is open from an event with a refund schedule
+    fn withdraw_before_start(
+        &mut self,
+        participant_account: &mut TestAccount,
+        wallet: &mut TestAccount,
+    ) -> ProgramResult {
+        let instruction = Instruction::WithdrawFromEvent {
+            event_id: EVENT_ID,
+            event_creator: self.creator.key,
+        };
+        process(
+            &instruction,
+            &mut [
+                &mut self.event,
+                participant_account,
+                wallet,
+                &mut self.payee,
+            ],
+        )
+    }
+
+    /// Waitlist of tier 0
+    fn waitlist(&mut self) -> TestAccount {
+        let mut waitlist = TestAccount::program_account(Waitlist::LEN);
+        let instruction = Instruction::InitializeWaitlist {
+            event_id: EVENT_ID,
+            event_creator: self.creator.key,
+            tier: 0,
+        };
+        process(
+            &instruction,
+            &mut [&mut self.event, &mut waitlist, &mut self.creator],
+        )
+        .unwrap();
+        waitlist
+    }
+
+    fn join_waitlist(
+        &mut self,
+        waitlist: &mut TestAccount,
+        wallet: &mut TestAccount,
+        deposit: u64,
+    ) -> Result<TestAccount, ProgramError> {
+        let mut participant_account = TestAccount::program_account(Participant::LEN);
+        let instruction = Instruction::JoinWaitlist {
+            event_id: EVENT_ID,
+            event_creator: self.creator.key,
+            tier: 0,
+            deposit,
+            allowlist_proof: vec![],
+        };
+        process(
+            &instruction,
+            &mut [
+                &mut self.event,
+                waitlist,
+                &mut participant_account,
+                wallet,
+                &mut self.system_program,
+            ],
+        )?;
+        Ok(participant_account)
+    }
+
+    fn creator_instruction(&mut self, instruction: Instruction) -> ProgramResult {
+        process(&instruction, &mut [&mut self.event, &mut self.creator])
+    }
+
+    fn start(&mut self) -> ProgramResult {
+        let start = Instruction::StartEvent {
+            event_id: EVENT_ID,
+            event_creator: self.creator.key,
+        };
+        self.creator_instruction(start)
+    }
+
+    fn settle(&mut self) -> ProgramResult {
+        let settle = Instruction::SettleEvent {
+            event_id: EVENT_ID,
+            event_creator: self.creator.key,
+        };
+        process(
+            &settle,
+            &mut [&mut self.event, &mut self.payee, &mut self.creator],
+        )
+    }
+
+    /// Register `wallets` in tier 0 with deposits paid by `payer`
+    fn join_group(
+        &mut self,
+        payer: &mut TestAccount,
+        wallets: &mut [TestAccount],
+    ) -> Result<Vec<TestAccount>, ProgramError> {
+        let instruction = Instruction::BatchParticipateInEvent {
+            event_id: EVENT_ID,
+            event_creator: self.creator.key,
+            tier: 0,
+            particpate_amount: PRICE,
+        };
+        let mut participant_accounts: Vec<TestAccount> = wallets
+            .iter()
+            .map(|_| TestAccount::program_account(Participant::LEN))
+            .collect();
+        let mut accounts: Vec<&mut TestAccount> =
+            vec![&mut self.event, payer, &mut self.system_program];
+        for (participant_account, wallet) in participant_accounts.iter_mut().zip(wallets) {
+            accounts.push(participant_account);
+            accounts.push(wallet);
+        }
+        process(&instruction, &mut accounts)?;
+        Ok(participant_accounts)
+    }
+
+    /// Contribute `amount` to the sponsor pool from `sponsor`, returning its sponsorship account
+    fn sponsor(&mut self, sponsor: &mut TestAccount, amount: u64) -> TestAccount {
+        let mut sponsorship = TestAccount::program_account(Sponsorship::LEN);
+        let instruction = Instruction::Sponsor {
+            event_id: EVENT_ID,
+            event_creator: self.creator.key,
+            amount,
+        };
+        process(
+            &instruction,
+            &mut [
+                &mut self.event,
+                &mut sponsorship,
+                sponsor,
+                &mut self.system_program,
+            ],
+        )
+        .unwrap();
+        sponsorship
+    }
+
+    fn claim_vested(&mut self) -> ProgramResult {
+        let claim = Instruction::ClaimVested {
+            event_id: EVENT_ID,
+            event_creator: self.creator.key,
+        };
+        process(&claim, &mut [&mut self.event, &mut self.payee])
+    }
+
+    /// Instruction signed by a participant wallet along with its participant account
+    fn participant_instruction(
+        &mut self,
+        instruction: Instruction,
+        participant_account: &mut TestAccount,
+        wallet: &mut TestAccount,
+    ) -> ProgramResult {
+        process(
+            &instruction,
+            &mut [&mut self.event, participant_account, wallet],
+        )
+    }
+
+    fn end(&mut self) -> ProgramResult {
+        let end = Instruction::EndEvent {
+            event_id: EVENT_ID,
+            event_creator: self.creator.key,
+        };
+        process(
+            &end,
+            &mut [&mut self.event, &mut self.creator, &mut self.payee],
+        )
+    }
+}
+
+fn tiers(configure: &mut Instruction) {
+    if let Instruction::InitializeEvent { tiers, .. } = configure {
+        *tiers = vec![
+            TicketTier {
+                price: PRICE,
+                capacity: 2,
+            },
+            TicketTier {
+                price: 3 * PRICE,
+                capacity: 1,
+            },
+        ];
+    }
+}
+
+#[test]
+fn joining_a_tier_escrows_its_price_and_records_the_tier() {
+    let mut env = Env::with(3, tiers);
+    let mut wallet = TestAccount::wallet();
+    let event_lamports = env.event.lamports;
+    let participant_account = env.join_tier(&mut wallet, 1, 3 * PRICE).unwrap();
+
+    let participant = participant_account.participant();
+    assert_eq!(participant.tier, 1);
+    assert_eq!(participant.deposit, 3 * PRICE);
+    assert_eq!(participant.participant, wallet.key);
+    assert_eq!(participant.event, env.event.key);
+    assert_eq!(wallet.lamports, WALLET_LAMPORTS - 3 * PRICE);
+    assert_eq!(env.event.lamports, event_lamports + 3 * PRICE);
+
+    let event = env.event.event();
+    assert_eq!(event.tier_count, 2);
+    assert_eq!(event.tier(1).unwrap().sold, 1);
+    assert_eq!(event.participant_count, 1);
+    assert_eq!(event.total_deposits, 3 * PRICE);
+}
+
+#[test]
+fn joins_are_checked_against_the_tier_price_and_capacity() {
+    let mut env = Env::with(3, tiers);
+    let mut first = TestAccount::wallet();
+    let mut second = TestAccount::wallet();
+
+    assert_eq!(
+        env.join_tier(&mut first, 1, PRICE).err(),
+        custom(RentShareError::DepositAmountMismatch).err()
+    );
+    assert_eq!(
+        env.join_tier(&mut first, 2, PRICE).err(),
+        custom(RentShareError::InvalidTicketTier).err()
+    );
+
+    env.join_tier(&mut first, 1, 3 * PRICE).unwrap();
+    assert_eq!(
+        env.join_tier(&mut second, 1, 3 * PRICE).err(),
+        custom(RentShareError::TicketTierSoldOut).err()
+    );
+    assert_eq!(second.lamports, WALLET_LAMPORTS);
+}
+
+#[test]
+fn a_full_event_rejects_more_participants() {
+    let mut env = Env::new(1);
+    let mut first = TestAccount::wallet();
+    let mut second = TestAccount::wallet();
+    env.join(&mut first);
+
+    assert_eq!(
+        env.join_tier(&mut second, 0, PRICE).err(),
+        custom(RentShareError::EventFull).err()
+    );
+}
+
+#[test]
+fn ending_a_started_event_pays_the_escrow_to_the_payee() {
+    let mut env = Env::new(2);
+    let mut first = TestAccount::wallet();
+    let mut second = TestAccount::wallet();
+    env.join(&mut first);
+    env.join(&mut second);
+
+    assert_eq!(env.end(), custom(RentShareError::InvalidEventStatus));
+    env.start().unwrap();
+    assert_eq!(
+        env.join_tier(&mut TestAccount::wallet(), 0, PRICE).err(),
+        custom(RentShareError::EventRegistrationClosed).err()
+    );
+
+    env.end().unwrap();
+    assert!(env.event.event().is_complete());
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS + 2 * PRICE);
+    assert_eq!(env.event.event().total_deposits, 0);
+    assert_eq!(
+        env.event.lamports,
+        Rent::default().minimum_balance(InitEvent::LEN)
+    );
+}
+
+#[test]
+fn only_the_creator_can_cancel_an_event() {
+    let mut env = Env::new(2);
+    let cancel = Instruction::CancelEvent {
+        event_creator: env.creator.key,
+        event_id: EVENT_ID,
+    };
+    let mut impostor = TestAccount::wallet();
+    assert_eq!(
+        process(&cancel, &mut [&mut env.event, &mut impostor]),
+        custom(RentShareError::EventCreatorMismatch)
+    );
+
+    env.creator.is_signer = false;
+    assert_eq!(
+        env.creator_instruction(cancel),
+        Err(ProgramError::MissingRequiredSignature)
+    );
+
+    env.creator.is_signer = true;
+    let cancel = Instruction::CancelEvent {
+        event_creator: env.creator.key,
+        event_id: EVENT_ID,
+    };
+    env.creator_instruction(cancel).unwrap();
+    assert!(env.event.event().is_terminated());
+}
+
+fn bond(amount: u64) -> impl FnOnce(&mut Instruction) {
+    move |instruction| {
+        if let Instruction::InitializeEvent { bond, .. } = instruction {
+            *bond = amount;
+        }
+    }
+}
+
+#[test]
+fn creator_bonds_are_returned_once_the_event_ends() {
+    let mut env = Env::with(2, bond(PRICE));
+    assert_eq!(env.creator.lamports, WALLET_LAMPORTS - PRICE);
+    assert_eq!(env.event.event().bond, PRICE);
+
+    let mut wallet = TestAccount::wallet();
+    env.join(&mut wallet);
+    env.start().unwrap();
+    env.end().unwrap();
+
+    assert_eq!(env.creator.lamports, WALLET_LAMPORTS);
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS + PRICE);
+    assert_eq!(env.event.event().bond, 0);
+}
+
+#[test]
+fn cancelling_splits_the_creator_bond_among_the_seats() {
+    let mut env = Env::with(3, bond(1_000_001));
+    let mut first = TestAccount::wallet();
+    let mut second = TestAccount::wallet();
+    let mut first_account = env.join(&mut first);
+    let mut second_account = env.join(&mut second);
+
+    let cancel = Instruction::CancelEvent {
+        event_creator: env.creator.key,
+        event_id: EVENT_ID,
+    };
+    env.creator_instruction(cancel).unwrap();
+    assert_eq!(env.creator.lamports, WALLET_LAMPORTS - 1_000_000);
+    assert_eq!(env.event.event().bond_share, 500_000);
+
+    env.withdraw(&mut first_account, &mut first, &mut [])
+        .unwrap();
+    env.withdraw(&mut second_account, &mut second, &mut [])
+        .unwrap();
+
+    let rent = Rent::default().minimum_balance(Participant::LEN);
+    assert_eq!(first.lamports, WALLET_LAMPORTS + rent + 500_000);
+    assert_eq!(second.lamports, WALLET_LAMPORTS + rent + 500_000);
+    assert_eq!(env.event.event().bond, 0);
+    assert_eq!(
+        env.event.lamports,
+        Rent::default().minimum_balance(InitEvent::LEN)
+    );
+}
+
+#[test]
+fn cancelling_an_empty_event_returns_the_whole_bond() {
+    let mut env = Env::with(3, bond(PRICE));
+    let cancel = Instruction::CancelEvent {
+        event_creator: env.creator.key,
+        event_id: EVENT_ID,
+    };
+    env.creator_instruction(cancel).unwrap();
+
+    assert_eq!(env.creator.lamports, WALLET_LAMPORTS);
+    assert_eq!(env.event.event().bond, 0);
+}
+
+#[test]
+fn invite_only_events_require_an_allowlist_proof() {
+    let mut invited = TestAccount::wallet();
+    let mut other = TestAccount::wallet();
+    let sibling = allowlist::leaf(&Pubkey::new_unique());
+    let root = allowlist::node(&allowlist::leaf(&invited.key), &sibling);
+    let mut env = Env::with(2, |instruction| {
+        if let Instruction::InitializeEvent { allowlist_root, .. } = instruction {
+            *allowlist_root = root;
+        }
+    });
+
+    let mut instruction = env.participation(0, PRICE);
+    assert_eq!(
+        env.participate(&mut invited, &instruction).err(),
+        custom(RentShareError::NotOnAllowlist).err()
+    );
+    if let Instruction::ParticipateInEvent {
+        allowlist_proof, ..
+    } = &mut instruction
+    {
+        *allowlist_proof = vec![sibling];
+    }
+    assert_eq!(
+        env.participate(&mut other, &instruction).err(),
+        custom(RentShareError::NotOnAllowlist).err()
+    );
+    env.participate(&mut invited, &instruction).unwrap();
+
+    // Removing the root opens the event to everyone until registration closes
+    let set_root = |allowlist_root| Instruction::SetAllowlistRoot {
+        event_id: EVENT_ID,
+        event_creator: env.creator.key,
+        allowlist_root,
+    };
+    let (clear, rotate) = (set_root([0; 32]), set_root(root));
+    env.creator_instruction(clear).unwrap();
+    env.join(&mut other);
+
+    env.start().unwrap();
+    assert_eq!(
+        env.creator_instruction(rotate),
+        custom(RentShareError::EventRegistrationClosed)
+    );
+}
+
+/// Instructions sysvar of a transaction where an Ed25519 program instruction verifies
+/// `permit`, signed by `signer`, right before the current instruction
+fn permit_transaction(signer: &Pubkey, permit: &JoinPermit) -> TestAccount {
+    const SIGNATURE_LEN: usize = 64;
+    const INLINE: u16 = u16::MAX;
+
+    let message = permit.try_to_vec().unwrap();
+    let public_key_offset: u16 = 16;
+    let signature_offset = public_key_offset + 32;
+    let message_offset = signature_offset + SIGNATURE_LEN as u16;
+    let mut ed25519_data = vec![1, 0];
+    for offset in [
+        signature_offset,
+        INLINE,
+        public_key_offset,
+        INLINE,
+        message_offset,
+        message.len() as u16,
+        INLINE,
+    ] {
+        ed25519_data.extend_from_slice(&offset.to_le_bytes());
+    }
+    ed25519_data.extend_from_slice(signer.as_ref());
+    ed25519_data.extend_from_slice(&[0; SIGNATURE_LEN]);
+    ed25519_data.extend_from_slice(&message);
+
+    let mut data = construct_instructions_data(&[
+        BorrowedInstruction {
+            program_id: &ed25519_program::id(),
+            accounts: vec![],
+            data: &ed25519_data,
+        },
+        BorrowedInstruction {
+            program_id: &PROGRAM_ID,
+            accounts: vec![],
+            data: &[],
+        },
+    ]);
+    store_current_index(&mut data, 1);
+    TestAccount {
+        key: sysvar::instructions::id(),
+        lamports: 1,
+        data,
+        owner: sysvar::id(),
+        is_signer: false,
+    }
+}
+
+/// Account at the PDA recording the use of a permit nonce, not created yet
+fn permit_nonce_account(event: &Pubkey, nonce: u64) -> TestAccount {
+    let nonce = nonce.to_le_bytes();
+    let seeds = permit::nonce_seeds(event, &nonce);
+    TestAccount {
+        key: Pubkey::find_program_address(&seeds, &PROGRAM_ID).0,
+        lamports: 0,
+        data: vec![],
+        owner: system_program::id(),
+        is_signer: false,
+    }
+}
+
+#[test]
+fn permits_are_checked_and_can_only_be_used_once() {
+    let signer = Pubkey::new_unique();
+    let mut env = Env::with(3, |instruction| {
+        if let Instruction::InitializeEvent { permit_signer, .. } = instruction {
+            *permit_signer = signer;
+        }
+    });
+    let mut wallet = TestAccount::wallet();
+    let event = env.event.key;
+    let permit_for = |wallet: &TestAccount, nonce: u64| JoinPermit {
+        event,
+        participant: wallet.key,
+        expiry: 2_000,
+        nonce,
+    };
+    let instruction = env.participation(0, PRICE);
+
+    // Permits of other signers or wallets are rejected
+    let permit = permit_for(&wallet, 1);
+    let mut nonce = permit_nonce_account(&event, 1);
+    let mut forged = permit_transaction(&Pubkey::new_unique(), &permit);
+    assert_eq!(
+        env.participate_with(&mut wallet, &instruction, &mut [&mut forged, &mut nonce])
+            .err(),
+        custom(RentShareError::InvalidPermit).err()
+    );
+    let mut other = TestAccount::wallet();
+    let mut permit_sysvar = permit_transaction(&signer, &permit);
+    assert_eq!(
+        env.participate_with(
+            &mut other,
+            &instruction,
+            &mut [&mut permit_sysvar, &mut nonce]
+        )
+        .err(),
+        custom(RentShareError::InvalidPermit).err()
+    );
+
+    // Lamports sent to the nonce address don't burn the permit
+    nonce.lamports = 1;
+    env.participate_with(
+        &mut wallet,
+        &instruction,
+        &mut [&mut permit_sysvar, &mut nonce],
+    )
+    .unwrap();
+    assert_eq!(nonce.owner, PROGRAM_ID);
+    assert_eq!(nonce.lamports, Rent::default().minimum_balance(0));
+    assert_eq!(
+        wallet.lamports,
+        WALLET_LAMPORTS - PRICE - Rent::default().minimum_balance(0) + 1
+    );
+
+    let mut replay = TestAccount::wallet();
+    let mut replay_sysvar = permit_transaction(&signer, &permit_for(&replay, 1));
+    assert_eq!(
+        env.participate_with(
+            &mut replay,
+            &instruction,
+            &mut [&mut replay_sysvar, &mut nonce]
+        )
+        .err(),
+        custom(RentShareError::PermitAlreadyUsed).err()
+    );
+
+    let mut late_sysvar = permit_transaction(&signer, &permit_for(&replay, 2));
+    let mut late_nonce = permit_nonce_account(&event, 2);
+    set_now(2_001);
+    assert_eq!(
+        env.participate_with(
+            &mut replay,
+            &instruction,
+            &mut [&mut late_sysvar, &mut late_nonce]
+        )
+        .err(),
+        custom(RentShareError::PermitExpired).err()
+    );
+    assert_eq!(env.event.event().participant_count, 1);
+}
+
+#[test]
+fn withdrawing_refunds_the_deposit_and_the_participant_rent() {
+    let mut env = Env::new(2);
+    let mut wallet = TestAccount::wallet();
+    let mut participant_account = env.join(&mut wallet);
+    assert!(participant_account.participant().is_joined());
+
+    let event_lamports = env.event.lamports;
+    let rent = participant_account.lamports;
+    let mut other = TestAccount::wallet();
+    assert_eq!(
+        env.withdraw(&mut participant_account, &mut other, &mut []),
+        custom(RentShareError::ParticipantMismatch)
+    );
+    env.withdraw(&mut participant_account, &mut wallet, &mut [])
+        .unwrap();
+
+    assert_eq!(wallet.lamports, WALLET_LAMPORTS + rent);
+    assert_eq!(env.event.lamports, event_lamports - PRICE);
+    assert_eq!(participant_account.lamports, 0);
+    assert!(participant_account.data.iter().all(|byte| *byte == 0));
+    assert_eq!(env.event.event().participant_count, 0);
+    assert_eq!(env.event.event().total_deposits, 0);
+    assert_eq!(env.event.event().tier(0).unwrap().sold, 0);
+}
+
+#[test]
+fn cancelled_events_refund_every_seat() {
+    let mut env = Env::new(2);
+    let mut first = TestAccount::wallet();
+    let mut second = TestAccount::wallet();
+    let mut first_account = env.join(&mut first);
+    let mut second_account = env.join(&mut second);
+
+    env.start().unwrap();
+    assert_eq!(
+        env.withdraw(&mut first_account, &mut first, &mut []),
+        custom(RentShareError::InvalidEventStatus)
+    );
+
+    let cancel = Instruction::CancelEvent {
+        event_creator: env.creator.key,
+        event_id: EVENT_ID,
+    };
+    env.creator_instruction(cancel).unwrap();
+    env.withdraw(&mut first_account, &mut first, &mut [])
+        .unwrap();
+    env.withdraw(&mut second_account, &mut second, &mut [])
+        .unwrap();
+
+    let rent = Rent::default().minimum_balance(Participant::LEN);
+    assert_eq!(first.lamports, WALLET_LAMPORTS + rent);
+    assert_eq!(second.lamports, WALLET_LAMPORTS + rent);
+    assert_eq!(env.event.event().total_deposits, 0);
+    assert_eq!(
+        env.event.lamports,
+        Rent::default().minimum_balance(InitEvent::LEN)
+    );
+}
+
+fn refund_schedule(configure: &mut Instruction) {
+    if let Instruction::InitializeEvent {
+        start_time,
+        refund_schedule,
+        ..
+    } = configure
+    {
+        *start_time = 10_000;
+        *refund_schedule = vec![
+            RefundStep {
+                seconds_before_start: 5_000,
+                refund_bps: 10_000,
+            },
+            RefundStep {
+                seconds_before_start: 1_000,
+                refund_bps: 2_500,
+            },
+        ];
+    }
+}
+
+#[test]
+fn refund_schedules_are_validated() {
+    let step = |seconds_before_start, refund_bps| RefundStep {
+        seconds_before_start,
+        refund_bps,
+    };
+    for (start, schedule) in [
+        (10_000, vec![step(1_000, 5_000), step(5_000, 10_000)]),
+        (10_000, vec![step(1_000, 10_001)]),
+        (0, vec![step(1_000, 5_000)]),
+        (10_000, vec![step(0, 0); 5]),
+    ] {
+        let result = Env::try_with(1, |instruction| {
+            if let Instruction::InitializeEvent {
+                start_time,
+                refund_schedule,
+                ..
+            } = instruction
+            {
+                *start_time = start;
+                *refund_schedule = schedule;
+            }
+        });
+        assert_eq!(
+            result.err(),
+            custom(RentShareError::InvalidRefundSchedule).err()
+        );
+    }
+}
+
+#[test]
+fn withdrawals_before_the_start_follow_the_refund_schedule() {
+    let mut env = Env::with(3, refund_schedule);
+    let mut early = TestAccount::wallet();
+    let mut late = TestAccount::wallet();
+    let mut last_minute = TestAccount::wallet();
+    let mut early_account = env.join(&mut early);
+    let mut late_account = env.join(&mut late);
+    let mut last_minute_account = env.join(&mut last_minute);
+    let rent = Rent::default().minimum_balance(Participant::LEN);
+
+    assert_eq!(
+        env.withdraw(
+            &mut early_account,
+            &mut early,
+            &mut [&mut TestAccount::wallet()]
+        ),
+        Err(ProgramError::InvalidAccountData)
+    );
+
+    set_now(5_000);
+    env.withdraw_before_start(&mut early_account, &mut early)
+        .unwrap();
+    assert_eq!(early.lamports, WALLET_LAMPORTS + rent);
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS);
+
+    set_now(5_001);
+    env.withdraw_before_start(&mut late_account, &mut late)
+        .unwrap();
+    assert_eq!(late.lamports, WALLET_LAMPORTS - PRICE + PRICE / 4 + rent);
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS + PRICE - PRICE / 4);
+
+    set_now(9_001);
+    env.withdraw_before_start(&mut last_minute_account, &mut last_minute)
+        .unwrap();
+    assert_eq!(last_minute.lamports, WALLET_LAMPORTS - PRICE + rent);
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS + 2 * PRICE - PRICE / 4);
+    assert_eq!(env.event.event().total_deposits, 0);
+    assert_eq!(
+        env.event.lamports,
+        Rent::default().minimum_balance(InitEvent::LEN)
+    );
+}
+
+#[test]
+fn cancelled_events_ignore_the_refund_schedule() {
+    let mut env = Env::with(1, refund_schedule);
+    let mut wallet = TestAccount::wallet();
+    let mut participant_account = env.join(&mut wallet);
+
+    set_now(9_500);
+    let cancel = Instruction::CancelEvent {
+        event_creator: env.creator.key,
+        event_id: EVENT_ID,
+    };
+    env.creator_instruction(cancel).unwrap();
+    env.withdraw(&mut participant_account, &mut wallet, &mut [])
+        .unwrap();
+
+    let rent = Rent::default().minimum_balance(Participant::LEN);
+    assert_eq!(wallet.lamports, WALLET_LAMPORTS + rent);
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS);
+}
+
+#[test]
+fn withdrawing_promotes_the_waitlist_head_into_its_own_account() {
+    let mut env = Env::new(1);
+    let mut leaving = TestAccount::wallet();
+    let mut leaving_account = env.join(&mut leaving);
+    let mut waitlist = env.waitlist();
+
+    let mut waiting = TestAccount::wallet();
+    let mut waiting_account = env
+        .join_waitlist(&mut waitlist, &mut waiting, PRICE)
+        .unwrap();
+    assert_eq!(waiting.lamports, WALLET_LAMPORTS - PRICE);
+    assert!(waiting_account.participant().is_waiting());
+
+    // A queued account doesn't hold a seat it could withdraw
+    assert_eq!(
+        env.withdraw(&mut waiting_account, &mut waiting, &mut []),
+        custom(RentShareError::AlreadyOnWaitlist)
+    );
+
+    let rent = leaving_account.lamports;
+    env.withdraw(
+        &mut leaving_account,
+        &mut leaving,
+        &mut [&mut waitlist, &mut waiting_account],
+    )
+    .unwrap();
+
+    assert_eq!(leaving.lamports, WALLET_LAMPORTS + rent);
+    assert_eq!(leaving_account.lamports, 0);
+    let promoted = waiting_account.participant();
+    assert!(promoted.is_joined());
+    assert_eq!(promoted.participant, waiting.key);
+    assert_eq!(promoted.deposit, PRICE);
+    assert_eq!(Waitlist::try_from_slice(&waitlist.data).unwrap().len, 0);
+    assert_eq!(env.event.event().participant_count, 1);
+    assert_eq!(env.event.event().total_deposits, PRICE);
+}
+
+#[test]
+fn waitlisted_wallets_can_leave_with_their_deposit() {
+    let mut env = Env::new(1);
+    env.join(&mut TestAccount::wallet());
+    let mut waitlist = env.waitlist();
+    let mut waiting = TestAccount::wallet();
+
+    let mut underpaying = Instruction::JoinWaitlist {
+        event_id: EVENT_ID,
+        event_creator: env.creator.key,
+        tier: 0,
+        deposit: PRICE - 1,
+        allowlist_proof: vec![],
+    };
+    let mut unused_account = TestAccount::program_account(Participant::LEN);
+    assert_eq!(
+        process(
+            &underpaying,
+            &mut [
+                &mut env.event,
+                &mut waitlist,
+                &mut unused_account,
+                &mut waiting,
+                &mut env.system_program,
+            ],
+        ),
+        custom(RentShareError::DepositAmountMismatch)
+    );
+    if let Instruction::JoinWaitlist { deposit, .. } = &mut underpaying {
+        *deposit = 0;
+    }
+    assert_eq!(
+        process(
+            &underpaying,
+            &mut [
+                &mut env.event,
+                &mut waitlist,
+                &mut unused_account,
+                &mut waiting,
+                &mut env.system_program,
+            ],
+        ),
+        custom(RentShareError::DepositAmountMismatch)
+    );
+
+    let mut waiting_account = env
+        .join_waitlist(&mut waitlist, &mut waiting, PRICE)
+        .unwrap();
+    assert_eq!(
+        env.join_waitlist(&mut waitlist, &mut waiting, PRICE).err(),
+        custom(RentShareError::AlreadyOnWaitlist).err()
+    );
+
+    let leave = Instruction::LeaveWaitlist {
+        event_id: EVENT_ID,
+        event_creator: env.creator.key,
+    };
+    assert_eq!(
+        process(
+            &leave,
+            &mut [
+                &mut env.event,
+                &mut waitlist,
+                &mut unused_account,
+                &mut waiting,
+            ],
+        ),
+        custom(RentShareError::ParticipantMismatch)
+    );
+
+    let rent = waiting_account.lamports;
+    process(
+        &leave,
+        &mut [
+            &mut env.event,
+            &mut waitlist,
+            &mut waiting_account,
+            &mut waiting,
+        ],
+    )
+    .unwrap();
+    assert_eq!(waiting.lamports, WALLET_LAMPORTS + rent);
+    assert_eq!(waiting_account.lamports, 0);
+    assert_eq!(
+        waitlist.lamports,
+        Rent::default().minimum_balance(Waitlist::LEN)
+    );
+    assert_eq!(Waitlist::try_from_slice(&waitlist.data).unwrap().len, 0);
+}
+
+#[test]
+fn removed_participants_are_refunded() {
+    let mut env = Env::new(2);
+    let mut wallet = TestAccount::wallet();
+    let mut participant_account = env.join(&mut wallet);
+    let remove = Instruction::RemoveParticipant {
+        event_id: EVENT_ID,
+        event_creator: env.creator.key,
+    };
+
+    let rent = participant_account.lamports;
+    process(
+        &remove,
+        &mut [
+            &mut env.event,
+            &mut env.creator,
+            &mut participant_account,
+            &mut wallet,
+        ],
+    )
+    .unwrap();
+    assert_eq!(wallet.lamports, WALLET_LAMPORTS + rent);
+    assert_eq!(participant_account.lamports, 0);
+    assert_eq!(env.event.event().participant_count, 0);
+}
+
+#[test]
+fn transferred_seats_follow_the_event_transfer_policy() {
+    let mut env = Env::new(2);
+    let mut holder = TestAccount::wallet();
+    let mut friend = TestAccount::wallet();
+    let mut participant_account = env.join(&mut holder);
+    let transfer = Instruction::TransferParticipation {
+        event_id: EVENT_ID,
+        event_creator: env.creator.key,
+        allowlist_proof: vec![],
+    };
+    let set_policy = |env: &mut Env, transfer_policy| {
+        let instruction = Instruction::SetTransferPolicy {
+            event_id: EVENT_ID,
+            event_creator: env.creator.key,
+            transfer_policy,
+        };
+        env.creator_instruction(instruction).unwrap();
+    };
+
+    set_policy(&mut env, TransferPolicy::Disabled);
+    assert_eq!(
+        process(
+            &transfer,
+            &mut [
+                &mut env.event,
+                &mut participant_account,
+                &mut holder,
+                &mut friend
+            ],
+        ),
+        custom(RentShareError::TransfersDisabled)
+    );
+
+    set_policy(&mut env, TransferPolicy::RequiresCreator);
+    env.creator.is_signer = false;
+    assert_eq!(
+        process(
+            &transfer,
+            &mut [
+                &mut env.event,
+                &mut participant_account,
+                &mut holder,
+                &mut friend,
+                &mut env.creator,
+            ],
+        ),
+        Err(ProgramError::MissingRequiredSignature)
+    );
+    env.creator.is_signer = true;
+    process(
+        &transfer,
+        &mut [
+            &mut env.event,
+            &mut participant_account,
+            &mut holder,
+            &mut friend,
+            &mut env.creator,
+        ],
+    )
+    .unwrap();
+    assert_eq!(participant_account.participant().participant, friend.key);
+
+    // The deposit now belongs to the new holder
+    assert_eq!(
+        env.withdraw(&mut participant_account, &mut holder, &mut []),
+        custom(RentShareError::ParticipantMismatch)
+    );
+    let rent = participant_account.lamports;
+    env.withdraw(&mut participant_account, &mut friend, &mut [])
+        .unwrap();
+    assert_eq!(friend.lamports, WALLET_LAMPORTS + PRICE + rent);
+    assert_eq!(holder.lamports, WALLET_LAMPORTS - PRICE);
+}
+
+#[test]
+fn seats_of_permit_events_cannot_be_transferred() {
+    let signer = Pubkey::new_unique();
+    let mut env = Env::with(2, |instruction| {
+        if let Instruction::InitializeEvent { permit_signer, .. } = instruction {
+            *permit_signer = signer;
+        }
+    });
+    let mut holder = TestAccount::wallet();
+    let permit = JoinPermit {
+        event: env.event.key,
+        participant: holder.key,
+        expiry: 2_000,
+        nonce: 1,
+    };
+    let mut permit_sysvar = permit_transaction(&signer, &permit);
+    let mut nonce = permit_nonce_account(&env.event.key, 1);
+    let instruction = env.participation(0, PRICE);
+    let mut participant_account = env
+        .participate_with(
+            &mut holder,
+            &instruction,
+            &mut [&mut permit_sysvar, &mut nonce],
+        )
+        .unwrap();
+
+    let transfer = Instruction::TransferParticipation {
+        event_id: EVENT_ID,
+        event_creator: env.creator.key,
+        allowlist_proof: vec![],
+    };
+    assert_eq!(
+        process(
+            &transfer,
+            &mut [
+                &mut env.event,
+                &mut participant_account,
+                &mut holder,
+                &mut TestAccount::wallet(),
+            ],
+        ),
+        custom(RentShareError::TransfersDisabled)
+    );
+}
+
+/// Ticket mint of an event along with its authority and the token program
+struct Tickets {
+    mint: TestAccount,
+    authority: TestAccount,
+    token_program: TestAccount,
+}
+
+impl Tickets {
+    fn new(env: &mut Env) -> Self {
+        let mut tickets = Tickets {
+            mint: TestAccount::token_account(Pubkey::new_unique(), Mint::default()),
+            authority: TestAccount {
+                key: ticket::find_authority(&env.event.key, &PROGRAM_ID).0,
+                lamports: 0,
+                data: vec![],
+                owner: system_program::id(),
+                is_signer: false,
+            },
+            token_program: TestAccount {
+                key: spl_token::id(),
+                lamports: 1,
+                data: vec![],
+                owner: Pubkey::default(),
+                is_signer: false,
+            },
+        };
+        let instruction = Instruction::InitializeTicketMint {
+            event_id: EVENT_ID,
+            event_creator: env.creator.key,
+            freeze_tickets: true,
+        };
+        process(
+            &instruction,
+            &mut [
+                &mut env.event,
+                &mut tickets.mint,
+                &mut env.creator,
+                &mut TestAccount::rent_sysvar(),
+                &mut tickets.token_program,
+            ],
+        )
+        .unwrap();
+        tickets
+    }
+
+    fn account(&self, wallet: &TestAccount) -> TestAccount {
+        TestAccount::token_account(
+            Pubkey::new_unique(),
+            TokenAccount {
+                mint: self.mint.key,
+                owner: wallet.key,
+                state: AccountState::Initialized,
+                ..TokenAccount::default()
+            },
+        )
+    }
+
+    fn join(
+        &mut self,
+        env: &mut Env,
+        wallet: &mut TestAccount,
+        token_account: &mut TestAccount,
+    ) -> TestAccount {
+        let instruction = env.participation(0, PRICE);
+        env.participate_with(
+            wallet,
+            &instruction,
+            &mut [
+                &mut self.mint,
+                token_account,
+                &mut self.authority,
+                &mut self.token_program,
+            ],
+        )
+        .unwrap()
+    }
+
+    fn withdraw(
+        &mut self,
+        env: &mut Env,
+        participant_account: &mut TestAccount,
+        wallet: &mut TestAccount,
+        token_account: &mut TestAccount,
+    ) -> ProgramResult {
+        let instruction = Instruction::WithdrawFromEvent {
+            event_id: EVENT_ID,
+            event_creator: env.creator.key,
+        };
+        process(
+            &instruction,
+            &mut [
+                &mut env.event,
+                participant_account,
+                wallet,
+                &mut self.mint,
+                token_account,
+                &mut self.authority,
+                &mut self.token_program,
+            ],
+        )
+    }
+
+    fn supply(&self) -> u64 {
+        Mint::unpack(&self.mint.data).unwrap().supply
+    }
+}
+
+#[test]
+fn tickets_are_minted_on_join_and_burned_or_frozen_when_the_seat_is_released() {
+    let mut env = Env::new(3);
+    let mut tickets = Tickets::new(&mut env);
+    let mut leaving = TestAccount::wallet();
+    let mut removed = TestAccount::wallet();
+    let mut leaving_tokens = tickets.account(&leaving);
+    let mut removed_tokens = tickets.account(&removed);
+
+    let mut leaving_account = tickets.join(&mut env, &mut leaving, &mut leaving_tokens);
+    let mut removed_account = tickets.join(&mut env, &mut removed, &mut removed_tokens);
+    assert_eq!(
+        leaving_account.participant().ticket_account,
+        leaving_tokens.key
+    );
+    assert_eq!(leaving_tokens.token().amount, 1);
+    assert_eq!(tickets.supply(), 2);
+
+    // A ticket moved to another wallet has to come back before the seat is given up
+    let mut held = leaving_tokens.token();
+    held.amount = 0;
+    held.pack_into_slice(&mut leaving_tokens.data);
+    assert_eq!(
+        tickets.withdraw(
+            &mut env,
+            &mut leaving_account,
+            &mut leaving,
+            &mut leaving_tokens
+        ),
+        custom(RentShareError::TicketNotHeld)
+    );
+    assert_eq!(
+        tickets.withdraw(
+            &mut env,
+            &mut leaving_account,
+            &mut leaving,
+            &mut removed_tokens
+        ),
+        custom(RentShareError::InvalidTicketAccount)
+    );
+
+    held.amount = 1;
+    held.pack_into_slice(&mut leaving_tokens.data);
+    tickets
+        .withdraw(
+            &mut env,
+            &mut leaving_account,
+            &mut leaving,
+            &mut leaving_tokens,
+        )
+        .unwrap();
+    assert_eq!(leaving_tokens.token().amount, 0);
+    assert_eq!(tickets.supply(), 1);
+
+    // The creator can't burn the ticket of a removed participant, so it is frozen
+    let remove = Instruction::RemoveParticipant {
+        event_id: EVENT_ID,
+        event_creator: env.creator.key,
+    };
+    process(
+        &remove,
+        &mut [
+            &mut env.event,
+            &mut env.creator,
+            &mut removed_account,
+            &mut removed,
+            &mut tickets.mint,
+            &mut removed_tokens,
+            &mut tickets.authority,
+            &mut tickets.token_program,
+        ],
+    )
+    .unwrap();
+    assert_eq!(removed_tokens.token().state, AccountState::Frozen);
+    assert_eq!(env.event.event().participant_count, 0);
+}
+
+fn occurrence_account(series: &Pubkey, index: u64) -> TestAccount {
+    TestAccount {
+        key: series::find_occurrence_address(series, index, &PROGRAM_ID).0,
+        lamports: 0,
+        data: vec![0; InitEvent::LEN],
+        owner: system_program::id(),
+        is_signer: false,
+    }
+}
+
+#[test]
+fn series_occurrences_are_instantiated_by_organizers_at_their_pdas() {
+    install_stubs();
+    let mut series = TestAccount::program_account(EventSeries::LEN);
+    let mut creator = TestAccount::wallet();
+    let mut organizer = TestAccount::wallet();
+    let mut outsider = TestAccount::wallet();
+    let mut system_program = TestAccount::system_program();
+    let payee = Pubkey::new_unique();
+    process(
+        &Instruction::InitializeEventSeries {
+            series_creator: creator.key,
+            first_event_id: 10,
+            payee_pubkey: payee,
+            fix_deposit_amount_per_person: PRICE,
+            total_partcipator: 5,
+            organizers: vec![organizer.key],
+            first_start: 5_000,
+            interval: 604_800,
+            max_occurrences: 2,
+        },
+        &mut [&mut series, &mut creator, &mut TestAccount::rent_sysvar()],
+    )
+    .unwrap();
+
+    let instantiate = Instruction::InstantiateOccurrence {
+        series_creator: creator.key,
+        first_event_id: 10,
+    };
+    let mut first = occurrence_account(&series.key, 0);
+    assert_eq!(
+        process(
+            &instantiate,
+            &mut [&mut series, &mut first, &mut outsider, &mut system_program]
+        )
+        .err(),
+        custom(RentShareError::NotSeriesOrganizer).err()
+    );
+
+    // Lamports sent to the occurrence address don't block its creation
+    first.lamports = 1;
+    process(
+        &instantiate,
+        &mut [&mut series, &mut first, &mut organizer, &mut system_program],
+    )
+    .unwrap();
+    assert_eq!(first.owner, PROGRAM_ID);
+    assert_eq!(
+        first.lamports,
+        Rent::default().minimum_balance(InitEvent::LEN)
+    );
+    let event = first.event();
+    assert_eq!(event.event_id, 10);
+    assert_eq!(event.event_creator, organizer.key);
+    assert_eq!(event.payee_pubkey, payee);
+    assert_eq!(event.series, series.key);
+    assert_eq!(event.start_time, 5_000);
+    assert_eq!(event.tier(0).unwrap().price, PRICE);
+    assert_eq!(event.tier(0).unwrap().capacity, 5);
+
+    let mut misplaced = TestAccount {
+        key: Pubkey::new_unique(),
+        ..occurrence_account(&series.key, 1)
+    };
+    assert_eq!(
+        process(
+            &instantiate,
+            &mut [
+                &mut series,
+                &mut misplaced,
+                &mut creator,
+                &mut system_program
+            ]
+        ),
+        Err(ProgramError::InvalidSeeds)
+    );
+
+    let mut second = occurrence_account(&series.key, 1);
+    process(
+        &instantiate,
+        &mut [&mut series, &mut second, &mut creator, &mut system_program],
+    )
+    .unwrap();
+    assert_eq!(second.event().event_id, 11);
+    assert_eq!(second.event().start_time, 5_000 + 604_800);
+    assert_eq!(second.event().event_creator, creator.key);
+
+    let mut third = occurrence_account(&series.key, 2);
+    assert_eq!(
+        process(
+            &instantiate,
+            &mut [&mut series, &mut third, &mut creator, &mut system_program]
+        )
+        .err(),
+        custom(RentShareError::EventSeriesFinished).err()
+    );
+}
+
+fn dispute_window(arbiter_key: Pubkey) -> impl FnOnce(&mut Instruction) {
+    move |instruction| {
+        if let Instruction::InitializeEvent {
+            arbiter,
+            dispute_window,
+            ..
+        } = instruction
+        {
+            *arbiter = arbiter_key;
+            *dispute_window = 500;
+        }
+    }
+}
+
+#[test]
+fn payouts_wait_for_the_dispute_window_to_close() {
+    let mut env = Env::with(2, dispute_window(Pubkey::new_unique()));
+    let mut wallet = TestAccount::wallet();
+    env.join(&mut wallet);
+    env.start().unwrap();
+    env.end().unwrap();
+    assert!(env.event.event().is_ended());
+    assert_eq!(env.event.event().dispute_deadline, 1_500);
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS);
+
+    assert_eq!(env.settle(), custom(RentShareError::DisputeWindowOpen));
+    set_now(1_501);
+    env.settle().unwrap();
+    assert!(env.event.event().is_complete());
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS + PRICE);
+}
+
+#[test]
+fn arbiters_resolve_disputes_with_a_refund_for_every_seat() {
+    let mut arbiter = TestAccount::wallet();
+    let mut env = Env::with(2, dispute_window(arbiter.key));
+    let mut first = TestAccount::wallet();
+    let mut second = TestAccount::wallet();
+    let mut first_account = env.join(&mut first);
+    let mut second_account = env.join(&mut second);
+    env.start().unwrap();
+    env.end().unwrap();
+
+    let creator = env.creator.key;
+    let dispute = || Instruction::OpenDispute {
+        event_id: EVENT_ID,
+        event_creator: creator,
+    };
+    env.participant_instruction(dispute(), &mut first_account, &mut first)
+        .unwrap();
+    assert_eq!(
+        env.participant_instruction(dispute(), &mut second_account, &mut second),
+        custom(RentShareError::DisputeAlreadyOpened)
+    );
+
+    set_now(1_501);
+    assert_eq!(env.settle(), custom(RentShareError::DisputeUnresolved));
+
+    let resolve = |refund_bps| Instruction::ResolveDispute {
+        event_id: EVENT_ID,
+        event_creator: creator,
+        refund_bps,
+    };
+    assert_eq!(
+        env.creator_instruction(resolve(5_000)),
+        custom(RentShareError::ArbiterMismatch)
+    );
+    process(&resolve(5_000), &mut [&mut env.event, &mut arbiter]).unwrap();
+    env.settle().unwrap();
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS + PRICE);
+
+    let claim = || Instruction::ClaimDisputeRefund {
+        event_id: EVENT_ID,
+        event_creator: creator,
+    };
+    env.participant_instruction(claim(), &mut first_account, &mut first)
+        .unwrap();
+    assert_eq!(
+        env.participant_instruction(claim(), &mut first_account, &mut first),
+        custom(RentShareError::NothingToClaim)
+    );
+    env.participant_instruction(claim(), &mut second_account, &mut second)
+        .unwrap();
+    assert_eq!(first.lamports, WALLET_LAMPORTS - PRICE / 2);
+    assert_eq!(second.lamports, WALLET_LAMPORTS - PRICE / 2);
+    assert_eq!(env.event.event().total_deposits, 0);
+    assert_eq!(
+        env.event.lamports,
+        Rent::default().minimum_balance(InitEvent::LEN)
+    );
+}
+
+#[test]
+fn disputes_can_only_be_opened_while_the_window_is_open() {
+    let mut env = Env::with(2, dispute_window(Pubkey::new_unique()));
+    let mut wallet = TestAccount::wallet();
+    let mut participant_account = env.join(&mut wallet);
+    let creator = env.creator.key;
+    let dispute = || Instruction::OpenDispute {
+        event_id: EVENT_ID,
+        event_creator: creator,
+    };
+    env.start().unwrap();
+    assert_eq!(
+        env.participant_instruction(dispute(), &mut participant_account, &mut wallet),
+        custom(RentShareError::DisputeWindowClosed)
+    );
+
+    env.end().unwrap();
+    set_now(1_501);
+    assert_eq!(
+        env.participant_instruction(dispute(), &mut participant_account, &mut wallet),
+        custom(RentShareError::DisputeWindowClosed)
+    );
+}
+
+fn confirmation_quorum(configure: &mut Instruction) {
+    if let Instruction::InitializeEvent {
+        confirmation_quorum_bps,
+        confirmation_window,
+        ..
+    } = configure
+    {
+        *confirmation_quorum_bps = 5_000;
+        *confirmation_window = 500;
+    }
+}
+
+#[test]
+fn payouts_wait_for_the_confirmation_quorum() {
+    let mut env = Env::with(3, confirmation_quorum);
+    let mut first = TestAccount::wallet();
+    let mut second = TestAccount::wallet();
+    let mut first_account = env.join(&mut first);
+    env.join(&mut second);
+    let creator = env.creator.key;
+    let confirm = || Instruction::ConfirmEvent {
+        event_id: EVENT_ID,
+        event_creator: creator,
+    };
+
+    env.start().unwrap();
+    assert_eq!(
+        env.participant_instruction(confirm(), &mut first_account, &mut first),
+        custom(RentShareError::ConfirmationClosed)
+    );
+    env.end().unwrap();
+    assert_eq!(env.settle(), custom(RentShareError::ConfirmationPending));
+
+    env.participant_instruction(confirm(), &mut first_account, &mut first)
+        .unwrap();
+    assert_eq!(
+        env.participant_instruction(confirm(), &mut first_account, &mut first),
+        custom(RentShareError::AlreadyConfirmed)
+    );
+    assert_eq!(env.event.event().confirmations, 1);
+
+    env.settle().unwrap();
+    assert!(env.event.event().is_complete());
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS + 2 * PRICE);
+}
+
+#[test]
+fn events_missing_their_confirmation_quorum_are_refunded() {
+    let mut env = Env::with(3, |instruction| {
+        confirmation_quorum(instruction);
+        bond(PRICE)(instruction);
+    });
+    let mut wallet = TestAccount::wallet();
+    let mut participant_account = env.join(&mut wallet);
+    env.start().unwrap();
+    env.end().unwrap();
+
+    set_now(1_501);
+    env.settle().unwrap();
+    assert!(env.event.event().is_terminated());
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS);
+
+    env.withdraw(&mut participant_account, &mut wallet, &mut [])
+        .unwrap();
+    let rent = Rent::default().minimum_balance(Participant::LEN);
+    assert_eq!(wallet.lamports, WALLET_LAMPORTS + rent + PRICE);
+    assert_eq!(
+        env.event.lamports,
+        Rent::default().minimum_balance(InitEvent::LEN)
+    );
+}
+
+/// Split the payout 70/30 between the event payee and `second`
+fn split_with(second: Pubkey) -> impl FnOnce(&mut Instruction) {
+    move |instruction| {
+        if let Instruction::InitializeEvent {
+            payee_pubkey,
+            payees,
+            ..
+        } = instruction
+        {
+            *payees = vec![
+                PayeeShare {
+                    payee: *payee_pubkey,
+                    share_bps: 7_000,
+                },
+                PayeeShare {
+                    payee: second,
+                    share_bps: 3_000,
+                },
+            ];
+        }
+    }
+}
+
+#[test]
+fn payee_splits_are_validated() {
+    let second = Pubkey::new_unique();
+    let invalid: [fn(&mut Vec<PayeeShare>); 3] = [
+        |payees| payees[1].share_bps = 2_999,
+        |payees| payees.swap(0, 1),
+        |payees| payees.extend(vec![PayeeShare::default(); 3]),
+    ];
+    for break_split in invalid {
+        let result = Env::try_with(1, |instruction| {
+            split_with(second)(instruction);
+            if let Instruction::InitializeEvent { payees, .. } = instruction {
+                break_split(payees);
+            }
+        });
+        assert_eq!(
+            result.err(),
+            custom(RentShareError::InvalidPayeeSplit).err()
+        );
+    }
+}
+
+#[test]
+fn payouts_are_split_across_the_payees() {
+    let mut second = TestAccount::wallet();
+    let mut env = Env::with(3, split_with(second.key));
+    for _ in 0..3 {
+        env.join(&mut TestAccount::wallet());
+    }
+    env.start().unwrap();
+
+    let end = Instruction::EndEvent {
+        event_id: EVENT_ID,
+        event_creator: env.creator.key,
+    };
+    process(
+        &end,
+        &mut [
+            &mut env.event,
+            &mut env.creator,
+            &mut env.payee,
+            &mut second,
+        ],
+    )
+    .unwrap();
+
+    let payout = 3 * PRICE;
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS + payout * 7 / 10);
+    assert_eq!(second.lamports, WALLET_LAMPORTS + payout * 3 / 10);
+}
+
+#[test]
+fn retained_refunds_are_split_across_the_payees() {
+    let mut second = TestAccount::wallet();
+    let mut env = Env::with(1, |instruction| {
+        refund_schedule(instruction);
+        split_with(second.key)(instruction);
+    });
+    let mut wallet = TestAccount::wallet();
+    let mut participant_account = env.join(&mut wallet);
+
+    let withdraw = Instruction::WithdrawFromEvent {
+        event_id: EVENT_ID,
+        event_creator: env.creator.key,
+    };
+    set_now(9_500);
+    process(
+        &withdraw,
+        &mut [
+            &mut env.event,
+            &mut participant_account,
+            &mut wallet,
+            &mut env.payee,
+            &mut second,
+        ],
+    )
+    .unwrap();
+
+    let rent = Rent::default().minimum_balance(Participant::LEN);
+    assert_eq!(wallet.lamports, WALLET_LAMPORTS - PRICE + rent);
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS + PRICE * 7 / 10);
+    assert_eq!(second.lamports, WALLET_LAMPORTS + PRICE * 3 / 10);
+}
+
+fn vesting(kind: Vesting, duration: i64) -> impl FnOnce(&mut Instruction) {
+    move |instruction| {
+        if let Instruction::InitializeEvent {
+            vesting,
+            vesting_duration,
+            ..
+        } = instruction
+        {
+            *vesting = kind;
+            *vesting_duration = duration;
+        }
+    }
+}
+
+#[test]
+fn vested_deposits_are_claimed_until_the_event_can_end() {
+    assert_eq!(
+        Env::try_with(1, vesting(Vesting::Linear, 0)).err(),
+        Some(ProgramError::InvalidArgument)
+    );
+
+    let mut env = Env::with(2, vesting(Vesting::Linear, 100));
+    env.join(&mut TestAccount::wallet());
+    env.join(&mut TestAccount::wallet());
+    assert_eq!(
+        env.claim_vested(),
+        custom(RentShareError::InvalidEventStatus)
+    );
+
+    env.start().unwrap();
+    assert_eq!(env.claim_vested(), custom(RentShareError::NothingToClaim));
+
+    set_now(1_025);
+    env.claim_vested().unwrap();
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS + PRICE / 2);
+    assert_eq!(env.end(), custom(RentShareError::VestingIncomplete));
+
+    set_now(1_100);
+    env.claim_vested().unwrap();
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS + 2 * PRICE);
+    assert_eq!(env.event.event().total_deposits, 0);
+    env.end().unwrap();
+}
+
+#[test]
+fn cancelled_vesting_events_refund_the_unvested_deposits() {
+    let mut env = Env::with(3, vesting(Vesting::Linear, 300));
+    let mut wallets = vec![
+        TestAccount::wallet(),
+        TestAccount::wallet(),
+        TestAccount::wallet(),
+    ];
+    let mut participant_accounts: Vec<TestAccount> =
+        wallets.iter_mut().map(|wallet| env.join(wallet)).collect();
+    env.start().unwrap();
+
+    set_now(1_001);
+    env.claim_vested().unwrap();
+    let vested = 3 * PRICE / 300;
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS + vested);
+
+    let cancel = Instruction::CancelEvent {
+        event_creator: env.creator.key,
+        event_id: EVENT_ID,
+    };
+    env.creator_instruction(cancel).unwrap();
+
+    let rent = Rent::default().minimum_balance(Participant::LEN);
+    for (participant_account, wallet) in participant_accounts.iter_mut().zip(&mut wallets).take(2) {
+        env.withdraw(participant_account, wallet, &mut []).unwrap();
+    }
+
+    // Each refund rounds down, the last seat pays the leftover to the payee
+    let withdraw = Instruction::WithdrawFromEvent {
+        event_id: EVENT_ID,
+        event_creator: env.creator.key,
+    };
+    process(
+        &withdraw,
+        &mut [
+            &mut env.event,
+            &mut participant_accounts[2],
+            &mut wallets[2],
+            &mut env.payee,
+        ],
+    )
+    .unwrap();
+
+    let refund = PRICE * (3 * PRICE - vested) / (3 * PRICE);
+    for wallet in &wallets {
+        assert_eq!(wallet.lamports, WALLET_LAMPORTS - PRICE + rent + refund);
+    }
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS + 3 * PRICE - 3 * refund);
+    assert_eq!(env.event.event().total_deposits, 0);
+    assert_eq!(
+        env.event.lamports,
+        Rent::default().minimum_balance(InitEvent::LEN)
+    );
+}
+
+#[test]
+fn group_registrations_escrow_every_seat_from_the_payer() {
+    let mut env = Env::new(3);
+    let mut payer = TestAccount::wallet();
+    let mut wallets = vec![TestAccount::wallet(), TestAccount::wallet()];
+    let participant_accounts = env.join_group(&mut payer, &mut wallets).unwrap();
+
+    assert_eq!(payer.lamports, WALLET_LAMPORTS - 2 * PRICE);
+    for (participant_account, wallet) in participant_accounts.iter().zip(&wallets) {
+        let participant = participant_account.participant();
+        assert!(participant.is_joined());
+        assert_eq!(participant.participant, wallet.key);
+        assert_eq!(participant.payer, payer.key);
+        assert_eq!(wallet.lamports, WALLET_LAMPORTS);
+    }
+    let event = env.event.event();
+    assert_eq!(event.participant_count, 2);
+    assert_eq!(event.total_deposits, 2 * PRICE);
+
+    // The group is registered entirely or not at all
+    let mut wallets = vec![TestAccount::wallet(), TestAccount::wallet()];
+    assert_eq!(
+        env.join_group(&mut payer, &mut wallets).err(),
+        custom(RentShareError::EventFull).err()
+    );
+    assert_eq!(
+        env.join_group(&mut payer, &mut []).err(),
+        Some(ProgramError::NotEnoughAccountKeys)
+    );
+    assert_eq!(env.event.event().participant_count, 2);
+}
+
+#[test]
+fn refunds_of_group_seats_go_back_to_the_payer() {
+    let mut env = Env::with(2, bond(PRICE));
+    let mut payer = TestAccount::wallet();
+    let mut wallets = vec![TestAccount::wallet(), TestAccount::wallet()];
+    let mut participant_accounts = env.join_group(&mut payer, &mut wallets).unwrap();
+
+    let cancel = Instruction::CancelEvent {
+        event_creator: env.creator.key,
+        event_id: EVENT_ID,
+    };
+    env.creator_instruction(cancel).unwrap();
+
+    let mut stranger = TestAccount::wallet();
+    assert_eq!(
+        env.withdraw(
+            &mut participant_accounts[0],
+            &mut wallets[0],
+            &mut [&mut stranger]
+        ),
+        custom(RentShareError::ParticipantMismatch)
+    );
+
+    let rent = participant_accounts[0].lamports;
+    env.withdraw(
+        &mut participant_accounts[0],
+        &mut wallets[0],
+        &mut [&mut payer],
+    )
+    .unwrap();
+    assert_eq!(
+        payer.lamports,
+        WALLET_LAMPORTS - 2 * PRICE + PRICE + PRICE / 2 + rent
+    );
+    assert_eq!(wallets[0].lamports, WALLET_LAMPORTS);
+}
+
+#[test]
+fn group_seats_are_transferred_with_the_payer_signature() {
+    let mut env = Env::new(1);
+    let mut payer = TestAccount::wallet();
+    let mut wallets = vec![TestAccount::wallet()];
+    let mut participant_accounts = env.join_group(&mut payer, &mut wallets).unwrap();
+    let mut friend = TestAccount::wallet();
+    let transfer = Instruction::TransferParticipation {
+        event_id: EVENT_ID,
+        event_creator: env.creator.key,
+        allowlist_proof: vec![],
+    };
+
+    assert_eq!(
+        process(
+            &transfer,
+            &mut [
+                &mut env.event,
+                &mut participant_accounts[0],
+                &mut wallets[0],
+                &mut friend,
+            ],
+        ),
+        Err(ProgramError::NotEnoughAccountKeys)
+    );
+    payer.is_signer = false;
+    assert_eq!(
+        process(
+            &transfer,
+            &mut [
+                &mut env.event,
+                &mut participant_accounts[0],
+                &mut wallets[0],
+                &mut friend,
+                &mut payer,
+            ],
+        ),
+        Err(ProgramError::MissingRequiredSignature)
+    );
+
+    payer.is_signer = true;
+    process(
+        &transfer,
+        &mut [
+            &mut env.event,
+            &mut participant_accounts[0],
+            &mut wallets[0],
+            &mut friend,
+            &mut payer,
+        ],
+    )
+    .unwrap();
+    let participant = participant_accounts[0].participant();
+    assert_eq!(participant.participant, friend.key);
+    assert_eq!(participant.payer, Pubkey::default());
+
+    // The payer handed the refund over to the new holder
+    let rent = participant_accounts[0].lamports;
+    env.withdraw(&mut participant_accounts[0], &mut friend, &mut [])
+        .unwrap();
+    assert_eq!(friend.lamports, WALLET_LAMPORTS + PRICE + rent);
+    assert_eq!(payer.lamports, WALLET_LAMPORTS - PRICE);
+}
+
+#[test]
+fn sponsor_pools_are_paid_out_with_the_deposits() {
+    let mut env = Env::new(2);
+    env.join(&mut TestAccount::wallet());
+    let mut sponsor = TestAccount::wallet();
+    let sponsorship = env.sponsor(&mut sponsor, PRICE);
+    env.sponsor(&mut sponsor, PRICE);
+
+    assert_eq!(sponsor.lamports, WALLET_LAMPORTS - 2 * PRICE);
+    assert_eq!(env.event.event().sponsor_pool, 2 * PRICE);
+    assert_eq!(env.event.event().total_deposits, PRICE);
+    let contributions = Sponsorship::try_from_slice(&sponsorship.data).unwrap();
+    assert_eq!(contributions.sponsor, sponsor.key);
+    assert_eq!(contributions.amount, PRICE);
+
+    env.start().unwrap();
+    env.end().unwrap();
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS + 3 * PRICE);
+    assert_eq!(env.event.event().sponsor_pool, 0);
+}
+
+#[test]
+fn sponsor_pools_can_go_to_the_attendees() {
+    let mut env = Env::new(2);
+    let mut wallets = [TestAccount::wallet(), TestAccount::wallet()];
+    let mut participant_accounts: Vec<TestAccount> =
+        wallets.iter_mut().map(|wallet| env.join(wallet)).collect();
+    env.sponsor(&mut TestAccount::wallet(), PRICE + 1);
+
+    let set_destination = Instruction::SetSponsorPoolDestination {
+        event_id: EVENT_ID,
+        event_creator: env.creator.key,
+        destination: SponsorPoolDestination::Attendees,
+    };
+    env.creator_instruction(set_destination).unwrap();
+    env.start().unwrap();
+    env.end().unwrap();
+
+    // The payee receives the deposits and what doesn't divide among the seats
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS + 2 * PRICE + 1);
+    assert_eq!(env.event.event().sponsor_share, PRICE / 2);
+
+    let event_creator = env.creator.key;
+    let claim = || Instruction::ClaimSponsorShare {
+        event_id: EVENT_ID,
+        event_creator,
+    };
+    env.participant_instruction(claim(), &mut participant_accounts[0], &mut wallets[0])
+        .unwrap();
+    assert_eq!(wallets[0].lamports, WALLET_LAMPORTS - PRICE + PRICE / 2);
+    assert_eq!(
+        env.participant_instruction(claim(), &mut participant_accounts[0], &mut wallets[0]),
+        custom(RentShareError::NothingToClaim)
+    );
+}
+
+#[test]
+fn sponsors_of_cancelled_events_are_refunded() {
+    let mut env = Env::new(1);
+    let mut sponsor = TestAccount::wallet();
+    let mut sponsorship = env.sponsor(&mut sponsor, PRICE);
+    let event_creator = env.creator.key;
+    let withdraw = || Instruction::WithdrawSponsorship {
+        event_id: EVENT_ID,
+        event_creator,
+    };
+    assert_eq!(
+        process(
+            &withdraw(),
+            &mut [&mut env.event, &mut sponsorship, &mut sponsor]
+        ),
+        custom(RentShareError::InvalidEventStatus)
+    );
+
+    let cancel = Instruction::CancelEvent {
+        event_creator: env.creator.key,
+        event_id: EVENT_ID,
+    };
+    env.creator_instruction(cancel).unwrap();
+
+    let mut stranger = TestAccount::wallet();
+    assert_eq!(
+        process(
+            &withdraw(),
+            &mut [&mut env.event, &mut sponsorship, &mut stranger]
+        ),
+        custom(RentShareError::SponsorshipMismatch)
+    );
+    let rent = sponsorship.lamports;
+    process(
+        &withdraw(),
+        &mut [&mut env.event, &mut sponsorship, &mut sponsor],
+    )
+    .unwrap();
+    assert_eq!(sponsor.lamports, WALLET_LAMPORTS + rent);
+    assert_eq!(env.event.event().sponsor_pool, 0);
+}
+
+/// Award the sponsor pool 70/30 to the first two ranks, with results due within 100 seconds
+fn prizes(instruction: &mut Instruction) {
+    if let Instruction::InitializeEvent {
+        prize_table,
+        results_window,
+        ..
+    } = instruction
+    {
+        *prize_table = vec![7_000, 3_000];
+        *results_window = 100;
+    }
+}
+
+#[test]
+fn prize_tables_are_validated() {
+    let invalid: [fn(&mut Instruction); 2] = [
+        |instruction| {
+            if let Instruction::InitializeEvent { prize_table, .. } = instruction {
+                prize_table[1] = 2_999;
+            }
+        },
+        |instruction| {
+            if let Instruction::InitializeEvent { results_window, .. } = instruction {
+                *results_window = 0;
+            }
+        },
+    ];
+    let expected = [
+        custom(RentShareError::InvalidPrizeTable).err(),
+        Some(ProgramError::InvalidArgument),
+    ];
+    for (break_prizes, expected) in invalid.iter().zip(expected) {
+        let result = Env::try_with(1, |instruction| {
+            prizes(instruction);
+            break_prizes(instruction);
+        });
+        assert_eq!(result.err(), expected);
+    }
+}
+
+#[test]
+fn competition_prizes_are_claimed_by_rank() {
+    let mut env = Env::with(3, prizes);
+    let mut wallets = [
+        TestAccount::wallet(),
+        TestAccount::wallet(),
+        TestAccount::wallet(),
+    ];
+    let mut participant_accounts: Vec<TestAccount> =
+        wallets.iter_mut().map(|wallet| env.join(wallet)).collect();
+    env.sponsor(&mut TestAccount::wallet(), PRICE);
+    env.start().unwrap();
+    env.end().unwrap();
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS + 3 * PRICE);
+    assert_eq!(env.event.event().prize_pool, PRICE);
+
+    let submit = Instruction::SubmitResults {
+        event_id: EVENT_ID,
+        event_creator: env.creator.key,
+    };
+    let (winner, rest) = participant_accounts.split_first_mut().unwrap();
+    process(
+        &submit,
+        &mut [
+            &mut env.event,
+            &mut env.creator,
+            &mut env.payee,
+            winner,
+            &mut rest[0],
+        ],
+    )
+    .unwrap();
+    assert_eq!(
+        process(
+            &submit,
+            &mut [&mut env.event, &mut env.creator, &mut env.payee],
+        ),
+        custom(RentShareError::ResultsAlreadySubmitted)
+    );
+
+    let event_creator = env.creator.key;
+    let claim = || Instruction::ClaimPrize {
+        event_id: EVENT_ID,
+        event_creator,
+    };
+    for (participant_account, wallet) in participant_accounts.iter_mut().zip(&mut wallets).take(2) {
+        env.participant_instruction(claim(), participant_account, wallet)
+            .unwrap();
+    }
+    assert_eq!(
+        env.participant_instruction(claim(), &mut participant_accounts[2], &mut wallets[2]),
+        custom(RentShareError::NothingToClaim)
+    );
+    assert_eq!(
+        wallets[0].lamports,
+        WALLET_LAMPORTS - PRICE + PRICE * 7 / 10
+    );
+    assert_eq!(
+        wallets[1].lamports,
+        WALLET_LAMPORTS - PRICE + PRICE * 3 / 10
+    );
+    assert_eq!(env.event.event().sponsor_pool, 0);
+}
+
+#[test]
+fn sponsors_are_refunded_when_the_results_miss_their_deadline() {
+    let mut env = Env::with(1, prizes);
+    let mut participant_account = env.join(&mut TestAccount::wallet());
+    let mut sponsor = TestAccount::wallet();
+    let mut sponsorship = env.sponsor(&mut sponsor, PRICE);
+    env.start().unwrap();
+    env.end().unwrap();
+    assert_eq!(env.event.event().results_deadline, 1_100);
+
+    let event_creator = env.creator.key;
+    let withdraw = || Instruction::WithdrawSponsorship {
+        event_id: EVENT_ID,
+        event_creator,
+    };
+    assert_eq!(
+        process(
+            &withdraw(),
+            &mut [&mut env.event, &mut sponsorship, &mut sponsor]
+        ),
+        custom(RentShareError::InvalidEventStatus)
+    );
+
+    set_now(1_101);
+    let submit = Instruction::SubmitResults {
+        event_id: EVENT_ID,
+        event_creator,
+    };
+    assert_eq!(
+        process(
+            &submit,
+            &mut [
+                &mut env.event,
+                &mut env.creator,
+                &mut env.payee,
+                &mut participant_account,
+            ],
+        ),
+        custom(RentShareError::ResultsDeadlinePassed)
+    );
+
+    let rent = sponsorship.lamports;
+    process(
+        &withdraw(),
+        &mut [&mut env.event, &mut sponsorship, &mut sponsor],
+    )
+    .unwrap();
+    assert_eq!(sponsor.lamports, WALLET_LAMPORTS + rent);
+    assert_eq!(env.payee.lamports, WALLET_LAMPORTS + PRICE);
+    assert_eq!(env.event.event().sponsor_pool, 0);
+}
+
+/// Require two seats' deposits by the registration deadline at 2000
+fn funding_goal(instruction: &mut Instruction) {
+    if let Instruction::InitializeEvent {
+        funding_goal,
+        registration_deadline,
+        ..
+    } = instruction
+    {
+        *funding_goal = 2 * PRICE;
+        *registration_deadline = 2_000;
+    }
+}
+
+#[test]
+fn funded_events_start_once_registration_closes() {
+    let mut env = Env::with(3, funding_goal);
+    let mut wallet = TestAccount::wallet();
+    let mut participant_account = env.join(&mut wallet);
+    env.join(&mut TestAccount::wallet());
+    assert_eq!(env.start(), custom(RentShareError::FundingOpen));
+
+    set_now(2_001);
+    assert_eq!(
+        env.join_tier(&mut TestAccount::wallet(), 0, PRICE).err(),
+        custom(RentShareError::EventRegistrationClosed).err()
+    );
+    assert_eq!(
+        env.withdraw(&mut participant_account, &mut wallet, &mut []),
+        custom(RentShareError::InvalidEventStatus)
+    );
+    env.start().unwrap();
+}
+
+#[test]
+fn unfunded_events_refund_every_deposit_in_full() {
+    let mut env = Env::with(3, |instruction| {
+        funding_goal(instruction);
+        refund_schedule(instruction);
+        bond(PRICE)(instruction);
+    });
+    let mut wallet = TestAccount::wallet();
+    let mut participant_account = env.join(&mut wallet);
+
+    set_now(9_500);
+    assert_eq!(env.start(), custom(RentShareError::FundingGoalNotMet));
+
+    // The refund schedule would keep the whole deposit by now, but it doesn't apply
+    env.withdraw(&mut participant_account, &mut wallet, &mut [])
+        .unwrap();
+    let rent = Rent::default().minimum_balance(Participant::LEN);
+    assert_eq!(wallet.lamports, WALLET_LAMPORTS + rent);
+
+    // Nor is the creator to blame, the bond goes back in full
+    let cancel = Instruction::CancelEvent {
+        event_creator: env.creator.key,
+        event_id: EVENT_ID,
+    };
+    env.creator_instruction(cancel).unwrap();
+    assert_eq!(env.creator.lamports, WALLET_LAMPORTS);
+}
+
+/// Let participants choose their deposit between half and twice the price
+fn flexible_pricing(instruction: &mut Instruction) {
+    if let Instruction::InitializeEvent {
+        min_deposit,
+        max_deposit,
+        ..
+    } = instruction
+    {
+        *min_deposit = PRICE / 2;
+        *max_deposit = 2 * PRICE;
+    }
+}
+
+#[test]
+fn flexible_deposits_are_checked_against_the_bounds() {
+    let mut env = Env::with(3, flexible_pricing);
+    for amount in [PRICE / 2 - 1, 2 * PRICE + 1] {
+        assert_eq!(
+            env.join_tier(&mut TestAccount::wallet(), 0, amount).err(),
+            custom(RentShareError::DepositAmountMismatch).err()
+        );
+    }
+
+    let mut wallet = TestAccount::wallet();
+    let participant_account = env.join_tier(&mut wallet, 0, PRICE / 2).unwrap();
+    assert_eq!(participant_account.participant().deposit, PRICE / 2);
+    assert_eq!(wallet.lamports, WALLET_LAMPORTS - PRICE / 2);
+
+    env.join_tier(&mut TestAccount::wallet(), 0, 2 * PRICE)
+        .unwrap();
+    assert_eq!(env.event.event().total_deposits, PRICE / 2 + 2 * PRICE);
+}
+
+#[test]
+fn flexible_deposits_are_accepted_on_the_waitlist() {
+    let mut env = Env::with(1, flexible_pricing);
+    let mut leaving = TestAccount::wallet();
+    let mut leaving_account = env.join_tier(&mut leaving, 0, PRICE / 2).unwrap();
+    let mut waitlist = env.waitlist();
+
+    let mut waiting = TestAccount::wallet();
+    assert_eq!(
+        env.join_waitlist(&mut waitlist, &mut waiting, 2 * PRICE + 1)
+            .err(),
+        custom(RentShareError::DepositAmountMismatch).err()
+    );
+    let mut waiting_account = env
+        .join_waitlist(&mut waitlist, &mut waiting, 2 * PRICE)
+        .unwrap();
+    assert_eq!(waiting.lamports, WALLET_LAMPORTS - 2 * PRICE);
+
+    env.withdraw(
+        &mut leaving_account,
+        &mut leaving,
+        &mut [&mut waitlist, &mut waiting_account],
+    )
+    .unwrap();
+    assert_eq!(waiting_account.participant().deposit, 2 * PRICE);
+    assert_eq!(env.event.event().total_deposits, 2 * PRICE);
+}
+
+/// Raise the price to twice then three times `PRICE` at 2000 and 3000
+fn price_schedule(instruction: &mut Instruction) {
+    if let Instruction::InitializeEvent { price_schedule, .. } = instruction {
+        *price_schedule = vec![
+            PriceStep {
+                starts_at: 2_000,
+                price: 2 * PRICE,
+            },
+            PriceStep {
+                starts_at: 3_000,
+                price: 3 * PRICE,
+            },
+        ];
+    }
+}
+
+#[test]
+fn price_schedules_are_validated() {
+    let unordered = |instruction: &mut Instruction| {
+        price_schedule(instruction);
+        if let Instruction::InitializeEvent { price_schedule, .. } = instruction {
+            price_schedule.swap(0, 1);
+        }
+    };
+    assert_eq!(
+        Env::try_with(2, unordered).err(),
+        custom(RentShareError::InvalidPriceSchedule).err()
+    );
+    assert_eq!(
+        Env::try_with(2, |instruction| {
+            price_schedule(instruction);
+            flexible_pricing(instruction);
+        })
+        .err(),
+        custom(RentShareError::InvalidPriceSchedule).err()
+    );
+    assert_eq!(
+        Env::try_with(3, |instruction| {
+            price_schedule(instruction);
+            tiers(instruction);
+        })
+        .err(),
+        custom(RentShareError::InvalidPriceSchedule).err()
+    );
+}
+
+#[test]
+fn joins_pay_the_price_in_effect() {
+    let mut env = Env::with(3, price_schedule);
+    env.join(&mut TestAccount::wallet());
+
+    set_now(2_000);
+    assert_eq!(
+        env.join_tier(&mut TestAccount::wallet(), 0, PRICE).err(),
+        custom(RentShareError::DepositAmountMismatch).err()
+    );
+    let participant_account = env
+        .join_tier(&mut TestAccount::wallet(), 0, 2 * PRICE)
+        .unwrap();
+    assert_eq!(participant_account.participant().deposit, 2 * PRICE);
+
+    set_now(3_000);
+    env.join_tier(&mut TestAccount::wallet(), 0, 3 * PRICE)
+        .unwrap();
+    assert_eq!(env.event.event().total_deposits, 6 * PRICE);
+}
+
+#[test]
+fn waitlisted_wallets_keep_the_price_they_queued_at() {
+    let mut env = Env::with(1, price_schedule);
+    let mut leaving = TestAccount::wallet();
+    let mut leaving_account = env.join(&mut leaving);
+    let mut waitlist = env.waitlist();
+
+    set_now(2_000);
+    let mut waiting = TestAccount::wallet();
+    assert_eq!(
+        env.join_waitlist(&mut waitlist, &mut waiting, PRICE).err(),
+        custom(RentShareError::DepositAmountMismatch).err()
+    );
+    let mut waiting_account = env
+        .join_waitlist(&mut waitlist, &mut waiting, 2 * PRICE)
+        .unwrap();
+
+    set_now(3_000);
+    env.withdraw(
+        &mut leaving_account,
+        &mut leaving,
+        &mut [&mut waitlist, &mut waiting_account],
+    )
+    .unwrap();
+    assert_eq!(waiting_account.participant().deposit, 2 * PRICE);
+    assert_eq!(env.event.event().total_deposits, 2 * PRICE);
+}
+
+/// Draw two seats among the entries taken until the registration deadline at 2000
+fn lottery(instruction: &mut Instruction) {
+    if let Instruction::InitializeEvent {
+        lottery,
+        registration_deadline,
+        ..
+    } = instruction
+    {
+        *lottery = true;
+        *registration_deadline = 2_000;
+    }
+}
+
+impl Env {
+    fn commit_draw(&mut self, slot_hashes: Option<&mut TestAccount>) -> ProgramResult {
+        let instruction = Instruction::CommitLotteryDraw {
+            event_id: EVENT_ID,
+            event_creator: self.creator.key,
+        };
+        let mut accounts = vec![&mut self.event];
+        accounts.extend(slot_hashes);
+        process(&instruction, &mut accounts)
+    }
+
+    fn draw(&mut self, slot_hashes: &mut TestAccount) -> ProgramResult {
+        let instruction = Instruction::DrawLottery {
+            event_id: EVENT_ID,
+            event_creator: self.creator.key,
+        };
+        process(&instruction, &mut [&mut self.event, slot_hashes])
+    }
+
+    fn resolve(
+        &mut self,
+        participant_account: &mut TestAccount,
+        wallet: &mut TestAccount,
+    ) -> ProgramResult {
+        let instruction = Instruction::ResolveLotteryEntry {
+            event_id: EVENT_ID,
+            event_creator: self.creator.key,
+        };
+        process(
+            &instruction,
+            &mut [&mut self.event, participant_account, wallet],
+        )
+    }
+}
+
+#[test]
+fn lottery_entries_are_seated_or_refunded_once_drawn() {
+    let mut env = Env::with(2, lottery);
+    let mut entries: Vec<(TestAccount, TestAccount)> = (0..3)
+        .map(|_| {
+            let mut wallet = TestAccount::wallet();
+            let participant_account = env.join(&mut wallet);
+            (participant_account, wallet)
+        })
+        .collect();
+    assert!(entries[0].0.participant().is_entered());
+    assert_eq!(env.event.event().lottery_pool, 3 * PRICE);
+    assert_eq!(env.event.event().participant_count, 0);
+    assert_eq!(
+        env.commit_draw(None),
+        custom(RentShareError::InvalidEventStatus)
+    );
+
+    set_now(2_001);
+    set_slot(100);
+    env.commit_draw(None).unwrap();
+    let draw_slot = env.event.event().lottery_draw_slot;
+    assert_eq!(draw_slot, 116);
+
+    set_slot(draw_slot);
+    let mut slot_hashes = TestAccount::slot_hashes(&[(draw_slot - 1, [1; 32])]);
+    assert_eq!(
+        env.draw(&mut slot_hashes),
+        custom(RentShareError::LotteryPending)
+    );
+
+    // The draw slot was skipped, so the next slot produced seeds the draw
+    set_slot(draw_slot + 3);
+    let mut slot_hashes = TestAccount::slot_hashes(&[
+        (draw_slot + 2, [3; 32]),
+        (draw_slot + 1, [2; 32]),
+        (draw_slot - 1, [1; 32]),
+    ]);
+    env.draw(&mut slot_hashes).unwrap();
+    let event = env.event.event();
+    assert!(event.lottery_drawn);
+    assert_eq!(event.lottery_seed, lottery::seed(&[2; 32], &env.event.key));
+    assert_eq!(env.start(), custom(RentShareError::LotteryPending));
+
+    for (participant_account, wallet) in &mut entries {
+        let won = env
+            .event
+            .event()
+            .is_lottery_winner(participant_account.participant().lottery_entry);
+        let rent = participant_account.lamports;
+        env.resolve(participant_account, wallet).unwrap();
+        if won {
+            assert!(participant_account.participant().is_joined());
+            assert_eq!(wallet.lamports, WALLET_LAMPORTS - PRICE);
+        } else {
+            assert_eq!(participant_account.lamports, 0);
+            assert_eq!(wallet.lamports, WALLET_LAMPORTS + rent);
+        }
+    }
+
+    let event = env.event.event();
+    assert_eq!(event.participant_count, 2);
+    assert_eq!(event.total_deposits, 2 * PRICE);
+    assert_eq!(event.lottery_pool, 0);
+    env.start().unwrap();
+}
+
+#[test]
+fn lottery_draws_are_only_committed_again_once_expired() {
+    let mut env = Env::with(2, lottery);
+    let mut wallet = TestAccount::wallet();
+    let mut participant_account = env.join(&mut wallet);
+
+    set_now(2_001);
+    env.commit_draw(None).unwrap();
+    let draw_slot = env.event.event().lottery_draw_slot;
+    assert_eq!(
+        env.commit_draw(None),
+        Err(ProgramError::NotEnoughAccountKeys)
+    );
+
+    // Neither a draw slot not reached yet nor a skipped one can be replaced
+    let mut slot_hashes = TestAccount::slot_hashes(&[(draw_slot - 1, [1; 32])]);
+    assert_eq!(
+        env.commit_draw(Some(&mut slot_hashes)),
+        custom(RentShareError::LotteryPending)
+    );
+    let mut slot_hashes =
+        TestAccount::slot_hashes(&[(draw_slot + 1, [2; 32]), (draw_slot - 1, [1; 32])]);
+    assert_eq!(
+        env.commit_draw(Some(&mut slot_hashes)),
+        custom(RentShareError::LotteryPending)
+    );
+
+    // Once the sysvar is full of later slots the hash of the first one is gone
+    let newest = draw_slot + lottery::MAX_ENTRIES as u64;
+    let later: Vec<(u64, [u8; 32])> = (0..lottery::MAX_ENTRIES as u64)
+        .map(|age| (newest - age, [2; 32]))
+        .collect();
+    let mut slot_hashes = TestAccount::slot_hashes(&later);
+    set_slot(newest + 1);
+    assert_eq!(
+        env.draw(&mut slot_hashes),
+        custom(RentShareError::LotteryDrawExpired)
+    );
+    assert_eq!(
+        env.resolve(&mut participant_account, &mut wallet),
+        custom(RentShareError::LotteryPending)
+    );
+
+    env.commit_draw(Some(&mut slot_hashes)).unwrap();
+    assert_eq!(
+        env.event.event().lottery_draw_slot,
+        newest + 1 + lottery::DRAW_DELAY_SLOTS
+    );
+}
+
+const CHECKIN_CODE: &[u8] = b"door-code";
+
+impl Env {
+    /// Open check-in for the code until 1100
+    fn open_check_in(&mut self) -> ProgramResult {
+        let instruction = Instruction::OpenCheckIn {
+            event_id: EVENT_ID,
+            event_creator: self.creator.key,
+            code_commitment: checkin::commitment(&self.event.key, CHECKIN_CODE),
+            checkin_window: 100,
+        };
+        self.creator_instruction(instruction)
+    }
+
+    fn check_in(
+        &mut self,
+        participant_account: &mut TestAccount,
+        wallet: &mut TestAccount,
+        checkin_commitment: [u8; 32],
+    ) -> ProgramResult {
+        let instruction = Instruction::CheckIn {
+            event_id: EVENT_ID,
+            event_creator: self.creator.key,
+            checkin_commitment,
+        };
+        self.participant_instruction(instruction, participant_account, wallet)
+    }
+
+    fn reveal_check_in(
+        &mut self,
+        participant_account: &mut TestAccount,
+        wallet: &mut TestAccount,
+    ) -> ProgramResult {
+        let instruction = Instruction::RevealCheckIn {
+            event_id: EVENT_ID,
+            event_creator: self.creator.key,
+            code: CHECKIN_CODE.to_vec(),
+        };
+        self.participant_instruction(instruction, participant_account, wallet)
+    }
+}
+
+#[test]
+fn participants_commit_to_the_check_in_code_and_reveal_it_once_closed() {
+    let mut env = Env::new(3);
+    let mut wallet = TestAccount::wallet();
+    let mut participant_account = env.join(&mut wallet);
+    let mut absent = TestAccount::wallet();
+    let mut absent_account = env.join(&mut absent);
+    let mut late = TestAccount::wallet();
+    let mut late_account = env.join(&mut late);
+    assert_eq!(
+        env.open_check_in(),
+        custom(RentShareError::InvalidEventStatus)
+    );
+
+    env.start().unwrap();
+    env.open_check_in().unwrap();
+    let commitment = checkin::participant_commitment(CHECKIN_CODE, &wallet.key);
+    env.check_in(&mut participant_account, &mut wallet, commitment)
+        .unwrap();
+    assert_eq!(
+        env.check_in(&mut participant_account, &mut wallet, commitment),
+        custom(RentShareError::AlreadyCheckedIn)
+    );
+    // Copying the commitment of another wallet doesn't check in
+    env.check_in(&mut absent_account, &mut absent, commitment)
+        .unwrap();
+    assert_eq!(
+        env.reveal_check_in(&mut participant_account, &mut wallet),
+        custom(RentShareError::CheckInOpen)
+    );
+
+    set_now(1_101);
+    let late_commitment = checkin::participant_commitment(CHECKIN_CODE, &late.key);
+    assert_eq!(
+        env.check_in(&mut late_account, &mut late, late_commitment),
+        custom(RentShareError::CheckInClosed)
+    );
+
+    env.reveal_check_in(&mut participant_account, &mut wallet)
+        .unwrap();
+    assert!(participant_account.participant().attended);
+    assert_eq!(
+        env.reveal_check_in(&mut participant_account, &mut wallet),
+        custom(RentShareError::AlreadyCheckedIn)
+    );
+    assert_eq!(
+        env.reveal_check_in(&mut absent_account, &mut absent),
+        custom(RentShareError::InvalidCheckInCode)
+    );
+    assert!(!absent_account.participant().attended);
+    assert_eq!(env.event.event().attendance, 1);
+}
@@ -0,0 +1,134 @@
+use std::fs;
+
+use borsh::BorshSerialize;
+use rentshare::{
+    error::RentShareError,
+    idl,
+    instruction::Instruction,
+    state::{EventSeries, InitEvent, Participant, Sponsorship, Vesting, Waitlist},
+};
+use serde_json::Value;
+use solana_program::pubkey::Pubkey;
+
+const IDL_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/idl/rentshare.json");
+
+fn find<'a>(idl: &'a Value, section: &str, name: &str) -> &'a Value {
+    idl[section]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["name"] == name)
+        .unwrap_or_else(|| panic!("{} missing from IDL {}", name, section))
+}
+
+#[test]
+fn idl_file_is_up_to_date() {
+    let generated = idl::to_json();
+    if std::env::var_os("UPDATE_IDL").is_some() {
+        fs::write(IDL_PATH, &generated).unwrap();
+        return;
+    }
+
+    let checked_in = fs::read_to_string(IDL_PATH).unwrap_or_default();
+    assert!(
+        checked_in == generated,
+        "idl/rentshare.json is out of date, rerun `cargo test --test idl` with UPDATE_IDL=1"
+    );
+}
+
+#[test]
+fn account_sizes_match_state() {
+    let idl = idl::generate();
+    assert_eq!(find(&idl, "accounts", "InitEvent")["size"], InitEvent::LEN);
+    assert_eq!(
+        find(&idl, "accounts", "Participant")["size"],
+        Participant::LEN
+    );
+    assert_eq!(find(&idl, "accounts", "Waitlist")["size"], Waitlist::LEN);
+    assert_eq!(
+        find(&idl, "accounts", "EventSeries")["size"],
+        EventSeries::LEN
+    );
+    assert_eq!(
+        find(&idl, "accounts", "Sponsorship")["size"],
+        Sponsorship::LEN
+    );
+}
+
+#[test]
+fn instruction_discriminants_match_borsh() {
+    let idl = idl::generate();
+    let instructions = [
+        (
+            "InitializeEvent",
+            Instruction::InitializeEvent {
+                payee_pubkey: Pubkey::default(),
+                event_creator: Pubkey::default(),
+                event_id: 0,
+                fix_deposit_amount_per_person: 0,
+                total_partcipator: 0,
+                tiers: Vec::new(),
+                allowlist_root: [0; 32],
+                permit_signer: Pubkey::default(),
+                start_time: 0,
+                refund_schedule: Vec::new(),
+                bond: 0,
+                arbiter: Pubkey::default(),
+                dispute_window: 0,
+                confirmation_quorum_bps: 0,
+                confirmation_window: 0,
+                payees: Vec::new(),
+                vesting: Vesting::None,
+                vesting_duration: 0,
+                prize_table: Vec::new(),
+                results_window: 0,
+                funding_goal: 0,
+                registration_deadline: 0,
+                min_deposit: 0,
+                max_deposit: 0,
+                price_schedule: Vec::new(),
+                lottery: false,
+            },
+        ),
+        (
+            "EndEvent",
+            Instruction::EndEvent {
+                event_id: 0,
+                event_creator: Pubkey::default(),
+            },
+        ),
+        (
+            "FreezeTicket",
+            Instruction::FreezeTicket {
+                event_id: 0,
+                event_creator: Pubkey::default(),
+            },
+        ),
+    ];
+
+    for (name, instruction) in instructions {
+        let tag = instruction.try_to_vec().unwrap()[0];
+        assert_eq!(
+            find(&idl, "instructions", name)["discriminant"],
+            tag,
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn error_codes_match_program_errors() {
+    let idl = idl::generate();
+    for (name, error) in [
+        (
+            "RentAlreadyPaidInFull",
+            RentShareError::RentAlreadyPaidInFull,
+        ),
+        ("EventFull", RentShareError::EventFull),
+        ("TicketAlreadyIssued", RentShareError::TicketAlreadyIssued),
+    ] {
+        assert_eq!(find(&idl, "errors", name)["code"], error as u32);
+        assert_eq!(find(&idl, "errors", name)["msg"], error.to_string());
+    }
+}